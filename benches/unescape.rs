@@ -0,0 +1,44 @@
+//! A small benchmark for unescaping, run with `cargo bench`.
+//!
+//! This doesn't use a benchmarking framework, to keep the crate free of dependencies,
+//! so the numbers are only rough.
+
+// Benchmarks aren't held to the MSRV, as `black_box` is too useful here
+#![allow(clippy::incompatible_msrv)]
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+use descape::UnescapeExt;
+
+fn bench(name: &str, input: &str) {
+    const ITERATIONS: u32 = 200;
+
+    // Warm up
+    for _ in 0 .. ITERATIONS / 10 {
+        black_box(black_box(input).to_unescaped().ok());
+    }
+    let mut best = Duration::from_secs(u64::MAX);
+    for _ in 0 .. ITERATIONS {
+        let start = Instant::now();
+        black_box(black_box(input).to_unescaped().ok());
+        best = best.min(start.elapsed());
+    }
+    #[allow(clippy::cast_precision_loss)]
+    let throughput = input.len() as f64 / best.as_secs_f64() / 1_000_000.0;
+    println!("{:<24} {:>10.2?} {:>10.1} MB/s", name, best, throughput);
+}
+
+fn main() {
+    let ascii = "The quick brown fox jumps over the lazy dog. ".repeat(20_000);
+    let unicode = "Příliš žluťoučký kůň úpěl ďábelské ódy. ❤️ ".repeat(20_000);
+    let sparse = "The quick brown fox jumps over the lazy dog.\\n".repeat(20_000);
+    let dense = r"\x41\t\u{1F600}\n\\".repeat(20_000);
+    // Short runs of text between escapes, where copying each run in one go matters most
+    let runs = r#"key\tvalue\n\"quoted\"\\path"#.repeat(20_000);
+
+    bench("ascii, no escapes", &ascii);
+    bench("unicode, no escapes", &unicode);
+    bench("ascii, sparse escapes", &sparse);
+    bench("dense escapes", &dense);
+    bench("short runs", &runs);
+}
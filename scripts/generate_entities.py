@@ -0,0 +1,26 @@
+#!/usr/bin/env python3
+"""Generates src/handlers/xml/entities.rs from the HTML5 named character references bundled with Python."""
+
+import sys
+from html.entities import html5
+
+
+def literal(value):
+    return "".join(c if c.isascii() and c.isprintable() and c not in "\"\\" else "\\u{%X}" % ord(c) for c in value)
+
+
+def main():
+    # Legacy entities without a semicolon are left out, since every sequence has to end in a semicolon
+    entities = sorted((name[:-1], value) for name, value in html5.items() if name.endswith(";"))
+
+    out = sys.stdout
+    out.write("// Generated by scripts/generate_entities.py. Don't edit this by hand.\n\n")
+    out.write("/// Every named character reference in HTML, sorted so it can be binary searched.\n")
+    out.write("pub(super) static ENTITIES: &[(&str, &str)] = &[\n")
+    for name, value in entities:
+        out.write("    (\"%s\", \"%s\"),\n" % (name, literal(value)))
+    out.write("];\n")
+
+
+if __name__ == "__main__":
+    main()
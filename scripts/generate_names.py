@@ -0,0 +1,62 @@
+#!/usr/bin/env python3
+"""Generates src/names/table.rs from the Unicode database bundled with Python.
+
+Names with a codepoint suffix, like `CJK UNIFIED IDEOGRAPH-4E00`, are left out,
+as they're parsed from the name instead.
+"""
+
+import sys
+import unicodedata
+
+DERIVED = (
+    "CJK UNIFIED IDEOGRAPH-",
+    "CJK COMPATIBILITY IDEOGRAPH-",
+    "TANGUT IDEOGRAPH-",
+    "KHITAN SMALL SCRIPT CHARACTER-",
+    "NUSHU CHARACTER-",
+)
+
+# Ranges that `unicodedata` doesn't give names for, even though Unicode derives them.
+# CPython leaves out Tangut, so these are the ranges from Unicode 14.
+HARDCODED = {
+    "TANGUT IDEOGRAPH-": [[0x17000, 0x187F7], [0x18D00, 0x18D08]],
+}
+
+
+def main():
+    names = []
+    ranges = {prefix: list(HARDCODED.get(prefix, [])) for prefix in DERIVED}
+    for codepoint in range(0x110000):
+        name = unicodedata.name(chr(codepoint), None)
+        if name is None:
+            continue
+        prefix = next((prefix for prefix in DERIVED if name.startswith(prefix)), None)
+        if prefix is None:
+            names.append((name, codepoint))
+            continue
+        if prefix in HARDCODED:
+            continue
+        spans = ranges[prefix]
+        if spans and spans[-1][1] + 1 == codepoint:
+            spans[-1][1] = codepoint
+        else:
+            spans.append([codepoint, codepoint])
+    names.sort()
+
+    out = sys.stdout
+    out.write("// Generated by scripts/generate_names.py from Unicode %s. Don't edit this by hand.\n\n" % unicodedata.unidata_version)
+    out.write("/// Every prefix of a name that ends in the character's codepoint, with the ranges of codepoints it covers.\n")
+    out.write("pub(super) static DERIVED: &[(&str, &[(u32, u32)])] = &[\n")
+    for prefix in DERIVED:
+        spans = ", ".join("(0x%X, 0x%X)" % (start, end) for start, end in ranges[prefix])
+        out.write("    (\"%s\", &[%s]),\n" % (prefix, spans))
+    out.write("];\n\n")
+    out.write("/// Every other name, sorted so it can be binary searched.\n")
+    out.write("pub(super) static NAMES: &[(&str, u32)] = &[\n")
+    for name, codepoint in names:
+        out.write("    (\"%s\", 0x%X),\n" % (name, codepoint))
+    out.write("];\n")
+
+
+if __name__ == "__main__":
+    main()
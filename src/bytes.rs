@@ -0,0 +1,340 @@
+//! Unescaping byte strings, where numeric escapes produce raw bytes instead of characters.
+
+use alloc::{
+    borrow::Cow,
+    vec::Vec
+};
+
+use crate::{DefaultHandler, InvalidEscape, EscapeErrorKind, parse_digits, to_char, combine_surrogates};
+
+/// An iterator over the bytes of a byte string, and their indices.
+///
+/// This is the byte string equivalent of [`core::str::CharIndices`].
+#[derive(Debug, Clone)]
+pub struct ByteIndices<'source> {
+    bytes: &'source [u8],
+    offset: usize
+}
+
+impl<'source> ByteIndices<'source> {
+    /// Creates an iterator over the given bytes, starting at index `0`.
+    #[must_use]
+    pub const fn new(bytes: &'source [u8]) -> Self {
+        Self { bytes, offset: 0 }
+    }
+
+    /// Views the underlying data as a subslice of the original data.
+    #[must_use]
+    pub const fn as_slice(&self) -> &'source [u8] {
+        self.bytes
+    }
+
+    /// Returns the index of the next byte, or the length of the underlying data if there are none left.
+    #[must_use]
+    pub const fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+impl Iterator for ByteIndices<'_> {
+    type Item = (usize, u8);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&byte, rest) = self.bytes.split_first()?;
+        let index = self.offset;
+        self.bytes = rest;
+        self.offset += 1;
+        Some((index, byte))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.bytes.len(), Some(self.bytes.len()))
+    }
+}
+
+/// A trait distinguishing an object as a handler for custom escape sequences in byte strings.
+///
+/// For convenience, this trait is **automatically implemented** for all implementors of `FnMut` with the correct signature.
+///
+pub trait ByteEscapeHandler {
+    /// Definition of a custom byte escape handler.
+    ///
+    /// Custom escape handlers are called before parsing any escape sequences,
+    /// and are given 4 arguments:
+    /// - `idx`: The index of the current byte (e.g. `Hello\nthere` gets `5`)
+    /// - `byte`: The current byte in the string (e.g. `\\n` gets `b'n'`)
+    /// - `iter`: A mutable reference to the underlying byte iterator -
+    ///   use this to get the rest of the string via [`ByteIndices::as_slice`],
+    ///   or get the next bytes
+    /// - `out`: The output buffer to push the bytes the sequence stands for onto
+    ///
+    /// Returning `Ok(())` replaces the sequence with whatever was pushed onto `out`,
+    /// which may be nothing at all,
+    /// and returning `Err` errors the unescaping at the index of the escape sequence.
+    /// The error spans everything the handler consumed from `iter` before returning.
+    ///
+    /// # Examples
+    ///
+    /// ## Expanding `\t` into four spaces
+    /// ```rust
+    /// # use descape::{UnescapeBytesExt, ByteEscapeHandler, ByteIndices};
+    /// fn spaces(idx: usize, byte: u8, iter: &mut ByteIndices, out: &mut Vec<u8>) -> Result<(), ()> {
+    ///     match byte {
+    ///         b't' => Ok(out.extend_from_slice(b"    ")),
+    ///         _ => descape::DefaultHandler.escape_byte(idx, byte, iter, out)
+    ///     }
+    /// }
+    ///
+    /// let unescaped = br"\tindented\xFF".to_unescaped_bytes_with(spaces).expect("this is fine");
+    /// assert_eq!(&*unescaped, b"    indented\xFF");
+    /// ```
+    #[allow(clippy::result_unit_err, clippy::missing_errors_doc)]
+    fn escape_byte(&mut self, idx: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), ()>;
+
+    /// Like [`ByteEscapeHandler::escape_byte`], but reports why an escape sequence is invalid.
+    ///
+    /// This is what's called while unescaping.
+    /// The default implementation forwards to [`ByteEscapeHandler::escape_byte`],
+    /// reporting any error as [`EscapeErrorKind::Rejected`].
+    #[allow(clippy::missing_errors_doc)]
+    fn escape_byte_detailed(&mut self, idx: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), EscapeErrorKind> {
+        self.escape_byte(idx, byte, iter, out).map_err(|()| EscapeErrorKind::Rejected)
+    }
+}
+
+impl<F> ByteEscapeHandler for F
+    where F: for<'iter, 'source, 'out> FnMut(usize, u8, &'iter mut ByteIndices<'source>, &'out mut Vec<u8>) -> Result<(), ()>
+{
+    fn escape_byte(&mut self, idx: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), ()> {
+        self(idx, byte, iter, out)
+    }
+}
+
+/// An extension trait for [`&[u8]`](slice) to allow parsing escape sequences in byte strings, only copying when needed.
+pub trait UnescapeBytesExt: crate::sealed::Sealed {
+    /**
+    Unescapes a byte string, returning an [`alloc::borrow::Cow`].
+    Will only allocate if the string has any escape sequences.
+
+    Uses [`crate::DefaultHandler`], which, unlike with [`crate::UnescapeExt::to_unescaped`],
+    treats `\xNN` and `\ooo` as raw bytes instead of codepoints.
+    `\u{HEX}`, `\uXXXX`, and `\UXXXXXXXX` still expand to the UTF-8 encoding of their codepoint.
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid byte, and why it was invalid.
+
+    # Examples
+    ## Parsing raw bytes
+    ```rust
+    # use descape::UnescapeBytesExt;
+    let escaped = br"\xFF\377\u{FF}".to_unescaped_bytes();
+    assert_eq!(
+        &*escaped.unwrap(),
+        b"\xFF\xFF\xC3\xBF"
+    );
+    ```
+
+    ## Erroring for octal escapes that don't fit in a byte
+    ```rust
+    # use descape::UnescapeBytesExt;
+    let invalid_escape = br"Uh oh! \777".to_unescaped_bytes();
+    assert_eq!(
+        invalid_escape.unwrap_err().index,
+        7
+    );
+    ```
+     */
+    fn to_unescaped_bytes(&self) -> Result<Cow<'_, [u8]>, InvalidEscape>;
+    /**
+    Unescapes a byte string using a custom escape handler. See the documentation of [`crate::ByteEscapeHandler`] for more details.
+
+    # Errors
+
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid byte, and why it was invalid.
+
+    */
+    fn to_unescaped_bytes_with(
+        &self,
+        callback: impl ByteEscapeHandler
+    ) -> Result<Cow<'_, [u8]>, InvalidEscape>;
+    /**
+    Unescapes a byte string using a custom escape handler, with escape sequences starting with `escape` instead of `\`.
+
+    The handler is called with the byte after `escape`, exactly like in [`UnescapeBytesExt::to_unescaped_bytes_with`].
+
+    # Errors
+
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid byte, and why it was invalid.
+
+    # Examples
+    ```rust
+    # use descape::{UnescapeBytesExt, PercentHandler};
+    let unescaped = b"caf%C3%A9%20au%20lait".to_unescaped_bytes_with_escape_byte(b'%', PercentHandler);
+    assert_eq!(&*unescaped.unwrap(), "café au lait".as_bytes());
+    ```
+    */
+    fn to_unescaped_bytes_with_escape_byte(
+        &self,
+        escape: u8,
+        callback: impl ByteEscapeHandler
+    ) -> Result<Cow<'_, [u8]>, InvalidEscape>;
+}
+
+impl UnescapeBytesExt for [u8] {
+    #[inline]
+    fn to_unescaped_bytes(&self) -> Result<Cow<'_, [u8]>, InvalidEscape> {
+        self.to_unescaped_bytes_with(DefaultHandler)
+    }
+
+    // Put this outside to prevent monomorphization bloat
+    fn to_unescaped_bytes_with(
+        &self,
+        callback: impl ByteEscapeHandler
+    ) -> Result<Cow<'_, [u8]>, InvalidEscape> {
+        self.to_unescaped_bytes_with_escape_byte(b'\\', callback)
+    }
+
+    fn to_unescaped_bytes_with_escape_byte(
+        &self,
+        escape: u8,
+        mut callback: impl ByteEscapeHandler
+    ) -> Result<Cow<'_, [u8]>, InvalidEscape> {
+        to_unescaped_bytes_with_mono(self, escape, &mut callback)
+    }
+}
+
+fn to_unescaped_bytes_with_mono<'this>(
+    this: &'this [u8],
+    escape: u8,
+    callback: &mut dyn ByteEscapeHandler
+) -> Result<Cow<'this, [u8]>, InvalidEscape> {
+    let mut iter = ByteIndices::new(this);
+    let mut owned = None::<Vec<u8>>;
+
+    while let Some((index, byte)) = iter.next() {
+        if byte != escape {
+            if let Some(owned) = &mut owned {
+                owned.push(byte);
+            }
+            continue;
+        }
+        let owned = owned.get_or_insert_with(|| {
+            let mut bytes = Vec::with_capacity(this.len());
+            bytes.extend_from_slice(&this[..index]);
+            bytes
+        });
+        if let Some((_, byte)) = iter.next() {
+            callback.escape_byte_detailed(index, byte, &mut iter, owned)
+                // Whatever the handler consumed is part of the invalid sequence
+                .map_err(|kind| InvalidEscape::with_kind(index, kind).with_len(iter.offset() - index))?;
+        } else {
+            // No matches found
+            return Err(InvalidEscape::with_kind(index, EscapeErrorKind::Truncated).with_len(1));
+        }
+    }
+
+    match owned {
+        Some(bytes) => Ok(Cow::Owned(bytes)),
+        None => Ok(Cow::Borrowed(this)),
+    }
+}
+
+impl ByteEscapeHandler for DefaultHandler {
+    fn escape_byte(&mut self, idx: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), ()> {
+        self.escape_byte_detailed(idx, byte, iter, out).map_err(|_| ())
+    }
+
+    fn escape_byte_detailed(&mut self, _: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), EscapeErrorKind> {
+        if let Some(chr) = crate::unescape_single(char::from(byte)) {
+            // All of the single character escapes are ASCII
+            #[allow(clippy::cast_possible_truncation)]
+            out.push(chr as u8);
+            return Ok(());
+        }
+        match byte {
+            b'u' => {
+                // Skip the needed amount of bytes, even on failure, so the error spans the sequence
+                let (res, skip) = unescape_unicode_bytes(iter.as_slice());
+                skip_bytes(iter, skip);
+                out.extend_from_slice(res?.encode_utf8(&mut [0; 4]).as_bytes());
+            },
+            b'U' => {
+                let rest = iter.as_slice();
+                let res = rest.get(..8).ok_or(EscapeErrorKind::Truncated)
+                    .and_then(|num| parse_digits(num, 16).ok_or(EscapeErrorKind::InvalidHexDigit))
+                    .and_then(to_char);
+                skip_bytes(iter, 8);
+                out.extend_from_slice(res?.encode_utf8(&mut [0; 4]).as_bytes());
+            },
+            b'x' => {
+                let rest = iter.as_slice();
+                let res = rest.get(..2).ok_or(EscapeErrorKind::Truncated)
+                    .and_then(|num| parse_digits(num, 16).ok_or(EscapeErrorKind::InvalidHexDigit));
+                skip_bytes(iter, 2);
+                #[allow(clippy::cast_possible_truncation)] // Two hex digits always fit
+                out.push(res? as u8);
+            },
+            b'0' ..= b'7' => {
+                // Could be \o, \oo, or \ooo
+                let rest = iter.as_slice();
+                let end = rest.iter()
+                    .take(2)
+                    .take_while(|b| (b'0' ..= b'7').contains(b))
+                    .count();
+                let mut num = [byte; 3];
+                num[1 ..= end].copy_from_slice(&rest[..end]);
+                let res = parse_digits(&num[..= end], 8).unwrap_or(0);
+                skip_bytes(iter, end);
+                if res > 0o377 {
+                    return Err(EscapeErrorKind::OutOfRange(res));
+                }
+                #[allow(clippy::cast_possible_truncation)] // Checked above
+                out.push(res as u8);
+            },
+            _ => return Err(EscapeErrorKind::UnknownEscape(char::from(byte)))
+        }
+        Ok(())
+    }
+}
+
+/// Advances the iterator by up to `bytes` bytes.
+fn skip_bytes(iter: &mut ByteIndices<'_>, bytes: usize) {
+    for _ in 0..bytes { iter.next(); }
+}
+
+/// Parses the part of a Unicode escape after the `u`,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn unescape_unicode_bytes(rest: &[u8]) -> (Result<char, EscapeErrorKind>, usize) {
+    if rest.first() == Some(&b'{') {
+        // \u{HEX}
+        let end = match rest.iter().position(|&b| b == b'}') {
+            Some(end) => end,
+            None => return (Err(EscapeErrorKind::Truncated), rest.len())
+        };
+        let res = parse_digits(&rest[1 .. end], 16)
+            .ok_or(EscapeErrorKind::InvalidHexDigit)
+            .and_then(to_char);
+        (res, end + 1)
+    } else {
+        // \uNNNN
+        let res = rest.get(..4).ok_or(EscapeErrorKind::Truncated)
+            .and_then(|num| parse_digits(num, 16).ok_or(EscapeErrorKind::InvalidHexDigit));
+        match res {
+            Ok(high @ 0xD800 ..= 0xDBFF) => {
+                // This might be the start of a UTF-16 surrogate pair, like in JSON
+                let low = rest.get(4 .. 10)
+                    .filter(|pair| pair.starts_with(b"\\u"))
+                    .and_then(|pair| parse_digits(&pair[2 ..], 16))
+                    .filter(|low| (0xDC00 ..= 0xDFFF).contains(low));
+                match low {
+                    Some(low) => (to_char(combine_surrogates(high, low)), 10),
+                    None => (Err(EscapeErrorKind::LoneSurrogate(high)), 4)
+                }
+            },
+            res => (res.and_then(to_char), 4)
+        }
+    }
+}
@@ -0,0 +1,568 @@
+//! The inverse of unescaping: turning raw strings back into escaped ones.
+
+use alloc::{
+    borrow::Cow,
+    string::String
+};
+use core::fmt::Write;
+
+/// A trait distinguishing an object as a policy for escaping characters.
+///
+/// For convenience, this trait is **automatically implemented** for all implementors of `FnMut` with the correct signature.
+///
+pub trait Escaper {
+    /// Definition of a custom escaping policy.
+    ///
+    /// Escapers are called once for every character in the string, and are given 2 arguments:
+    /// - `chr`: The current character in the string
+    /// - `out`: The output string to push the escaped representation of the character onto
+    ///
+    /// Returning `false` means the character should be written verbatim,
+    /// and returning `true` means the escaper has already pushed its replacement onto `out`.
+    /// Returning `true` without pushing anything removes the character entirely.
+    ///
+    /// Anything pushed onto `out` before returning `false` is kept, followed by the character itself.
+    ///
+    /// # Examples
+    ///
+    /// ## Escaping single quotes for a shell
+    /// ```rust
+    /// # use descape::EscapeExt;
+    /// fn quote(chr: char, out: &mut String) -> bool {
+    ///     if chr == '\'' {
+    ///         out.push_str(r"'\''");
+    ///         return true;
+    ///     }
+    ///     false
+    /// }
+    ///
+    /// let escaped = "it's".to_escaped_with(quote);
+    /// assert_eq!(escaped, r"it'\''s");
+    /// ```
+    ///
+    /// ## Removing characters entirely
+    /// ```rust
+    /// # use descape::EscapeExt;
+    /// fn no_vowels(chr: char, _: &mut String) -> bool {
+    ///     "aeiou".contains(chr)
+    /// }
+    ///
+    /// let escaped = "Hello there".to_escaped_with(no_vowels);
+    /// assert_eq!(escaped, "Hll thr");
+    /// ```
+    fn escape_char(&mut self, chr: char, out: &mut String) -> bool;
+}
+
+impl<F> Escaper for F
+    where F: FnMut(char, &mut String) -> bool
+{
+    fn escape_char(&mut self, chr: char, out: &mut String) -> bool {
+        self(chr, out)
+    }
+}
+
+/// The default escaping policy.
+///
+/// This is [`ShortestEscaper::new`], so the following characters are escaped:
+/// - `\\` -> `\\\\`
+/// - `"` -> `\\"`
+/// - `\x07` -> `\\a`
+/// - `\x08` -> `\\b`
+/// - `\t` -> `\\t`
+/// - `\n` -> `\\n`
+/// - `\x0B` -> `\\v`
+/// - `\x0C` -> `\\f`
+/// - `\r` -> `\\r`
+/// - `\x1B` -> `\\e`
+/// - Any other control character -> `\\xNN`
+///
+/// All other characters, including printable non-ASCII ones, are left untouched.
+/// The output can always be turned back into the original string with [`crate::DefaultHandler`].
+///
+pub struct DefaultEscaper;
+
+impl Escaper for DefaultEscaper {
+    #[inline]
+    fn escape_char(&mut self, chr: char, out: &mut String) -> bool {
+        ShortestEscaper::new().escape_char(chr, out)
+    }
+}
+
+/// An escaping policy that writes every character in the shortest way [`crate::DefaultHandler`] can read back,
+/// with a choice of which quotes to escape.
+///
+/// Characters with a mnemonic, like `\\n` or `\\e`, are written with it,
+/// and any other control character is written as `\\xNN`.
+/// Quotes are only escaped if they're set to be, which by default is only `"`.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{EscapeExt, UnescapeExt, ShortestEscaper};
+/// let raw = "\"it's\"\x1B`\x07`";
+/// assert_eq!(raw.to_escaped_with(ShortestEscaper::new()), r#"\"it's\"\e`\a`"#);
+///
+/// let escaper = ShortestEscaper::new().double_quotes(false).single_quotes(true).backticks(true);
+/// let escaped = raw.to_escaped_with(escaper);
+/// assert_eq!(escaped, r#""it\'s"\e\`\a\`"#);
+/// assert_eq!(escaped.to_unescaped().unwrap(), raw);
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ShortestEscaper {
+    double_quotes: bool,
+    single_quotes: bool,
+    backticks: bool,
+}
+
+impl Default for ShortestEscaper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ShortestEscaper {
+    /// Creates an escaper that behaves exactly like [`DefaultEscaper`], only escaping `"` out of the quotes.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { double_quotes: true, single_quotes: false, backticks: false }
+    }
+
+    /// Sets whether `"` is escaped as `\\"`.
+    ///
+    /// Defaults to `true`.
+    #[must_use]
+    pub const fn double_quotes(self, escape: bool) -> Self {
+        Self { double_quotes: escape, ..self }
+    }
+
+    /// Sets whether `'` is escaped as `\\'`.
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn single_quotes(self, escape: bool) -> Self {
+        Self { single_quotes: escape, ..self }
+    }
+
+    /// Sets whether `` ` `` is escaped as ``\\` ``.
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn backticks(self, escape: bool) -> Self {
+        Self { backticks: escape, ..self }
+    }
+}
+
+impl Escaper for ShortestEscaper {
+    fn escape_char(&mut self, chr: char, out: &mut String) -> bool {
+        let escaped = match chr {
+            '\\' => "\\\\",
+            '"' if self.double_quotes => "\\\"",
+            '\'' if self.single_quotes => "\\'",
+            '`' if self.backticks => "\\`",
+            '\x07' => "\\a",
+            '\x08' => "\\b",
+            '\t' => "\\t",
+            '\n' => "\\n",
+            '\x0B' => "\\v",
+            '\x0C' => "\\f",
+            '\r' => "\\r",
+            '\x1B' => "\\e",
+            // Control characters all fit in a byte, and `\xNN` is shorter than `\u{NN}`
+            c if c.is_control() => {
+                // Writing to a string can't fail
+                let _ = write!(out, "\\x{:02X}", c as u32);
+                return true;
+            }
+            _ => return false
+        };
+        out.push_str(escaped);
+        true
+    }
+}
+
+/// An escaping policy that gives exactly the same output as [`char::escape_default`].
+///
+/// The following characters are escaped:
+/// - `\\` -> `\\\\`
+/// - `'` -> `\\'`
+/// - `"` -> `\\"`
+/// - `\n` -> `\\n`
+/// - `\t` -> `\\t`
+/// - `\r` -> `\\r`
+/// - Any other character outside of printable ASCII -> `\\u{hex}`, in lowercase
+///
+/// The output is always valid in a Rust string literal,
+/// and can be turned back into the original string with [`crate::RustHandler`].
+///
+pub struct RustEscaper;
+
+impl Escaper for RustEscaper {
+    fn escape_char(&mut self, chr: char, out: &mut String) -> bool {
+        if (' ' ..= '~').contains(&chr) && !matches!(chr, '\\' | '\'' | '"') {
+            return false;
+        }
+        out.extend(chr.escape_default());
+        true
+    }
+}
+
+/// An escaping policy for the inside of a JSON string, the inverse of [`crate::JsonHandler`].
+///
+/// The following characters are escaped:
+/// - `"` -> `\\"`
+/// - `\\` -> `\\\\`
+/// - `\x08` -> `\\b`
+/// - `\x0C` -> `\\f`
+/// - `\n` -> `\\n`
+/// - `\r` -> `\\r`
+/// - `\t` -> `\\t`
+/// - Any other ASCII control character -> `\\u00XX`
+///
+/// With [`JsonEscaper::ascii_only`], every non-ASCII character is escaped as `\\uXXXX` too,
+/// using a surrogate pair for characters outside of the Basic Multilingual Plane.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{EscapeExt, JsonEscaper};
+/// let escaped = "\"caf\u{E9}\" \u{1F600}".to_escaped_with(JsonEscaper::new().ascii_only(true));
+/// assert_eq!(escaped, r#"\"caf\u00e9\" \ud83d\ude00"#);
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct JsonEscaper {
+    ascii_only: bool,
+}
+
+impl JsonEscaper {
+    /// Creates an escaper that leaves non-ASCII characters as they are.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { ascii_only: false }
+    }
+
+    /// Sets whether every non-ASCII character is escaped, for output that's only ASCII.
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn ascii_only(self, ascii_only: bool) -> Self {
+        Self { ascii_only }
+    }
+}
+
+impl Escaper for JsonEscaper {
+    fn escape_char(&mut self, chr: char, out: &mut String) -> bool {
+        let escaped = match chr {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\x08' => "\\b",
+            '\x0C' => "\\f",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            c if c < ' ' || (self.ascii_only && !c.is_ascii()) => {
+                for unit in c.encode_utf16(&mut [0; 2]) {
+                    // Writing to a string can't fail
+                    let _ = write!(out, "\\u{:04x}", unit);
+                }
+                return true;
+            }
+            _ => return false
+        };
+        out.push_str(escaped);
+        true
+    }
+}
+
+/// An escaping policy for output that's only ASCII, which [`crate::DefaultHandler`] can always read back.
+///
+/// ASCII characters are escaped exactly like [`ShortestEscaper::new`] does,
+/// and every non-ASCII character is written as `\\u{HEX}`, in uppercase.
+/// Characters are escaped one by one, so a combining character is escaped separately from the one it combines with.
+///
+/// With [`AsciiEscaper::surrogate_pairs`], non-ASCII characters are written as `\\uXXXX` instead,
+/// using a surrogate pair for characters outside of the Basic Multilingual Plane.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{EscapeExt, UnescapeExt, AsciiEscaper};
+/// let raw = "caf\u{E9}\t\u{1F600}";
+/// let escaped = raw.to_escaped_with(AsciiEscaper::new());
+/// assert_eq!(escaped, r"caf\u{E9}\t\u{1F600}");
+/// assert_eq!(escaped.to_unescaped().unwrap(), raw);
+///
+/// let escaped = raw.to_escaped_with(AsciiEscaper::new().surrogate_pairs(true));
+/// assert_eq!(escaped, r"caf\u00E9\t\uD83D\uDE00");
+/// assert_eq!(escaped.to_unescaped().unwrap(), raw);
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct AsciiEscaper {
+    surrogate_pairs: bool,
+}
+
+impl AsciiEscaper {
+    /// Creates an escaper that writes non-ASCII characters as `\\u{HEX}`.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { surrogate_pairs: false }
+    }
+
+    /// Sets whether non-ASCII characters are written as UTF-16 code units, like `\\uXXXX`, instead of `\\u{HEX}`.
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn surrogate_pairs(self, surrogate_pairs: bool) -> Self {
+        Self { surrogate_pairs }
+    }
+}
+
+impl Escaper for AsciiEscaper {
+    fn escape_char(&mut self, chr: char, out: &mut String) -> bool {
+        if chr.is_ascii() {
+            return ShortestEscaper::new().escape_char(chr, out);
+        }
+        // Writing to a string can't fail
+        if self.surrogate_pairs {
+            for unit in chr.encode_utf16(&mut [0; 2]) {
+                let _ = write!(out, "\\u{:04X}", unit);
+            }
+        } else {
+            let _ = write!(out, "\\u{{{:X}}}", chr as u32);
+        }
+        true
+    }
+}
+
+/// An extension trait for [`&str`](str) to allow escaping special characters in strings, only copying when needed.
+pub trait EscapeExt: crate::sealed::Sealed {
+    /**
+    Escapes a string, returning an [`alloc::borrow::Cow`].
+    Will only allocate if the string has any characters that need escaping.
+
+    Uses [`crate::DefaultEscaper`].
+
+    # Examples
+    ## Escaping a string
+    ```rust
+    # use descape::{EscapeExt, UnescapeExt};
+    let raw = "Hello,\n\"world\"!";
+    let escaped = raw.to_escaped();
+    assert_eq!(escaped, r#"Hello,\n\"world\"!"#);
+    assert_eq!(escaped.to_unescaped().unwrap(), raw);
+    ```
+
+    ## Not allocating for a string without special characters
+    ```rust
+    # use std::borrow::Cow; use descape::EscapeExt;
+    assert_eq!(
+        "Nothing to see here! ❤️".to_escaped(),
+        Cow::Borrowed("Nothing to see here! ❤️")
+    );
+    ```
+     */
+    fn to_escaped(&self) -> Cow<'_, str>;
+    /**
+    Escapes a string exactly like [`str::escape_default`], returning an [`alloc::borrow::Cow`].
+    Will only allocate if the string has any characters that need escaping.
+
+    Uses [`crate::RustEscaper`].
+    This isn't named `escape_default`, as the method on `str` itself would always be called instead.
+
+    # Examples
+    ```rust
+    # use std::borrow::Cow; use descape::{EscapeExt, UnescapeExt, RustHandler};
+    let raw = "it's\tcafé";
+    let escaped = raw.to_escaped_default();
+    assert_eq!(escaped, raw.escape_default().to_string());
+    assert_eq!(escaped, r"it\'s\tcaf\u{e9}");
+    assert_eq!(escaped.to_unescaped_with(RustHandler).unwrap(), raw);
+
+    assert_eq!("plain ASCII".to_escaped_default(), Cow::Borrowed("plain ASCII"));
+    ```
+     */
+    fn to_escaped_default(&self) -> Cow<'_, str>;
+    /**
+    Quotes a string for use as a single argument in a POSIX shell, returning an [`alloc::borrow::Cow`].
+    Will only allocate if the string needs quoting.
+
+    A string is left as-is if it's made of only ASCII letters, digits, `_`, `-`, `.`, and `/`.
+    Anything else is wrapped in single quotes, where everything is taken literally,
+    except for single quotes themselves, which are written as `'\''`.
+
+    # Examples
+    ```rust
+    # use std::borrow::Cow; use descape::EscapeExt;
+    assert_eq!("./some-file_1.txt".to_shell_escaped(), Cow::Borrowed("./some-file_1.txt"));
+    assert_eq!("two words".to_shell_escaped(), "'two words'");
+    assert_eq!("it's $HOME".to_shell_escaped(), r"'it'\''s $HOME'");
+    assert_eq!("".to_shell_escaped(), "''");
+    ```
+     */
+    fn to_shell_escaped(&self) -> Cow<'_, str>;
+    /**
+    Escapes a string for use inside of a JSON string, returning an [`alloc::borrow::Cow`].
+    Will only allocate if the string has any characters that need escaping.
+
+    Uses [`crate::JsonEscaper`], leaving non-ASCII characters as they are.
+    The surrounding quotes aren't added.
+
+    # Examples
+    ```rust
+    # use std::borrow::Cow; use descape::{EscapeExt, UnescapeExt, JsonHandler};
+    let raw = "say \"hi\"\n\u{1F600}";
+    let escaped = raw.to_json_escaped();
+    assert_eq!(escaped, "say \\\"hi\\\"\\n\u{1F600}");
+    assert_eq!(escaped.to_unescaped_with(JsonHandler).unwrap(), raw);
+
+    assert_eq!("caf\u{E9}".to_json_escaped(), Cow::Borrowed("caf\u{E9}"));
+    ```
+     */
+    fn to_json_escaped(&self) -> Cow<'_, str>;
+    /**
+    Escapes a string so that it's only printable ASCII, returning an [`alloc::borrow::Cow`].
+    Will only allocate if the string has any characters that need escaping.
+
+    Uses [`crate::AsciiEscaper`], writing non-ASCII characters as `\\u{HEX}`.
+    The output can always be turned back into the original string with [`crate::DefaultHandler`].
+
+    # Examples
+    ```rust
+    # use std::borrow::Cow; use descape::{EscapeExt, UnescapeExt};
+    let raw = "\"na\u{EF}ve\"\n";
+    let escaped = raw.to_escaped_ascii();
+    assert_eq!(escaped, r#"\"na\u{EF}ve\"\n"#);
+    assert_eq!(escaped.to_unescaped().unwrap(), raw);
+
+    assert_eq!("plain ASCII".to_escaped_ascii(), Cow::Borrowed("plain ASCII"));
+    ```
+     */
+    fn to_escaped_ascii(&self) -> Cow<'_, str>;
+    /**
+    Escapes a string using a custom escaping policy. See the documentation of [`crate::Escaper`] for more details.
+    Will only allocate if the escaper escapes any characters.
+     */
+    fn to_escaped_with(
+        &self,
+        escaper: impl Escaper
+    ) -> Cow<'_, str>;
+    /**
+    Escapes a string using a custom escaping policy, like [`EscapeExt::to_escaped_with`],
+    giving up if the escaped string would be longer than `max_len` bytes.
+
+    This is checked after every character, so the output never grows much past the limit before it's caught.
+    Returns `None` if the limit was exceeded.
+
+    # Examples
+    ```rust
+    # use descape::{EscapeExt, DefaultEscaper};
+    assert_eq!("\n\n".to_escaped_with_limit(DefaultEscaper, 4).unwrap(), r"\n\n");
+    assert_eq!("\n\n\n".to_escaped_with_limit(DefaultEscaper, 4), None);
+    ```
+     */
+    fn to_escaped_with_limit(
+        &self,
+        escaper: impl Escaper,
+        max_len: usize
+    ) -> Option<Cow<'_, str>>;
+}
+
+impl EscapeExt for str {
+    #[inline]
+    fn to_escaped(&self) -> Cow<'_, str> {
+        self.to_escaped_with(DefaultEscaper)
+    }
+
+    #[inline]
+    fn to_escaped_default(&self) -> Cow<'_, str> {
+        self.to_escaped_with(RustEscaper)
+    }
+
+    #[inline]
+    fn to_json_escaped(&self) -> Cow<'_, str> {
+        self.to_escaped_with(JsonEscaper::new())
+    }
+
+    #[inline]
+    fn to_escaped_ascii(&self) -> Cow<'_, str> {
+        self.to_escaped_with(AsciiEscaper::new())
+    }
+
+    fn to_shell_escaped(&self) -> Cow<'_, str> {
+        let safe = |chr: char| chr.is_ascii_alphanumeric() || matches!(chr, '_' | '-' | '.' | '/');
+        if !self.is_empty() && self.chars().all(safe) {
+            return Cow::Borrowed(self);
+        }
+        let mut quoted = String::with_capacity(self.len() + 2);
+        quoted.push('\'');
+        for chr in self.chars() {
+            if chr == '\'' {
+                // Close the quotes, add an escaped quote, and open them again
+                quoted.push_str(r"'\''");
+            } else {
+                quoted.push(chr);
+            }
+        }
+        quoted.push('\'');
+        Cow::Owned(quoted)
+    }
+
+    // Put this outside to prevent monomorphization bloat
+    fn to_escaped_with(
+        &self,
+        mut escaper: impl Escaper
+    ) -> Cow<'_, str> {
+        // This can't fail, as no string can be longer than `usize::MAX`
+        to_escaped_with_mono(self, &mut escaper, usize::MAX).unwrap_or(Cow::Borrowed(self))
+    }
+
+    fn to_escaped_with_limit(
+        &self,
+        mut escaper: impl Escaper,
+        max_len: usize
+    ) -> Option<Cow<'_, str>> {
+        to_escaped_with_mono(self, &mut escaper, max_len)
+    }
+}
+
+fn to_escaped_with_mono<'this>(
+    this: &'this str,
+    escaper: &mut dyn Escaper,
+    max_len: usize
+) -> Option<Cow<'this, str>> {
+    let mut owned = None::<String>;
+    // Escapers only ever push onto an empty string while we're still borrowing,
+    // and an empty string doesn't allocate
+    let mut scratch = String::new();
+
+    for (index, chr) in this.char_indices() {
+        if let Some(owned) = &mut owned {
+            if !escaper.escape_char(chr, owned) {
+                owned.push(chr);
+            }
+            if owned.len() > max_len {
+                return None;
+            }
+            continue;
+        }
+        let handled = escaper.escape_char(chr, &mut scratch);
+        if !handled && scratch.is_empty() {
+            continue;
+        }
+        let mut string = String::with_capacity(this.len() + scratch.len());
+        string.push_str(&this[..index]);
+        string.push_str(&scratch);
+        if !handled {
+            string.push(chr);
+        }
+        if string.len() > max_len {
+            return None;
+        }
+        owned = Some(string);
+    }
+
+    match owned {
+        Some(string) => Some(Cow::Owned(string)),
+        None if this.len() > max_len => None,
+        None => Some(Cow::Borrowed(this)),
+    }
+}
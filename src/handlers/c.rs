@@ -0,0 +1,92 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, skip_bytes, consume, unescape_unicode_long, parse_fixed};
+
+/// An escape handler following the rules of C11 string literals.
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - `\\?` -> `?`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the octal value, modulo 256
+/// - `\\xH...` -> the value of every hexadecimal digit that follows, modulo 256
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\UXXXXXXXX` -> `\u{XXXXXXXX}`
+///
+/// Octal and hexadecimal escapes wrap around instead of erroring, like a C `char` would,
+/// and produce the character with the resulting value.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, CHandler};
+/// let unescaped = r"what\?\x1g \400".to_unescaped_with(CHandler);
+/// assert_eq!(unescaped.unwrap(), "what?\x01g \0");
+/// # }
+/// ```
+///
+pub struct CHandler;
+
+impl EscapeHandler for CHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            '?' => '?',
+            'x' => {
+                let rest = iter.as_str();
+                let len = rest.bytes().take_while(u8::is_ascii_hexdigit).count();
+                if len == 0 {
+                    return Err(if rest.is_empty() { EscapeErrorKind::Truncated } else { EscapeErrorKind::InvalidHexDigit });
+                }
+                skip_bytes(iter, len);
+                char::from(wrapping_parse(&rest.as_bytes()[.. len], 16))
+            },
+            'u' => {
+                consume(iter, |rest| parse_fixed(rest, 4))?
+            },
+            'U' => {
+                consume(iter, unescape_unicode_long)?
+            },
+            c if c.is_digit(8) => {
+                let rest = iter.as_str();
+                let len = rest.bytes().take(2).take_while(|byte| (b'0' ..= b'7').contains(byte)).count();
+                skip_bytes(iter, len);
+                let mut digits = [b'0'; 3];
+                digits[0] = c as u8;
+                digits[1 ..= len].copy_from_slice(&rest.as_bytes()[.. len]);
+                char::from(wrapping_parse(&digits[..= len], 8))
+            },
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// Parses ASCII digits in the given radix, wrapping around on overflow like a C `unsigned char`.
+#[allow(clippy::cast_possible_truncation)] // Digits are always less than the radix
+fn wrapping_parse(digits: &[u8], radix: u8) -> u8 {
+    digits.iter().fold(0u8, |acc, &digit| {
+        let digit = char::from(digit).to_digit(u32::from(radix)).unwrap_or(0) as u8;
+        acc.wrapping_mul(radix).wrapping_add(digit)
+    })
+}
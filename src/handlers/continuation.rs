@@ -0,0 +1,34 @@
+use core::str::CharIndices;
+
+use crate::{DefaultHandler, EscapeErrorKind, MultiEscapeHandler, Replacement};
+
+/// An escape handler that adds line continuations on top of another handler.
+///
+/// A backslash immediately followed by a newline (`\n` or `\r\n`) is removed along with the newline,
+/// like in C, shell scripts, and TOML multiline strings.
+/// Every other escape sequence is passed on to the wrapped handler.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, ContinuationHandler, DefaultHandler};
+/// let unescaped = "one \\\ntwo \\\r\nthree\\n".to_unescaped_with(ContinuationHandler(DefaultHandler));
+/// assert_eq!(unescaped.unwrap(), "one two three\n");
+/// ```
+///
+pub struct ContinuationHandler<H = DefaultHandler>(pub H);
+
+impl<H> MultiEscapeHandler for ContinuationHandler<H>
+    where H: MultiEscapeHandler
+{
+    fn escape_multi<'source>(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'source>) -> Result<Option<Replacement<'source>>, EscapeErrorKind> {
+        match chr {
+            '\n' => Ok(None),
+            // Only swallow the carriage return if it's part of a CRLF pair
+            '\r' if iter.as_str().starts_with('\n') => {
+                iter.next();
+                Ok(None)
+            },
+            _ => self.0.escape_multi(idx, chr, iter)
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, scan_number, to_char, unescape_utf16, unescape_unicode_long};
+
+/// An escape handler following the rules of C# regular string literals, as defined in
+/// [the C# language specification](https://learn.microsoft.com/en-us/dotnet/csharp/language-reference/language-specification/lexical-structure#6456-string-literals).
+///
+/// The following escapes are valid:
+/// - `\\0` -> `\0`
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\e` -> `\x1B`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - `\\xH`, `\\xHH`, `\\xHHH`, and `\\xHHHH` -> `\u{HHHH}`
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\UXXXXXXXX` -> `\u{XXXXXXXX}`
+///
+/// Unlike in most other formats, `\\x` takes as many hexadecimal digits as it can, up to four,
+/// so `\\x41` is `A`, but `\\x41BC` is `\u{41BC}`.
+///
+/// A `\\uXXXX` high surrogate immediately followed by a `\\uXXXX` low surrogate
+/// is combined into the character the pair encodes. Lone surrogates are invalid, as they can't be in a Rust string.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, CSharpHandler};
+/// let unescaped = r"\x41\x042gC\U0001F600".to_unescaped_with(CSharpHandler);
+/// assert_eq!(unescaped.unwrap(), "ABgC\u{1F600}");
+///
+/// r"\x".to_unescaped_with(CSharpHandler).expect_err("\\x needs at least one digit");
+/// # }
+/// ```
+///
+pub struct CSharpHandler;
+
+impl EscapeHandler for CSharpHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            '0' => '\0',
+            'a' => '\x07',
+            'b' => '\x08',
+            'e' => '\x1B',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            'x' => match iter.as_str().chars().next() {
+                // One to four digits
+                Some(first) if first.is_ascii_hexdigit() => {
+                    iter.next();
+                    to_char(consume(iter, |rest| scan_number(first, rest, 16, 4)))?
+                },
+                Some(_) => return Err(EscapeErrorKind::InvalidHexDigit),
+                None => return Err(EscapeErrorKind::Truncated)
+            },
+            'u' => consume(iter, unescape_utf16)?,
+            'U' => consume(iter, unescape_unicode_long)?,
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
@@ -0,0 +1,91 @@
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind};
+#[cfg(feature = "alloc")]
+use crate::InvalidEscape;
+
+/// An escape handler following the rules of quoted CSV fields from RFC 4180, where a quote is escaped by doubling it.
+///
+/// The only valid escape is `""` -> `"`.
+///
+/// Like [`crate::SqlAnsiHandler`], this relies on the escape character being `"`,
+/// through [`crate::UnescapeExt::to_unescaped_with_escape_char`],
+/// and is given the contents of the field without the surrounding quotes.
+/// To find where a field ends in a whole line, use [`CsvHandler::unescape_field`] instead.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, CsvHandler};
+/// let unescaped = r#"she said ""hi"" \n"#.to_unescaped_with_escape_char('"', CsvHandler);
+/// assert_eq!(unescaped.unwrap(), r#"she said "hi" \n"#);
+/// # }
+/// ```
+///
+pub struct CsvHandler;
+
+impl CsvHandler {
+    /**
+    Unescapes a quoted field at the start of `line`, which starts right after the opening quote,
+    returning the unescaped contents along with the rest of the line after the closing quote.
+
+    A doubled quote is part of the field, and the first quote that isn't doubled ends it.
+    Will only allocate if the field has any doubled quotes.
+
+    # Errors
+    Errors with [`EscapeErrorKind::Unterminated`] if the line ends before the closing quote,
+    with the index being the length of the line.
+
+    # Examples
+    ```rust
+    # use descape::CsvHandler;
+    let line = r#""she said ""hi""",2,3"#;
+    let (field, rest) = CsvHandler::unescape_field(&line[1 ..]).unwrap();
+    assert_eq!(field, r#"she said "hi""#);
+    assert_eq!(rest, ",2,3");
+    ```
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn unescape_field(line: &str) -> Result<(Cow<'_, str>, &str), InvalidEscape> {
+        let mut owned = None::<String>;
+        let mut start = 0;
+
+        loop {
+            let quote = match line[start ..].find('"') {
+                Some(offset) => start + offset,
+                None => return Err(InvalidEscape::with_kind(line.len(), EscapeErrorKind::Unterminated)),
+            };
+            let rest = &line[quote + 1 ..];
+            if !rest.starts_with('"') {
+                let contents = match owned {
+                    Some(mut string) => {
+                        string.push_str(&line[start .. quote]);
+                        Cow::Owned(string)
+                    },
+                    None => Cow::Borrowed(&line[.. quote])
+                };
+                return Ok((contents, rest));
+            }
+            // Keep one of the two quotes
+            let owned = owned.get_or_insert_with(|| String::with_capacity(line.len()));
+            owned.push_str(&line[start ..= quote]);
+            start = quote + 2;
+        }
+    }
+}
+
+impl EscapeHandler for CsvHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, _: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        match chr {
+            '"' => Ok(Some('"')),
+            c => Err(EscapeErrorKind::UnknownEscape(c))
+        }
+    }
+}
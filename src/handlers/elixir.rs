@@ -0,0 +1,74 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, parse_fixed, unescape_hex, unescape_braced};
+
+/// An escape handler following the rules of Elixir strings, as defined in
+/// [the Elixir documentation](https://hexdocs.pm/elixir/syntax-reference.html#strings).
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\d` -> `\x7F`
+/// - `\\e` -> `\x1B`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\s` -> `\x20`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\0` -> `\0`
+/// - `\\xHH` -> `\u{HH}`
+/// - `\\x{HEX}` -> `\u{HEX}`
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\u{HEX}` -> `\u{HEX}`
+///
+/// Surrogates aren't characters, so they're invalid in `\\uXXXX` escapes, even in pairs.
+/// A backslash followed by a newline is removed along with it, as a line continuation.
+///
+/// Any other escape is replaced with the character after the backslash, like Elixir does,
+/// so `\\\\`, `\\"`, and `\\#` all stand for themselves.
+///
+/// Elixir puts the raw byte in the string for `\\xHH` escapes,
+/// but a Rust string can only hold characters, so these produce the character with that value instead.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, ElixirHandler};
+/// let unescaped = r"\d\s\x41\x{1F600}é\#{x}".to_unescaped_with(ElixirHandler);
+/// assert_eq!(unescaped.unwrap(), "\x7F A\u{1F600}\u{E9}#{x}");
+/// # }
+/// ```
+///
+pub struct ElixirHandler;
+
+impl EscapeHandler for ElixirHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'a' => '\x07',
+            'b' => '\x08',
+            'd' => '\x7F',
+            'e' => '\x1B',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            's' => ' ',
+            't' => '\t',
+            'v' => '\x0B',
+            '0' => '\0',
+            'x' | 'u' if iter.as_str().starts_with('{') => consume(iter, unescape_braced)?,
+            'x' => consume(iter, unescape_hex)?,
+            'u' => consume(iter, |rest| parse_fixed(rest, 4))?,
+            '\n' => return Ok(None),
+            '\r' if iter.as_str().starts_with('\n') => {
+                iter.next();
+                return Ok(None);
+            },
+            c => c
+        } ))
+    }
+}
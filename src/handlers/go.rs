@@ -0,0 +1,89 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, parse_fixed, unescape_hex, unescape_unicode_long};
+
+/// An escape handler following the rules of Go string literals, as defined in
+/// [the Go specification](https://go.dev/ref/spec#Rune_literals).
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - `\\ooo` -> `\ooo`, for exactly three octal digits, up to `\\377`
+/// - `\\xNN` -> `\xNN`
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\UXXXXXXXX` -> `\u{XXXXXXXX}`
+///
+/// Unlike most other formats, octal escapes must have all three digits,
+/// erroring with [`EscapeErrorKind::InvalidOctalDigit`] or [`EscapeErrorKind::Truncated`] otherwise,
+/// and [`EscapeErrorKind::OutOfRange`] if they don't fit in a byte.
+///
+/// Go puts the raw byte in the string for octal and `\\xNN` escapes,
+/// but a Rust string can only hold characters, so these produce the character with that value instead.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, GoHandler};
+/// let unescaped = r"\a\101\x42C\U0001F600".to_unescaped_with(GoHandler);
+/// assert_eq!(unescaped.unwrap(), "\x07ABC\u{1F600}");
+///
+/// r"\7".to_unescaped_with(GoHandler).expect_err("Go needs all three octal digits");
+/// # }
+/// ```
+///
+pub struct GoHandler;
+
+impl EscapeHandler for GoHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            'x' => consume(iter, unescape_hex)?,
+            'u' => consume(iter, |rest| parse_fixed(rest, 4))?,
+            'U' => consume(iter, unescape_unicode_long)?,
+            c @ '0' ..= '7' => consume(iter, |rest| unescape_byte_oct(c, rest))?,
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// Parses the two octal digits after `first` at the start of the string, which must fit in a byte,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn unescape_byte_oct(first: char, rest: &str) -> (Result<char, EscapeErrorKind>, usize) {
+    let mut codepoint = u32::from(first) - u32::from('0');
+    let mut len = 0;
+    for chr in rest.chars().take(2) {
+        len += chr.len_utf8();
+        match chr.to_digit(8) {
+            Some(digit) => codepoint = codepoint * 8 + digit,
+            None => return (Err(EscapeErrorKind::InvalidOctalDigit), len)
+        }
+    }
+    if len < 2 {
+        return (Err(EscapeErrorKind::Truncated), len);
+    }
+    match char::from_u32(codepoint) {
+        Some(chr) if codepoint <= 0xFF => (Ok(chr), len),
+        _ => (Err(EscapeErrorKind::OutOfRange(codepoint)), len)
+    }
+}
@@ -0,0 +1,112 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, unescape_braced, unescape_utf16};
+#[cfg(feature = "alloc")]
+use crate::{EscapeWriter, TrailingPolicy};
+
+/// An escape handler following the rules of GraphQL strings, as defined in
+/// [the GraphQL specification](https://spec.graphql.org/October2021/#sec-String-Value).
+///
+/// The following escapes are valid:
+/// - `\\"` -> `"`
+/// - `\\\\` -> `\\`
+/// - `\\/` -> `/`
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\u{HEX}` -> `\u{HEX}`
+///
+/// A `\\uXXXX` high surrogate immediately followed by a `\\uXXXX` low surrogate
+/// is combined into the character the pair encodes. Lone surrogates are invalid.
+///
+/// This is for strings in quotes; use [`GraphQLBlockHandler`] for block strings in triple quotes.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, GraphQLHandler};
+/// let unescaped = r#"\"a\/b\" \u{1F600}\uD83D\uDE00"#.to_unescaped_with(GraphQLHandler);
+/// assert_eq!(unescaped.unwrap(), "\"a/b\" \u{1F600}\u{1F600}");
+///
+/// r"\x41".to_unescaped_with(GraphQLHandler).expect_err("GraphQL has no hex escapes");
+/// # }
+/// ```
+///
+pub struct GraphQLHandler;
+
+impl EscapeHandler for GraphQLHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' if iter.as_str().starts_with('{') => consume(iter, unescape_braced)?,
+            'u' => consume(iter, unescape_utf16)?,
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// An escape handler following the rules of GraphQL block strings, as defined in
+/// [the GraphQL specification](https://spec.graphql.org/October2021/#BlockStringCharacter).
+///
+/// The only valid escape is `\\"""` -> `"""`. Every other backslash is kept as it is, so this never errors,
+/// even for a backslash at the very end of the string.
+///
+/// The indentation and blank lines that GraphQL removes from block strings are left as they are.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, GraphQLBlockHandler};
+/// let unescaped = r#"say \"""hi\""" \n \"ok\""#.to_unescaped_with(GraphQLBlockHandler);
+/// assert_eq!(unescaped.unwrap(), r#"say """hi""" \n \"ok\""#);
+///
+/// assert_eq!(r"C:\".to_unescaped_with(GraphQLBlockHandler).unwrap(), r"C:\");
+/// ```
+///
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub struct GraphQLBlockHandler;
+
+#[cfg(feature = "alloc")]
+impl EscapeWriter for GraphQLBlockHandler {
+    fn write_escape(&mut self, _: usize, mut chr: char, iter: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind> {
+        loop {
+            if chr == '"' && iter.as_str().starts_with("\"\"") {
+                consume(iter, |_| ((), 2));
+                out.push_str("\"\"\"");
+                return Ok(());
+            }
+            out.push('\\');
+            if chr != '\\' {
+                out.push(chr);
+                return Ok(());
+            }
+            // The second backslash could start a `\"""` of its own
+            if let Some((_, next)) = iter.next() {
+                chr = next;
+            } else {
+                out.push('\\');
+                return Ok(());
+            }
+        }
+    }
+
+    fn trailing_backslash(&self) -> TrailingPolicy {
+        TrailingPolicy::Literal
+    }
+}
@@ -0,0 +1,133 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, to_char};
+
+/// The names of the ASCII control characters, for escapes like `\ESC`.
+///
+/// `SOH` comes before `SO`, so the longest name that matches is always found first.
+const MNEMONICS: [(&str, char); 34] = [
+    ("NUL", '\x00'), ("SOH", '\x01'), ("STX", '\x02'), ("ETX", '\x03'),
+    ("EOT", '\x04'), ("ENQ", '\x05'), ("ACK", '\x06'), ("BEL", '\x07'),
+    ("BS", '\x08'), ("HT", '\x09'), ("LF", '\x0A'), ("VT", '\x0B'),
+    ("FF", '\x0C'), ("CR", '\x0D'), ("SO", '\x0E'), ("SI", '\x0F'),
+    ("DLE", '\x10'), ("DC1", '\x11'), ("DC2", '\x12'), ("DC3", '\x13'),
+    ("DC4", '\x14'), ("NAK", '\x15'), ("SYN", '\x16'), ("ETB", '\x17'),
+    ("CAN", '\x18'), ("EM", '\x19'), ("SUB", '\x1A'), ("ESC", '\x1B'),
+    ("FS", '\x1C'), ("GS", '\x1D'), ("RS", '\x1E'), ("US", '\x1F'),
+    ("SP", ' '), ("DEL", '\x7F'),
+];
+
+/// An escape handler following the rules of Haskell string literals, as defined in
+/// [the Haskell 2010 report](https://www.haskell.org/onlinereport/haskell2010/haskellch2.html#x7-200002.6).
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\"` -> `"`
+/// - `\\'` -> `'`
+/// - `\\&` -> nothing, to separate an escape from what comes after it, like in `\\SO\\&H`
+/// - `\\^@` through `\\^_` -> `\0` through `\x1F`
+/// - `\\NUL`, `\\SOH`, and every other name of an ASCII control character, along with `\\SP` and `\\DEL`
+/// - `\\DDD` -> `\u{DDD}`, in decimal
+/// - `\\oOOO` -> `\u{OOO}`, in octal
+/// - `\\xHHH` -> `\u{HHH}`, in hexadecimal
+/// - A backslash, whitespace, and another backslash -> nothing, as a string gap
+///
+/// Numeric escapes take as many digits as there are, so `\\1234` is `\u{4D2}`;
+/// use `\\&` to end one early.
+/// Names also take as many characters as they can, so `\\SOH` is `\x01`, and not `\x0E` followed by `H`.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, HaskellHandler};
+/// let unescaped = r"\ESC[0m\1234\&5\SOH\SO\&H".to_unescaped_with(HaskellHandler);
+/// assert_eq!(unescaped.unwrap(), "\x1B[0m\u{4D2}5\x01\x0EH");
+///
+/// let gap = "one \\\n    \\two".to_unescaped_with(HaskellHandler);
+/// assert_eq!(gap.unwrap(), "one two");
+/// # }
+/// ```
+///
+pub struct HaskellHandler;
+
+impl EscapeHandler for HaskellHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            '&' => return Ok(None),
+            '^' => match iter.next() {
+                #[allow(clippy::cast_possible_truncation)] // These are all ASCII
+                Some((_, c @ '@' ..= '_')) => char::from({ c as u8 } - b'@'),
+                Some((_, c)) => return Err(EscapeErrorKind::UnknownEscape(c)),
+                None => return Err(EscapeErrorKind::Truncated)
+            },
+            'o' => consume(iter, |rest| unescape_number(rest, 8))?,
+            'x' => consume(iter, |rest| unescape_number(rest, 16))?,
+            c if c.is_ascii_digit() => {
+                // The first digit was already taken, so it's parsed along with the rest
+                consume(iter, |rest| continue_number(c.to_digit(10).unwrap_or(0), rest, 10))?
+            },
+            c if c.is_whitespace() => {
+                let rest = iter.as_str();
+                let gap = rest.len() - rest.trim_start().len();
+                consume(iter, |_| ((), gap));
+                return match iter.next() {
+                    Some((_, '\\')) => Ok(None),
+                    Some((_, c)) => Err(EscapeErrorKind::UnknownEscape(c)),
+                    None => Err(EscapeErrorKind::Truncated)
+                };
+            },
+            c => {
+                let rest = iter.as_str();
+                let (name, mnemonic) = MNEMONICS.iter()
+                    .find(|(name, _)| name.starts_with(c) && rest.starts_with(&name[1 ..]))
+                    .ok_or(EscapeErrorKind::UnknownEscape(c))?;
+                consume(iter, |_| ((), name.len() - 1));
+                *mnemonic
+            }
+        } ))
+    }
+}
+
+/// Parses a numeric escape after its prefix, which has at least one digit.
+fn unescape_number(rest: &str, radix: u32) -> (Result<char, EscapeErrorKind>, usize) {
+    match rest.chars().next().and_then(|chr| chr.to_digit(radix)) {
+        Some(first) => {
+            let (res, len) = continue_number(first, &rest[1 ..], radix);
+            (res, len + 1)
+        },
+        None if rest.is_empty() => (Err(EscapeErrorKind::Truncated), 0),
+        None if radix == 8 => (Err(EscapeErrorKind::InvalidOctalDigit), 0),
+        None => (Err(EscapeErrorKind::InvalidHexDigit), 0)
+    }
+}
+
+/// Parses the rest of a numeric escape after its first digit, taking every digit there is.
+fn continue_number(first: u32, rest: &str, radix: u32) -> (Result<char, EscapeErrorKind>, usize) {
+    let len = rest.bytes().take_while(|&byte| char::from(byte).is_digit(radix)).count();
+    // Anything too big to fit is too big to be a character anyways
+    let value = rest[.. len].chars()
+        .filter_map(|chr| chr.to_digit(radix))
+        .fold(first, |value, digit| value.checked_mul(radix).and_then(|value| value.checked_add(digit)).unwrap_or(u32::MAX));
+    (to_char(value), len)
+}
@@ -0,0 +1,83 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, DefaultHandler, OrElse};
+
+/// An escape handler that accepts any escape sequence, and replaces it with the character after the backslash.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, IdentityHandler};
+/// let unescaped = r"\H\e\l\l\o \n \\ \W\o\r\l\d".to_unescaped_with(IdentityHandler);
+/// assert_eq!(unescaped.unwrap(), r"Hello n \ World");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IdentityHandler;
+
+impl EscapeHandler for IdentityHandler {
+    fn escape(&mut self, _: usize, chr: char, _: &mut CharIndices) -> Result<Option<char>, ()> {
+        Ok(Some(chr))
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, _: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some(chr))
+    }
+}
+
+/// An escape handler that accepts any escape sequence, and removes both the backslash and the character after it.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, DropHandler};
+/// let unescaped = r"What if I want a \nnewline?".to_unescaped_with(DropHandler);
+/// assert_eq!(unescaped.unwrap(), "What if I want a newline?");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct DropHandler;
+
+impl EscapeHandler for DropHandler {
+    fn escape(&mut self, _: usize, _: char, _: &mut CharIndices) -> Result<Option<char>, ()> {
+        Ok(None)
+    }
+
+    fn escape_detailed(&mut self, _: usize, _: char, _: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(None)
+    }
+}
+
+/// An escape handler that tries another handler, falling back to [`DefaultHandler`] if it rejects the sequence.
+///
+/// This is the same as `handler.or_else(DefaultHandler)`, including rewinding the iterator,
+/// for when only a few extra escapes are needed on top of the default ones.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, MapOrDefault}; use std::str::CharIndices;
+/// let brackets = MapOrDefault(|_: usize, chr: char, _: &mut CharIndices| match chr {
+///     'l' => Ok(Some('<')),
+///     'g' => Ok(Some('>')),
+///     _ => Err(())
+/// });
+///
+/// assert_eq!(r"\lb\g\n".to_unescaped_with(brackets).unwrap(), "<b>\n");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct MapOrDefault<H>(pub H);
+
+impl<H: EscapeHandler> EscapeHandler for MapOrDefault<H> {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+        OrElse(self.0.by_ref(), DefaultHandler).escape_detailed(idx, chr, iter)
+    }
+}
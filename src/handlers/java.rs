@@ -0,0 +1,145 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, scan_number, unescape_utf16};
+
+/// An escape handler following the rules of Java string literals, as defined in
+/// [the Java Language Specification](https://docs.oracle.com/javase/specs/jls/se21/html/jls-3.html#jls-3.10.7).
+///
+/// The following escapes are valid:
+/// - `\\b` -> `\x08`
+/// - `\\s` -> `\x20`
+/// - `\\t` -> `\x09`
+/// - `\\n` -> `\x0A`
+/// - `\\f` -> `\x0C`
+/// - `\\r` -> `\x0D`
+/// - `\\"` -> `"`
+/// - `\\'` -> `'`
+/// - `\\\\` -> `\\`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the octal value, from `\\0` up to `\\377`
+/// - `\\uXXXX` -> `\u{XXXX}`, with any amount of `u`s
+/// - `\\$` -> `$`, only with [`JavaHandler::dollar`]
+///
+/// Unicode escapes must have exactly four hexadecimal digits, so there's no `\\u{HEX}`, and there are no `\\x` escapes.
+/// A `\\uXXXX` high surrogate immediately followed by a `\\uXXXX` low surrogate
+/// is combined into the character the pair encodes. Lone surrogates are invalid, as they can't be in a Rust string.
+///
+/// Octal escapes starting with `\\4` through `\\7` only take one more digit, so `\\400` is `\\40` followed by `0`.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, JavaHandler};
+/// let unescaped = r"A\uuu0042\103\s😀".to_unescaped_with(JavaHandler::new());
+/// assert_eq!(unescaped.unwrap(), "ABC \u{1F600}");
+///
+/// r"\u{41}".to_unescaped_with(JavaHandler::new()).expect_err("Java has no braced escapes");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct JavaHandler {
+    dollar: bool,
+}
+
+impl JavaHandler {
+    /// Creates a handler for Java string literals.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { dollar: false }
+    }
+
+    /// Sets whether `\$` is a valid escape for `$`, like in Kotlin and Groovy.
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn dollar(self, dollar: bool) -> Self {
+        Self { dollar }
+    }
+}
+
+impl EscapeHandler for JavaHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'b' => '\x08',
+            's' => ' ',
+            't' => '\t',
+            'n' => '\n',
+            'f' => '\x0C',
+            'r' => '\r',
+            '"' => '"',
+            '\'' => '\'',
+            '\\' => '\\',
+            '$' if self.dollar => '$',
+            'u' => {
+                // \uuuu0041 is the same as A
+                while iter.as_str().starts_with('u') {
+                    iter.next();
+                }
+                consume(iter, unescape_utf16)?
+            },
+            c @ '0' ..= '7' => {
+                // \4 through \7 only take one more digit, so nothing goes past \377
+                let max_digits = if c > '3' { 2 } else { 3 };
+                let codepoint = consume(iter, |rest| scan_number(c, rest, 8, max_digits));
+                char::from_u32(codepoint).unwrap_or(char::REPLACEMENT_CHARACTER)
+            },
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// An escape handler following the rules of Kotlin string literals, as defined in
+/// [the Kotlin documentation](https://kotlinlang.org/docs/characters.html).
+///
+/// The following escapes are valid:
+/// - `\\b` -> `\x08`
+/// - `\\t` -> `\x09`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\"` -> `"`
+/// - `\\'` -> `'`
+/// - `\\\\` -> `\\`
+/// - `\\$` -> `$`
+/// - `\\uXXXX` -> `\u{XXXX}`
+///
+/// Like in Java, Unicode escapes must have exactly four hexadecimal digits,
+/// and surrogate pairs are combined into the character they encode.
+/// Unlike Java, there are no octal escapes, and no `\\f` or `\\s`.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, KotlinHandler};
+/// let unescaped = r"\$price\t€".to_unescaped_with(KotlinHandler);
+/// assert_eq!(unescaped.unwrap(), "$price\t\u{20AC}");
+///
+/// r"\101".to_unescaped_with(KotlinHandler).expect_err("Kotlin has no octal escapes");
+/// # }
+/// ```
+///
+pub struct KotlinHandler;
+
+impl EscapeHandler for KotlinHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'b' => '\x08',
+            't' => '\t',
+            'n' => '\n',
+            'r' => '\r',
+            '"' => '"',
+            '\'' => '\'',
+            '\\' => '\\',
+            '$' => '$',
+            'u' => consume(iter, unescape_utf16)?,
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
@@ -0,0 +1,97 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, unescape_hex, unescape_braced, unescape_utf16, scan_number};
+
+/// An escape handler following the rules of JavaScript string literals, as defined in
+/// [ECMAScript 2024](https://tc39.es/ecma262/2024/#sec-literals-string-literals).
+///
+/// The following escapes are valid:
+/// - `\\b` -> `\x08`
+/// - `\\t` -> `\x09`
+/// - `\\n` -> `\x0A`
+/// - `\\v` -> `\x0B`
+/// - `\\f` -> `\x0C`
+/// - `\\r` -> `\x0D`
+/// - `\\0` -> `\0`, when not followed by a digit
+/// - `\\xNN` -> `\xNN`
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\u{HEX}` -> `\u{HEX}`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the octal value, up to `\\377`, outside of strict mode
+///
+/// A `\\uXXXX` high surrogate immediately followed by a `\\uXXXX` low surrogate
+/// is combined into the character the pair encodes. Lone surrogates are invalid, as they can't be in a Rust string.
+///
+/// A backslash before a line terminator is a line continuation, and is removed along with it.
+/// Any other character after a backslash stands for itself, so `\\q` becomes `q`.
+///
+/// In strict mode, enabled with [`JsHandler::strict`], legacy octal escapes are invalid,
+/// along with `\\8`, `\\9`, and `\\0` followed by a digit.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, JsHandler};
+/// let unescaped = r"\q\x41\u{1F600}😀\101".to_unescaped_with(JsHandler::new());
+/// assert_eq!(unescaped.unwrap(), "qA\u{1F600}\u{1F600}A");
+///
+/// r"\101".to_unescaped_with(JsHandler::new().strict(true)).expect_err("strict mode has no octal");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct JsHandler {
+    strict: bool,
+}
+
+impl JsHandler {
+    /// Creates a handler for string literals outside of strict mode.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { strict: false }
+    }
+
+    /// Sets whether this handler is for strict mode code, where legacy octal escapes are invalid.
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn strict(self, strict: bool) -> Self {
+        Self { strict }
+    }
+}
+
+impl EscapeHandler for JsHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        let next_is_digit = iter.as_str().starts_with(|chr: char| chr.is_ascii_digit());
+        Ok(Some( match chr {
+            'b' => '\x08',
+            't' => '\t',
+            'n' => '\n',
+            'v' => '\x0B',
+            'f' => '\x0C',
+            'r' => '\r',
+            '0' if !next_is_digit => '\0',
+            'x' => consume(iter, unescape_hex)?,
+            'u' if iter.as_str().starts_with('{') => consume(iter, unescape_braced)?,
+            'u' => consume(iter, unescape_utf16)?,
+            '\n' | '\u{2028}' | '\u{2029}' => return Ok(None),
+            '\r' => {
+                if iter.as_str().starts_with('\n') {
+                    iter.next();
+                }
+                return Ok(None);
+            },
+            c @ '0' ..= '9' if self.strict => return Err(EscapeErrorKind::UnknownEscape(c)),
+            c @ '0' ..= '7' => {
+                // \4 through \7 only take one more digit, so nothing goes past \377
+                let max_digits = if c > '3' { 2 } else { 3 };
+                let codepoint = consume(iter, |rest| scan_number(c, rest, 8, max_digits));
+                char::from_u32(codepoint).unwrap_or(char::REPLACEMENT_CHARACTER)
+            },
+            c => c
+        } ))
+    }
+}
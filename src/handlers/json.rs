@@ -0,0 +1,55 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, unescape_utf16};
+
+/// An escape handler following the rules of JSON strings, as defined in [RFC 8259](https://www.rfc-editor.org/rfc/rfc8259#section-7).
+///
+/// The following escapes are valid:
+/// - `\\"` -> `"`
+/// - `\\\\` -> `\\`
+/// - `\\/` -> `/`
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\uXXXX` -> `\u{XXXX}`
+///
+/// A `\\uXXXX` high surrogate immediately followed by a `\\uXXXX` low surrogate
+/// is combined into the character the pair encodes. Lone surrogates are invalid.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, JsonHandler};
+/// let unescaped = r#"\"a\/b\" 😀"#.to_unescaped_with(JsonHandler);
+/// assert_eq!(unescaped.unwrap(), "\"a/b\" \u{1F600}");
+///
+/// r"\x41".to_unescaped_with(JsonHandler).expect_err("JSON has no hex escapes");
+/// # }
+/// ```
+///
+pub struct JsonHandler;
+
+impl EscapeHandler for JsonHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            '"' => '"',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => {
+                consume(iter, unescape_utf16)?
+            },
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
@@ -0,0 +1,103 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, unescape_hex, unescape_braced, unescape_dec};
+
+/// An escape handler following the rules of Lua string literals, as defined in
+/// [the Lua 5.4 reference manual](https://www.lua.org/manual/5.4/manual.html#3.1).
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\"` -> `"`
+/// - `\\'` -> `'`
+/// - `\\d`, `\\dd`, and `\\ddd` -> the character with that **decimal** value, up to `\\255`
+/// - `\\xHH` -> `\u{HH}`
+/// - `\\u{HEX}` -> `\u{HEX}`
+/// - `\\z` -> nothing, skipping all whitespace after it, including newlines
+///
+/// A backslash followed by a newline is replaced with just the newline.
+/// Decimal escapes above `\\255` are [`EscapeErrorKind::OutOfRange`].
+///
+/// Lua puts the raw byte in the string for decimal and `\\xHH` escapes,
+/// but a Rust string can only hold characters, so these produce the character with that value instead.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, LuaHandler};
+/// let unescaped = "\\122\\x41\\u{1F600} and \\z\n     more".to_unescaped_with(LuaHandler);
+/// assert_eq!(unescaped.unwrap(), "zA\u{1F600} and more");
+///
+/// r"\256".to_unescaped_with(LuaHandler).expect_err("decimal escapes have to fit in a byte");
+/// # }
+/// ```
+///
+pub struct LuaHandler;
+
+impl EscapeHandler for LuaHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            '\\' => '\\',
+            '"' => '"',
+            '\'' => '\'',
+            'x' => consume(iter, unescape_hex)?,
+            'u' => consume(iter, unescape_braced_lua)?,
+            'z' => {
+                let rest = iter.as_str();
+                let skipped = rest.len() - rest.trim_start_matches(is_lua_space).len();
+                consume(iter, |_| ((), skipped));
+                return Ok(None);
+            },
+            newline @ '\n' | newline @ '\r' => {
+                // \r\n and \n\r both count as one newline
+                let other = if newline == '\n' { '\r' } else { '\n' };
+                if iter.as_str().starts_with(other) {
+                    iter.next();
+                }
+                '\n'
+            },
+            c if c.is_ascii_digit() => {
+                let value = consume(iter, |rest| unescape_dec(c, rest));
+                if value > 0xFF {
+                    return Err(EscapeErrorKind::OutOfRange(value));
+                }
+                #[allow(clippy::cast_possible_truncation)] // It was just checked to fit
+                { char::from(value as u8) }
+            },
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// Parses the part of a `\u{HEX}` escape after the `u`, where the braces can't be left out.
+fn unescape_braced_lua(string: &str) -> (Result<char, EscapeErrorKind>, usize) {
+    if string.starts_with('{') {
+        unescape_braced(string)
+    } else if string.is_empty() {
+        (Err(EscapeErrorKind::Truncated), 0)
+    } else {
+        (Err(EscapeErrorKind::InvalidHexDigit), 0)
+    }
+}
+
+/// Whether a character counts as whitespace in Lua, which unlike Rust includes `\x0B`.
+const fn is_lua_space(chr: char) -> bool {
+    matches!(chr, ' ' | '\t' | '\n' | '\x0B' | '\x0C' | '\r')
+}
@@ -0,0 +1,56 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind};
+
+/// An escape handler built from a table of single character escapes.
+///
+/// Each pair maps the character after the backslash to the character the sequence stands for.
+/// Any character that isn't in the table is rejected with [`EscapeErrorKind::UnknownEscape`],
+/// so this can be combined with another handler through [`EscapeHandler::or_else`].
+///
+/// The table is searched in order, so the first matching pair wins.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, EscapeHandler, MapHandler, DefaultHandler};
+/// static BRACKETS: MapHandler = MapHandler::new(&[('l', '<'), ('g', '>')]);
+///
+/// assert_eq!(r"\lb\g".to_unescaped_with(BRACKETS).unwrap(), "<b>");
+/// r"\n".to_unescaped_with(BRACKETS).expect_err("\\n isn't in the table");
+///
+/// assert_eq!(r"\lb\g\n".to_unescaped_with(BRACKETS.or_else(DefaultHandler)).unwrap(), "<b>\n");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MapHandler<'map> {
+    map: &'map [(char, char)],
+}
+
+impl<'map> MapHandler<'map> {
+    /// Creates a handler from a table of `(escape, replacement)` pairs.
+    #[must_use]
+    pub const fn new(map: &'map [(char, char)]) -> Self {
+        Self { map }
+    }
+
+    /// Gets the table this handler was created from.
+    #[must_use]
+    pub const fn map(&self) -> &'map [(char, char)] {
+        self.map
+    }
+}
+
+impl EscapeHandler for MapHandler<'_> {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, _: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        self.map.iter()
+            .find(|(escape, _)| *escape == chr)
+            .map(|&(_, replacement)| Some(replacement))
+            .ok_or(EscapeErrorKind::UnknownEscape(chr))
+    }
+}
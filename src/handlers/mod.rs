@@ -0,0 +1,76 @@
+//! Ready-made escape handlers for common formats.
+
+#[cfg(feature = "alloc")]
+mod continuation;
+#[cfg(feature = "alloc")]
+pub use continuation::ContinuationHandler;
+#[cfg(feature = "alloc")]
+mod resilient;
+#[cfg(feature = "alloc")]
+pub use resilient::ResilientHandler;
+mod or_else;
+pub use or_else::OrElse;
+mod map;
+pub use map::MapHandler;
+mod identity;
+pub use identity::{IdentityHandler, DropHandler, MapOrDefault};
+mod named;
+pub use named::NamedUnicodeHandler;
+mod json;
+pub use json::JsonHandler;
+mod graphql;
+pub use graphql::GraphQLHandler;
+#[cfg(feature = "alloc")]
+pub use graphql::GraphQLBlockHandler;
+mod java;
+pub use java::{JavaHandler, KotlinHandler};
+mod js;
+pub use js::JsHandler;
+mod rust;
+pub use rust::RustHandler;
+mod c;
+pub use c::CHandler;
+mod csharp;
+pub use csharp::CSharpHandler;
+mod go;
+pub use go::GoHandler;
+mod lua;
+pub use lua::LuaHandler;
+mod haskell;
+pub use haskell::HaskellHandler;
+mod ocaml;
+pub use ocaml::OCamlHandler;
+mod elixir;
+pub use elixir::ElixirHandler;
+mod shell;
+pub use shell::ShellHandler;
+mod powershell;
+pub use powershell::PowerShellHandler;
+#[cfg(feature = "alloc")]
+mod percent;
+#[cfg(feature = "alloc")]
+pub use percent::PercentHandler;
+#[cfg(feature = "alloc")]
+mod python;
+#[cfg(feature = "alloc")]
+pub use python::{PythonStrHandler, PythonBytesHandler};
+#[cfg(feature = "alloc")]
+mod ruby;
+#[cfg(feature = "alloc")]
+pub use ruby::RubyHandler;
+#[cfg(feature = "alloc")]
+mod php;
+#[cfg(feature = "alloc")]
+pub use php::PhpHandler;
+mod sql;
+pub use sql::{SqlAnsiHandler, PostgresEStringHandler};
+mod csv;
+pub use csv::CsvHandler;
+mod toml;
+pub use toml::TomlHandler;
+mod yaml;
+pub use yaml::YamlHandler;
+#[cfg(feature = "alloc")]
+mod xml;
+#[cfg(feature = "alloc")]
+pub use xml::XmlHandler;
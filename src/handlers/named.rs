@@ -0,0 +1,43 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, DefaultHandler};
+#[cfg(feature = "unicode-names")]
+use crate::consume;
+
+/// An escape handler that adds `\N{NAME}` escapes on top of another handler,
+/// which handles every other sequence.
+///
+/// Names are matched like Python does, ignoring ASCII case,
+/// and the `CJK UNIFIED IDEOGRAPH-XXXX` and `HANGUL SYLLABLE` forms are understood as well.
+/// An unknown name or a missing `}` is reported as [`EscapeErrorKind::UnknownName`].
+///
+/// The name table is only included with the `unicode-names` feature.
+/// Without it, `\\N` is rejected like any other unknown escape.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, NamedUnicodeHandler, DefaultHandler};
+/// # #[cfg(feature = "unicode-names")] {
+/// let unescaped = r"\N{GREEK SMALL LETTER ALPHA}\n".to_unescaped_with(NamedUnicodeHandler(DefaultHandler));
+/// assert_eq!(unescaped.unwrap(), "\u{3B1}\n");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct NamedUnicodeHandler<H = DefaultHandler>(pub H);
+
+impl<H: EscapeHandler> EscapeHandler for NamedUnicodeHandler<H> {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+        match chr {
+            #[cfg(feature = "unicode-names")]
+            'N' => consume(iter, crate::names::unescape_named).map(Some),
+            #[cfg(not(feature = "unicode-names"))]
+            'N' => Err(EscapeErrorKind::UnknownEscape('N')),
+            _ => self.0.escape_detailed(idx, chr, iter)
+        }
+    }
+}
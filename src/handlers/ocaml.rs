@@ -0,0 +1,103 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, scan_number, unescape_hex, unescape_braced};
+
+/// An escape handler following the rules of OCaml string literals, as defined in
+/// [the OCaml manual](https://ocaml.org/manual/5.2/lex.html#sss:escape-sequences).
+///
+/// The following escapes are valid:
+/// - `\\b` -> `\x08`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\ ` -> ` `
+/// - `\\\\` -> `\\`
+/// - `\\"` -> `"`
+/// - `\\'` -> `'`
+/// - `\\ddd` -> the character with that **decimal** value, up to `\\255`
+/// - `\\xHH` -> `\u{HH}`
+/// - `\\oOOO` -> the character with that octal value, up to `\\o377`
+/// - `\\u{HEX}` -> `\u{HEX}`
+///
+/// Decimal and octal escapes must have exactly three digits, so `\\65` is invalid, and has to be written as `\\065`.
+/// A backslash followed by a newline is removed along with the newline,
+/// and any spaces and tabs at the start of the next line.
+///
+/// OCaml puts the raw byte in the string for decimal, octal, and `\\xHH` escapes,
+/// but a Rust string can only hold characters, so these produce the character with that value instead.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, OCamlHandler};
+/// let unescaped = "\\065\\o102\\x43\\u{1F600} and \\\n     more".to_unescaped_with(OCamlHandler);
+/// assert_eq!(unescaped.unwrap(), "ABC\u{1F600} and more");
+///
+/// r"\65".to_unescaped_with(OCamlHandler).expect_err("decimal escapes need three digits");
+/// # }
+/// ```
+///
+pub struct OCamlHandler;
+
+impl EscapeHandler for OCamlHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        let value = match chr {
+            'b' => return Ok(Some('\x08')),
+            'n' => return Ok(Some('\n')),
+            'r' => return Ok(Some('\r')),
+            't' => return Ok(Some('\t')),
+            ' ' => return Ok(Some(' ')),
+            '\\' => return Ok(Some('\\')),
+            '"' => return Ok(Some('"')),
+            '\'' => return Ok(Some('\'')),
+            'x' => return consume(iter, unescape_hex).map(Some),
+            'u' => return match iter.as_str().chars().next() {
+                Some('{') => consume(iter, unescape_braced).map(Some),
+                Some(_) => Err(EscapeErrorKind::InvalidHexDigit),
+                None => Err(EscapeErrorKind::Truncated)
+            },
+            newline @ '\n' | newline @ '\r' => {
+                if newline == '\r' && iter.next().map(|(_, chr)| chr) != Some('\n') {
+                    return Err(EscapeErrorKind::UnknownEscape('\r'));
+                }
+                let rest = iter.as_str();
+                let indent = rest.len() - rest.trim_start_matches(|chr| chr == ' ' || chr == '\t').len();
+                consume(iter, |_| ((), indent));
+                return Ok(None);
+            },
+            'o' => match iter.next() {
+                Some((_, c)) if c.is_digit(8) => consume(iter, |rest| unescape_exact(c, rest, 8))?,
+                Some(_) => return Err(EscapeErrorKind::InvalidOctalDigit),
+                None => return Err(EscapeErrorKind::Truncated)
+            },
+            c if c.is_ascii_digit() => consume(iter, |rest| unescape_exact(c, rest, 10))?,
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        };
+        if value > 0xFF {
+            return Err(EscapeErrorKind::OutOfRange(value));
+        }
+        #[allow(clippy::cast_possible_truncation)] // It was just checked to fit
+        let byte = { value as u8 };
+        Ok(Some(char::from(byte)))
+    }
+}
+
+/// Parses a number of exactly three digits in the given radix, starting with the digit `first`,
+/// followed by the rest of the string.
+fn unescape_exact(first: char, rest: &str, radix: u32) -> (Result<u32, EscapeErrorKind>, usize) {
+    let (value, len) = scan_number(first, rest, radix, 3);
+    let res = if len == 2 {
+        Ok(value)
+    } else if rest.len() == len {
+        Err(EscapeErrorKind::Truncated)
+    } else if radix == 8 {
+        Err(EscapeErrorKind::InvalidOctalDigit)
+    } else {
+        Err(EscapeErrorKind::InvalidDecimalDigit)
+    };
+    (res, len)
+}
@@ -0,0 +1,53 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind};
+
+/// An escape handler that tries one handler, falling back to another if it rejects the sequence.
+///
+/// Made with [`EscapeHandler::or_else`].
+///
+/// Before calling the first handler, the iterator is saved,
+/// and if the first handler errors, it's rewound to that point before calling the second.
+/// This way, the second handler sees the sequence exactly like the first did,
+/// even if the first consumed some characters before giving up on it.
+///
+/// If both handlers reject the sequence, the error from the second is reported,
+/// spanning only what the second consumed.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, EscapeHandler, DefaultHandler}; use std::str::CharIndices;
+/// fn brackets(_: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+///     // Consumes a character before deciding whether this is a sequence it knows
+///     match (chr, iter.next()) {
+///         ('l', Some((_, 't'))) => Ok(Some('<')),
+///         ('g', Some((_, 't'))) => Ok(Some('>')),
+///         _ => Err(())
+///     }
+/// }
+///
+/// let unescaped = r"\lt\tag\gt\n".to_unescaped_with(brackets.or_else(DefaultHandler));
+/// assert_eq!(unescaped.unwrap(), "<\tag>\n");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct OrElse<A, B>(pub A, pub B);
+
+impl<A, B> EscapeHandler for OrElse<A, B>
+    where A: EscapeHandler, B: EscapeHandler
+{
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+        // Cloning this is pretty cheap
+        let start = iter.clone();
+        self.0.escape_detailed(idx, chr, iter).or_else(|_| {
+            *iter = start;
+            self.1.escape_detailed(idx, chr, iter)
+        })
+    }
+}
@@ -0,0 +1,39 @@
+use alloc::vec::Vec;
+
+use crate::{ByteEscapeHandler, ByteIndices, EscapeErrorKind, parse_digits};
+
+/// An escape handler for percent-encoding, as used in URLs and `application/x-www-form-urlencoded` data.
+///
+/// Every escape is exactly two hexadecimal digits, which stand for a single raw byte.
+///
+/// As percent-encoding works on bytes, and starts escapes with `%` instead of `\`,
+/// this handler is meant to be used with [`crate::UnescapeBytesExt::to_unescaped_bytes_with_escape_byte`],
+/// with `b'%'` as the escape byte.
+/// The result can be turned back into a string with [`alloc::string::String::from_utf8`] afterwards.
+///
+/// Forms also encode spaces as `+`, which isn't an escape sequence, and needs to be replaced separately.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeBytesExt, PercentHandler};
+/// let unescaped = b"100%25%20%F0%9F%98%80".to_unescaped_bytes_with_escape_byte(b'%', PercentHandler);
+/// assert_eq!(&*unescaped.unwrap(), "100% 😀".as_bytes());
+///
+/// b"%G0".to_unescaped_bytes_with_escape_byte(b'%', PercentHandler).expect_err("G isn't hexadecimal");
+/// ```
+///
+pub struct PercentHandler;
+
+impl ByteEscapeHandler for PercentHandler {
+    fn escape_byte(&mut self, idx: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), ()> {
+        self.escape_byte_detailed(idx, byte, iter, out).map_err(|_| ())
+    }
+
+    fn escape_byte_detailed(&mut self, _: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), EscapeErrorKind> {
+        let (_, low) = iter.next().ok_or(EscapeErrorKind::Truncated)?;
+        let value = parse_digits(&[byte, low], 16).ok_or(EscapeErrorKind::InvalidHexDigit)?;
+        #[allow(clippy::cast_possible_truncation)] // Two hex digits always fit
+        out.push(value as u8);
+        Ok(())
+    }
+}
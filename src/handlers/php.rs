@@ -0,0 +1,67 @@
+use alloc::{
+    string::String,
+    str::CharIndices
+};
+
+use crate::{EscapeWriter, EscapeErrorKind, consume, to_char, unescape_braced};
+use super::sql::{unescape_byte, unescape_utf8};
+
+/// An escape handler following the rules of PHP double-quoted strings, as defined in
+/// [the PHP manual](https://www.php.net/manual/en/language.types.string.php#language.types.string.syntax.double).
+///
+/// The following escapes are valid:
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\e` -> `\x1B`
+/// - `\\f` -> `\x0C`
+/// - `\\\\` -> `\\`
+/// - `\\$` -> `$`
+/// - `\\"` -> `"`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the byte with that octal value, modulo 256
+/// - `\\xh` and `\\xhh` -> the byte with that hexadecimal value
+/// - `\\u{HEX}` -> `\u{HEX}`
+///
+/// Any other escape is kept as it was written, backslash and all, like [`crate::UnknownEscapePolicy::KeepBackslash`].
+/// This includes `\\'`, and an `\\x` or `\\u` without any digits or braces after it.
+///
+/// Octal and hexadecimal escapes give single bytes, so a character outside of ASCII is made of several of them in a row,
+/// like `\\xC3\\xA9` for `é`. Bytes that don't make up a whole UTF-8 character are invalid,
+/// as they can't be put in a Rust string. Variable interpolation is left as-is.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, PhpHandler};
+/// let unescaped = r#"\$cost: \"5\u{20AC}\" \xC3\xA9\q"#.to_unescaped_with(PhpHandler);
+/// assert_eq!(unescaped.unwrap(), "$cost: \"5\u{20AC}\" \u{E9}\\q");
+/// ```
+///
+pub struct PhpHandler;
+
+impl EscapeWriter for PhpHandler {
+    fn write_escape(&mut self, _: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind> {
+        let chr = match chr {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            'e' => '\x1B',
+            'f' => '\x0C',
+            '\\' => '\\',
+            '$' => '$',
+            '"' => '"',
+            'u' if iter.as_str().starts_with('{') => consume(iter, unescape_braced)?,
+            c => match unescape_byte(c, iter) {
+                Some(value) if value < 0x80 => to_char(value)?,
+                Some(lead) => unescape_utf8(lead, iter)?,
+                None => {
+                    out.push('\\');
+                    c
+                }
+            }
+        };
+        out.push(chr);
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, unescape_braced};
+
+/// An escape handler following the rules of PowerShell double-quoted strings, as described in
+/// [about_Special_Characters](https://learn.microsoft.com/en-us/powershell/module/microsoft.powershell.core/about/about_special_characters).
+///
+/// PowerShell escapes with a backtick instead of a backslash, so this handler relies on the escape character being `` ` ``,
+/// through [`crate::UnescapeExt::to_unescaped_with_escape_char`]. Backslashes are ordinary characters, and are left alone.
+///
+/// The following escapes are valid:
+/// - `` `0 `` -> `\0`
+/// - `` `a `` -> `\x07`
+/// - `` `b `` -> `\x08`
+/// - `` `e `` -> `\x1B`
+/// - `` `f `` -> `\x0C`
+/// - `` `n `` -> `\x0A`
+/// - `` `r `` -> `\x0D`
+/// - `` `t `` -> `\x09`
+/// - `` `v `` -> `\x0B`
+/// - `` `u{HEX} `` -> `\u{HEX}`
+///
+/// Any other character after a backtick stands for itself, so `` `" `` becomes `"`, `` `$ `` becomes `$`,
+/// and ```` `` ```` becomes `` ` ``.
+/// A doubled `""` inside the string isn't handled, as it doesn't start with the escape character.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, PowerShellHandler};
+/// let unescaped = r"C:\temp`t`$name`u{1F600}".to_unescaped_with_escape_char('`', PowerShellHandler);
+/// assert_eq!(unescaped.unwrap(), "C:\\temp\t$name\u{1F600}");
+/// # }
+/// ```
+///
+pub struct PowerShellHandler;
+
+impl EscapeHandler for PowerShellHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            '0' => '\0',
+            'a' => '\x07',
+            'b' => '\x08',
+            'e' => '\x1B',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            'u' if iter.as_str().starts_with('{') => consume(iter, unescape_braced)?,
+            c => c
+        } ))
+    }
+}
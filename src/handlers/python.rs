@@ -0,0 +1,160 @@
+use alloc::{
+    borrow::Cow,
+    string::String,
+    str::CharIndices,
+    vec::Vec
+};
+
+use crate::{
+    MultiEscapeHandler, ByteEscapeHandler, ByteIndices, EscapeErrorKind, Replacement,
+    consume, parse_digits, parse_fixed, unescape_oct, unescape_hex, unescape_unicode_long, to_char
+};
+
+/// An escape handler following the rules of Python `str` literals.
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the character with that octal value, up to `\u{1FF}`
+/// - `\\xHH` -> `\u{HH}`
+/// - `\\uHHHH` -> `\u{HHHH}`
+/// - `\\UHHHHHHHH` -> `\u{HHHHHHHH}`
+/// - `\\N{NAME}` -> the character with that Unicode name, only with the `unicode-names` feature
+///
+/// A backslash followed by a newline is removed along with it, as a line continuation.
+///
+/// Any other escape is kept as it was written, backslash and all, like Python does.
+/// Surrogates can't be represented in a Rust string, so `\\uD800` is [`EscapeErrorKind::LoneSurrogate`].
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, PythonStrHandler};
+/// let unescaped = r"\d+\t\x41\101\u00e9".to_unescaped_with(PythonStrHandler);
+/// assert_eq!(unescaped.unwrap(), "\\d+\tAA\u{E9}");
+/// ```
+///
+pub struct PythonStrHandler;
+
+impl MultiEscapeHandler for PythonStrHandler {
+    fn escape_multi<'source>(&mut self, _: usize, chr: char, iter: &mut CharIndices<'source>) -> Result<Option<Replacement<'source>>, EscapeErrorKind> {
+        Ok(Some(Replacement::Char( match chr {
+            'a' => '\x07',
+            'b' => '\x08',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            '\n' => return Ok(None),
+            '\r' if iter.as_str().starts_with('\n') => {
+                iter.next();
+                return Ok(None);
+            },
+            'x' => consume(iter, unescape_hex)?,
+            'u' => consume(iter, |rest| parse_fixed(rest, 4))?,
+            'U' => consume(iter, unescape_unicode_long)?,
+            #[cfg(feature = "unicode-names")]
+            'N' => consume(iter, crate::names::unescape_named)?,
+            #[cfg(not(feature = "unicode-names"))]
+            'N' => return Err(EscapeErrorKind::UnknownEscape('N')),
+            c if c.is_digit(8) => to_char(consume(iter, |rest| unescape_oct(c, rest)))?,
+            c => {
+                let mut kept = String::with_capacity(1 + c.len_utf8());
+                kept.push('\\');
+                kept.push(c);
+                return Ok(Some(Replacement::Str(Cow::Owned(kept))));
+            }
+        } )))
+    }
+}
+
+/// An escape handler following the rules of Python `bytes` literals, for use with [`crate::UnescapeBytesExt`].
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the byte with that octal value, modulo 256
+/// - `\\xHH` -> the byte `0xHH`
+///
+/// A backslash followed by a newline is removed along with it, as a line continuation.
+///
+/// Any other escape is kept as it was written, backslash and all, like Python does.
+/// This includes `\\u`, `\\U`, and `\\N`, which only mean something in `str` literals.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeBytesExt, PythonBytesHandler};
+/// let unescaped = br"\x00\xff\101\u0041".to_unescaped_bytes_with(PythonBytesHandler);
+/// assert_eq!(&*unescaped.unwrap(), b"\x00\xFFA\\u0041");
+/// ```
+///
+pub struct PythonBytesHandler;
+
+impl ByteEscapeHandler for PythonBytesHandler {
+    fn escape_byte(&mut self, idx: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), ()> {
+        self.escape_byte_detailed(idx, byte, iter, out).map_err(|_| ())
+    }
+
+    fn escape_byte_detailed(&mut self, _: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), EscapeErrorKind> {
+        out.push( match byte {
+            b'a' => b'\x07',
+            b'b' => b'\x08',
+            b'f' => b'\x0C',
+            b'n' => b'\n',
+            b'r' => b'\r',
+            b't' => b'\t',
+            b'v' => b'\x0B',
+            b'\\' => b'\\',
+            b'\'' => b'\'',
+            b'"' => b'"',
+            b'\n' => return Ok(()),
+            b'\r' if iter.as_slice().first() == Some(&b'\n') => {
+                iter.next();
+                return Ok(());
+            },
+            b'x' => {
+                let digits = [
+                    iter.next().ok_or(EscapeErrorKind::Truncated)?.1,
+                    iter.next().ok_or(EscapeErrorKind::Truncated)?.1
+                ];
+                let value = parse_digits(&digits, 16).ok_or(EscapeErrorKind::InvalidHexDigit)?;
+                #[allow(clippy::cast_possible_truncation)] // Two hex digits always fit
+                { value as u8 }
+            },
+            b'0' ..= b'7' => {
+                let rest = iter.as_slice();
+                let len = rest.iter().take(2).take_while(|byte| (b'0' ..= b'7').contains(byte)).count();
+                let mut digits = [byte; 3];
+                digits[1 ..= len].copy_from_slice(&rest[.. len]);
+                for _ in 0 .. len { iter.next(); }
+                // At most \777, which Python cuts down to a byte
+                let value = parse_digits(&digits[..= len], 8).unwrap_or(0);
+                (value & 0xFF) as u8
+            },
+            _ => {
+                out.extend_from_slice(&[b'\\', byte]);
+                return Ok(());
+            }
+        } );
+        Ok(())
+    }
+}
@@ -0,0 +1,54 @@
+use alloc::string::String;
+use core::str::CharIndices;
+
+use crate::{DefaultHandler, EscapeWriter, EscapeErrorKind};
+
+/// An escape handler that never rejects a sequence, keeping any the wrapped handler rejects exactly as written.
+///
+/// If the wrapped handler errors, the iterator is rewound to right after the character following the backslash,
+/// and the backslash and that character are kept. Anything after them is unescaped as usual,
+/// so a malformed sequence like `\\xZZ` is kept whole, since `ZZ` is just text.
+///
+/// Unlike [`crate::UnescapeExt::to_unescaped_lossy`], nothing is replaced with `U+FFFD`,
+/// so text that was never meant to be escaped comes out unchanged.
+/// A backslash at the very end of the string is still an error,
+/// as there's no sequence to call the handler with.
+///
+/// This always keeps a backslash, even when the escape character is something else.
+/// The wrapped handler's [`EscapeWriter::max_output_len`] still applies, as going over it isn't a problem with any one sequence.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, ResilientHandler, DefaultHandler};
+/// let unescaped = r"C:\quux\n\x4Z".to_unescaped_with(ResilientHandler(DefaultHandler));
+/// assert_eq!(unescaped.unwrap(), "C:\\quux\n\\x4Z");
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct ResilientHandler<H = DefaultHandler>(pub H);
+
+impl<H> EscapeWriter for ResilientHandler<H>
+    where H: EscapeWriter
+{
+    fn write_escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind> {
+        self.write_escape_with('\\', idx, chr, iter, out)
+    }
+
+    fn write_escape_with(
+        &mut self, escape: char, idx: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String
+    ) -> Result<(), EscapeErrorKind> {
+        let start = iter.clone();
+        let written = out.len();
+        if self.0.write_escape_with(escape, idx, chr, iter, out).is_err() {
+            *iter = start;
+            out.truncate(written);
+            out.push('\\');
+            out.push(chr);
+        }
+        Ok(())
+    }
+
+    fn max_output_len(&self) -> usize {
+        self.0.max_output_len()
+    }
+}
@@ -0,0 +1,95 @@
+use alloc::{
+    string::String,
+    str::CharIndices
+};
+
+use crate::{
+    EscapeWriter, EscapeErrorKind,
+    consume, parse_fixed, scan_number, unescape_oct, unescape_braced_multi, unescape_control, to_char
+};
+
+/// An escape handler following the rules of Ruby double-quoted string literals.
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\e` -> `\x1B`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\s` -> `\x20`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the character with that octal value, modulo 256
+/// - `\\xH` and `\\xHH` -> `\u{HH}`
+/// - `\\uHHHH` -> `\u{HHHH}`
+/// - `\\u{H HH HHH ...}` -> each codepoint, separated by spaces or tabs
+/// - `\\cX` -> the control character for `X`, like `\\cG` -> `\x07`
+///
+/// A backslash followed by a newline is removed along with it, as a line continuation.
+///
+/// Any other escape is replaced with the character after the backslash, like Ruby does.
+/// The `\\C-X` and `\\M-X` forms aren't supported, and `#{}` interpolation is left as-is.
+///
+/// Ruby puts the raw byte in the string for octal and `\\xHH` escapes,
+/// but a Rust string can only hold characters, so these produce the character with that value instead.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, RubyHandler};
+/// let unescaped = r"\s\x41\101\u{1F600 21}\q".to_unescaped_with(RubyHandler);
+/// assert_eq!(unescaped.unwrap(), " AA\u{1F600}!q");
+/// ```
+///
+pub struct RubyHandler;
+
+impl EscapeWriter for RubyHandler {
+    fn write_escape(&mut self, _: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind> {
+        let chr = match chr {
+            'a' => '\x07',
+            'b' => '\x08',
+            'e' => '\x1B',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            's' => ' ',
+            't' => '\t',
+            'v' => '\x0B',
+            '\n' => return Ok(()),
+            '\r' if iter.as_str().starts_with('\n') => {
+                iter.next();
+                return Ok(());
+            },
+            'x' => match iter.as_str().chars().next() {
+                // One or two digits
+                Some(first) if first.is_ascii_hexdigit() => {
+                    iter.next();
+                    to_char(consume(iter, |rest| scan_number(first, rest, 16, 2)))?
+                },
+                Some(_) => return Err(EscapeErrorKind::InvalidHexDigit),
+                None => return Err(EscapeErrorKind::Truncated)
+            },
+            'u' if iter.as_str().starts_with('{') => return consume(iter, |rest| unescape_braced_multi(rest, out)),
+            'u' => consume(iter, |rest| parse_fixed(rest, 4))?,
+            'c' => {
+                let rest = iter.as_str();
+                match (unescape_control(iter), rest.chars().next()) {
+                    (Some(chr), _) => chr,
+                    (None, Some(chr)) => return Err(EscapeErrorKind::UnknownEscape(chr)),
+                    (None, None) => return Err(EscapeErrorKind::Truncated),
+                }
+            },
+            c if c.is_digit(8) => {
+                // At most \777, which Ruby cuts down to a byte
+                let value = consume(iter, |rest| unescape_oct(c, rest));
+                to_char(value & 0xFF)?
+            },
+            c => c
+        };
+        out.push(chr);
+        Ok(())
+    }
+}
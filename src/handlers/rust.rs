@@ -0,0 +1,111 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, skip_bytes, consume, unescape_hex, to_char};
+
+/// An escape handler following the rules of Rust string literals.
+///
+/// The following escapes are valid:
+/// - `\\n` -> `\n`
+/// - `\\r` -> `\r`
+/// - `\\t` -> `\t`
+/// - `\\\\` -> `\\`
+/// - `\\0` -> `\0`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - `\\xNN` -> `\xNN`, where `NN` is at most `7F`
+/// - `\\u{HEX}` -> `\u{HEX}`, with 1 to 6 hexadecimal digits, optionally separated by underscores
+///
+/// A backslash at the end of a line is removed, along with the newline and any whitespace at the start of the next line.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, RustHandler};
+/// let unescaped = "Hello, \\\n    world!\\u{1_F600}".to_unescaped_with(RustHandler);
+/// assert_eq!(unescaped.unwrap(), "Hello, world!\u{1_F600}");
+///
+/// r"\a".to_unescaped_with(RustHandler).expect_err("Rust has no bell escape");
+/// # }
+/// ```
+///
+pub struct RustHandler;
+
+impl EscapeHandler for RustHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '\\' => '\\',
+            '0' => '\0',
+            '\'' => '\'',
+            '"' => '"',
+            'x' => {
+                let res = consume(iter, unescape_hex)?;
+                if !res.is_ascii() {
+                    return Err(EscapeErrorKind::OutOfRange(res as u32));
+                }
+                res
+            },
+            'u' => {
+                consume(iter, unescape_rust_unicode)?
+            },
+            '\r' if iter.as_str().starts_with('\n') => {
+                iter.next();
+                skip_whitespace(iter);
+                return Ok(None);
+            },
+            '\n' => {
+                skip_whitespace(iter);
+                return Ok(None);
+            },
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// Skips the whitespace that Rust skips after a line continuation.
+fn skip_whitespace(iter: &mut CharIndices) {
+    let rest = iter.as_str();
+    let skip = rest.len() - rest.trim_start_matches(|chr| matches!(chr, ' ' | '\t' | '\n' | '\r')).len();
+    skip_bytes(iter, skip);
+}
+
+/// Parses the part of a `\u{HEX}` escape after the `u`,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn unescape_rust_unicode(
+    string: &str
+) -> (Result<char, EscapeErrorKind>, usize) {
+    let rest = match string.strip_prefix('{') {
+        Some(rest) => rest,
+        None if string.is_empty() => return (Err(EscapeErrorKind::Truncated), 0),
+        None => return (Err(EscapeErrorKind::InvalidHexDigit), 0)
+    };
+    let end = match rest.find('}') {
+        Some(end) => end,
+        None => return (Err(EscapeErrorKind::Truncated), string.len())
+    };
+    let digits = &rest[.. end];
+    // The first digit can't be an underscore
+    if digits.is_empty() || digits.starts_with('_') {
+        return (Err(EscapeErrorKind::InvalidHexDigit), end + 2);
+    }
+    let mut count = 0;
+    let mut codepoint = 0u32;
+    for digit in digits.chars().filter(|&chr| chr != '_') {
+        let digit = match digit.to_digit(16) {
+            Some(digit) => digit,
+            None => return (Err(EscapeErrorKind::InvalidHexDigit), end + 2)
+        };
+        count += 1;
+        codepoint = codepoint.saturating_mul(16).saturating_add(digit);
+    }
+    if count > 6 {
+        return (Err(EscapeErrorKind::OutOfRange(codepoint)), end + 2);
+    }
+    (to_char(codepoint), end + 2)
+}
@@ -0,0 +1,96 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, skip_bytes, parse_digits, to_char};
+
+/// An escape handler following the rules of Bash's ANSI-C quoting, `$'...'`.
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\e` -> `\x1B`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\v` -> `\x0B`
+/// - `\\\\` -> `\\`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - `\\?` -> `?`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the octal value, modulo 256
+/// - `\\xH` and `\\xHH` -> `\xHH`
+/// - `\\uH` to `\\uHHHH` -> `\u{HHHH}`
+/// - `\\UH` to `\\UHHHHHHHH` -> `\u{HHHHHHHH}`
+/// - `\\cX` -> the control character for `X`, so `\\cA` and `\\ca` are `\x01`, and `\\c?` is `\x7F`
+///
+/// Octal and hexadecimal escapes produce the character with their value,
+/// where Bash would produce a raw byte.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, ShellHandler};
+/// let unescaped = r"it\'s \cA\x7☺".to_unescaped_with(ShellHandler);
+/// assert_eq!(unescaped.unwrap(), "it's \x01\x07\u{263A}");
+/// # }
+/// ```
+///
+pub struct ShellHandler;
+
+impl EscapeHandler for ShellHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'a' => '\x07',
+            'b' => '\x08',
+            'e' | 'E' => '\x1B',
+            'f' => '\x0C',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'v' => '\x0B',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            '?' => '?',
+            'x' => hex_run(iter, 2)?,
+            'u' => hex_run(iter, 4)?,
+            'U' => hex_run(iter, 8)?,
+            'c' => {
+                let (_, chr) = iter.next().ok_or(EscapeErrorKind::Truncated)?;
+                if !chr.is_ascii() {
+                    return Err(EscapeErrorKind::UnknownEscape(chr));
+                }
+                // This is how Bash does it, so any ASCII character is fine
+                if chr == '?' { '\x7F' } else { char::from(chr.to_ascii_uppercase() as u8 & 0x1F) }
+            },
+            c if c.is_digit(8) => {
+                let rest = iter.as_str();
+                let len = rest.bytes().take(2).take_while(|byte| (b'0' ..= b'7').contains(byte)).count();
+                skip_bytes(iter, len);
+                let mut digits = [b'0'; 3];
+                digits[0] = c as u8;
+                digits[1 ..= len].copy_from_slice(&rest.as_bytes()[.. len]);
+                // At most \777, which Bash cuts down to a byte
+                let codepoint = parse_digits(&digits[..= len], 8).unwrap_or(0);
+                char::from((codepoint & 0xFF) as u8)
+            },
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// Parses between 1 and `max` hexadecimal digits into a character, consuming them from the iterator.
+fn hex_run(iter: &mut CharIndices, max: usize) -> Result<char, EscapeErrorKind> {
+    let rest = iter.as_str();
+    let len = rest.bytes().take(max).take_while(u8::is_ascii_hexdigit).count();
+    skip_bytes(iter, len);
+    match parse_digits(&rest.as_bytes()[.. len], 16) {
+        Some(codepoint) => to_char(codepoint),
+        None if rest.is_empty() => Err(EscapeErrorKind::Truncated),
+        None => Err(EscapeErrorKind::InvalidHexDigit)
+    }
+}
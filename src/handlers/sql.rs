@@ -0,0 +1,197 @@
+#[cfg(feature = "alloc")]
+use alloc::borrow::Cow;
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, consume, scan_number, to_char, unescape_utf16, unescape_unicode_long};
+#[cfg(feature = "alloc")]
+use crate::{InvalidEscape, UnescapeExt};
+
+/// An escape handler following the rules of standard SQL string literals, where a quote is escaped by doubling it.
+///
+/// The only valid escape is `''` -> `'`.
+///
+/// There's no backslash involved, so this handler relies on the escape character being `'`,
+/// through [`crate::UnescapeExt::to_unescaped_with_escape_char`].
+/// It's given the contents of the literal, without the surrounding quotes;
+/// any other `'` would end the literal, so it's invalid here.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, SqlAnsiHandler};
+/// let unescaped = r"it''s C:\temp".to_unescaped_with_escape_char('\'', SqlAnsiHandler);
+/// assert_eq!(unescaped.unwrap(), r"it's C:\temp");
+///
+/// "it's".to_unescaped_with_escape_char('\'', SqlAnsiHandler).expect_err("the quote ends the literal");
+/// # }
+/// ```
+///
+pub struct SqlAnsiHandler;
+
+impl EscapeHandler for SqlAnsiHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, _: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        match chr {
+            '\'' => Ok(Some('\'')),
+            c => Err(EscapeErrorKind::UnknownEscape(c))
+        }
+    }
+}
+
+/// An escape handler following the rules of Postgres escape string constants, written as `E'...'`, as defined in
+/// [the PostgreSQL documentation](https://www.postgresql.org/docs/current/sql-syntax-lexical.html#SQL-SYNTAX-STRINGS-ESCAPE).
+///
+/// The following escapes are valid:
+/// - `\\b` -> `\x08`
+/// - `\\f` -> `\x0C`
+/// - `\\n` -> `\x0A`
+/// - `\\r` -> `\x0D`
+/// - `\\t` -> `\x09`
+/// - `\\o`, `\\oo`, and `\\ooo` -> the byte with that octal value
+/// - `\\xh` and `\\xhh` -> the byte with that hexadecimal value
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\UXXXXXXXX` -> `\u{XXXXXXXX}`
+///
+/// Any other character after a backslash stands for itself, including `\\\\`, `\\'`, and an `\\x` without any digits.
+///
+/// Octal and hexadecimal escapes give single bytes, so a character outside of ASCII is made of several of them in a row,
+/// like `\\xC3\\xA9` for `é`. Bytes that don't make up a whole UTF-8 character are invalid,
+/// as are escapes that give a NUL character, since Postgres doesn't allow them in text.
+/// A `\\uXXXX` high surrogate immediately followed by a `\\uXXXX` low surrogate is combined into the character the pair encodes.
+///
+/// Inside the literal, a quote can also be escaped by doubling it. A doubled quote isn't a backslash escape,
+/// so it can't be handled here; use [`PostgresEStringHandler::unescape_literal`] to unescape both at once.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, PostgresEStringHandler};
+/// let unescaped = r"caf\xC3\xa9\t\303\251é\q".to_unescaped_with(PostgresEStringHandler);
+/// assert_eq!(unescaped.unwrap(), "caf\u{E9}\t\u{E9}\u{E9}q");
+///
+/// r"\xC3".to_unescaped_with(PostgresEStringHandler).expect_err("that's only half of a character");
+/// # }
+/// ```
+///
+pub struct PostgresEStringHandler;
+
+impl PostgresEStringHandler {
+    /**
+    Unescapes an escape string constant at the start of `literal`, which starts right after the opening quote,
+    returning the unescaped contents along with the rest of the string after the closing quote.
+
+    Backslash escapes are unescaped with [`PostgresEStringHandler`], and a doubled quote is part of the literal.
+    The first quote that isn't escaped either way ends it.
+    Will only allocate if the literal has any escape sequences.
+
+    # Errors
+    Errors if there's an invalid escape sequence before the closing quote,
+    passing back the byte index of the invalid character, and why it was invalid.
+
+    If the string ends before the closing quote, this errors with [`EscapeErrorKind::Unterminated`],
+    with the index being the length of the string.
+
+    # Examples
+    ```rust
+    # use descape::PostgresEStringHandler;
+    let source = r"E'it''s \'quoted\'' AS x";
+    let (literal, rest) = PostgresEStringHandler::unescape_literal(&source[2 ..]).unwrap();
+    assert_eq!(literal, "it's 'quoted'");
+    assert_eq!(rest, " AS x");
+    ```
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn unescape_literal(literal: &str) -> Result<(Cow<'_, str>, &str), InvalidEscape> {
+        let (mut contents, mut start) = literal.unescape_until_consumed('\'', Self)?;
+
+        // A quote right after the closing one means it was doubled
+        while literal[start ..].starts_with('\'') {
+            start += 1;
+            let (part, len) = literal[start ..].unescape_until_consumed('\'', Self)
+                .map_err(|mut err| { err.index += start; err })?;
+            let owned = contents.to_mut();
+            owned.push('\'');
+            owned.push_str(&part);
+            start += len;
+        }
+
+        Ok((contents, &literal[start ..]))
+    }
+}
+
+/// Parses an octal or hexadecimal byte escape, or returns `None` if `chr` doesn't start one.
+pub(super) fn unescape_byte(chr: char, iter: &mut CharIndices) -> Option<u32> {
+    match chr {
+        'x' => {
+            let first = iter.as_str().chars().next().filter(char::is_ascii_hexdigit)?;
+            iter.next();
+            Some(consume(iter, |rest| scan_number(first, rest, 16, 2)))
+        },
+        // Values past a byte wrap around, like they do in Postgres
+        '0' ..= '7' => Some(consume(iter, |rest| scan_number(chr, rest, 8, 3)) & 0xFF),
+        _ => None
+    }
+}
+
+/// Parses the rest of a UTF-8 character made of byte escapes, after the byte escape for its first byte.
+pub(super) fn unescape_utf8(lead: u32, iter: &mut CharIndices) -> Result<char, EscapeErrorKind> {
+    let len = match lead {
+        0xC2 ..= 0xDF => 2,
+        0xE0 ..= 0xEF => 3,
+        0xF0 ..= 0xF4 => 4,
+        _ => return Err(EscapeErrorKind::OutOfRange(lead))
+    };
+    #[allow(clippy::cast_possible_truncation)] // Bytes are masked to fit
+    let mut bytes = [{ lead as u8 }; 4];
+    for byte in &mut bytes[1 .. len] {
+        let rest = iter.as_str();
+        let next = match rest.strip_prefix('\\').and_then(|rest| rest.chars().next()) {
+            Some(chr) => chr,
+            None => return Err(EscapeErrorKind::OutOfRange(lead))
+        };
+        let mut lookahead = iter.clone();
+        lookahead.nth(1);
+        match unescape_byte(next, &mut lookahead) {
+            Some(value) => {
+                *iter = lookahead;
+                #[allow(clippy::cast_possible_truncation)] // Bytes are masked to fit
+                { *byte = value as u8; }
+            },
+            None => return Err(EscapeErrorKind::OutOfRange(lead))
+        }
+    }
+    core::str::from_utf8(&bytes[.. len]).ok()
+        .and_then(|string| string.chars().next())
+        .ok_or(EscapeErrorKind::OutOfRange(lead))
+}
+
+impl EscapeHandler for PostgresEStringHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        let unescaped = match chr {
+            'b' => return Ok(Some('\x08')),
+            'f' => return Ok(Some('\x0C')),
+            'n' => return Ok(Some('\n')),
+            'r' => return Ok(Some('\r')),
+            't' => return Ok(Some('\t')),
+            'u' => consume(iter, unescape_utf16)?,
+            'U' => consume(iter, unescape_unicode_long)?,
+            c => match unescape_byte(c, iter) {
+                Some(value) if value < 0x80 => to_char(value)?,
+                Some(lead) => unescape_utf8(lead, iter)?,
+                None => return Ok(Some(c))
+            }
+        };
+        if unescaped == '\0' {
+            return Err(EscapeErrorKind::OutOfRange(0));
+        }
+        Ok(Some(unescaped))
+    }
+}
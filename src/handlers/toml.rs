@@ -0,0 +1,95 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, skip_bytes, consume, parse_fixed, unescape_unicode_long};
+
+/// An escape handler following the rules of TOML basic strings, as defined in [TOML 1.0](https://toml.io/en/v1.0.0#string).
+///
+/// The following escapes are valid:
+/// - `\\b` -> `\x08`
+/// - `\\t` -> `\x09`
+/// - `\\n` -> `\x0A`
+/// - `\\f` -> `\x0C`
+/// - `\\r` -> `\x0D`
+/// - `\\"` -> `"`
+/// - `\\\\` -> `\\`
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\UXXXXXXXX` -> `\u{XXXXXXXX}`
+///
+/// In multiline basic strings, enabled with [`TomlHandler::multiline`],
+/// a backslash at the end of a line is removed along with all whitespace and newlines after it.
+/// Only spaces and tabs may come between the backslash and the end of the line.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, TomlHandler};
+/// let unescaped = r#"\"quoted\"\tand \U0001F600"#.to_unescaped_with(TomlHandler::new());
+/// assert_eq!(unescaped.unwrap(), "\"quoted\"\tand \u{1F600}");
+///
+/// let unescaped = "The quick \\  \n\n    brown fox".to_unescaped_with(TomlHandler::new().multiline(true));
+/// assert_eq!(unescaped.unwrap(), "The quick brown fox");
+///
+/// r"\x41".to_unescaped_with(TomlHandler::new()).expect_err("TOML has no hex escapes");
+/// # }
+/// ```
+///
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct TomlHandler {
+    multiline: bool,
+}
+
+impl TomlHandler {
+    /// Creates a handler for single-line basic strings.
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { multiline: false }
+    }
+
+    /// Sets whether this handler is for multiline basic strings, where a backslash can end a line.
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn multiline(self, multiline: bool) -> Self {
+        Self { multiline }
+    }
+}
+
+impl EscapeHandler for TomlHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            'b' => '\x08',
+            't' => '\t',
+            'n' => '\n',
+            'f' => '\x0C',
+            'r' => '\r',
+            '"' => '"',
+            '\\' => '\\',
+            'u' => consume(iter, |rest| parse_fixed(rest, 4))?,
+            'U' => consume(iter, unescape_unicode_long)?,
+            c if self.multiline && ends_line(c, iter.as_str()) => {
+                let rest = iter.as_str();
+                let skip = rest.len() - rest.trim_start_matches(|chr| matches!(chr, ' ' | '\t' | '\n' | '\r')).len();
+                skip_bytes(iter, skip);
+                return Ok(None);
+            },
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// Checks whether `chr` and the `rest` after it are only whitespace until the end of the line.
+fn ends_line(chr: char, rest: &str) -> bool {
+    match chr {
+        '\n' => true,
+        '\r' => rest.starts_with('\n'),
+        ' ' | '\t' => {
+            let rest = rest.trim_start_matches(|chr| chr == ' ' || chr == '\t');
+            rest.starts_with('\n') || rest.starts_with("\r\n")
+        },
+        _ => false
+    }
+}
@@ -0,0 +1,112 @@
+use alloc::{
+    borrow::Cow,
+    str::CharIndices
+};
+
+use crate::{MultiEscapeHandler, EscapeErrorKind, Replacement, consume, skip_bytes, parse_digits, to_char};
+
+#[cfg(feature = "html-entities")]
+mod entities;
+
+/// An escape handler for XML and HTML character references, like `&amp;`, `&#65;`, and `&#x41;`.
+///
+/// The following references are valid:
+/// - `&#DDD;` -> the character with that decimal codepoint
+/// - `&#xHHH;` and `&#XHHH;` -> the character with that hexadecimal codepoint
+/// - `&amp;` -> `&`
+/// - `&lt;` -> `<`
+/// - `&gt;` -> `>`
+/// - `&quot;` -> `"`
+/// - `&apos;` -> `'`
+///
+/// With the `html-entities` feature, every named character reference in HTML is valid too, like `&eacute;` or `&NotEqualTilde;`.
+///
+/// References start with `&` instead of `\`, so this handler relies on the escape character being `&`,
+/// through [`crate::UnescapeExt::to_unescaped_with_escape_char`].
+/// Every reference has to end in `;`.
+///
+/// # Errors
+/// A reference that isn't closed before the string ends is [`EscapeErrorKind::Truncated`].
+/// A numeric reference with a bad digit is [`EscapeErrorKind::InvalidHexDigit`] or [`EscapeErrorKind::InvalidDecimalDigit`],
+/// and a name that isn't known, or isn't followed by `;`, is [`EscapeErrorKind::UnknownName`].
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, XmlHandler};
+/// let unescaped = "&lt;a href=&quot;x&#x3F;y&#61;1&amp;z&quot;&gt;".to_unescaped_with_escape_char('&', XmlHandler);
+/// assert_eq!(unescaped.unwrap(), "<a href=\"x?y=1&z\">");
+///
+/// "fish &chips".to_unescaped_with_escape_char('&', XmlHandler).expect_err("there's no ;");
+/// ```
+///
+pub struct XmlHandler;
+
+impl MultiEscapeHandler for XmlHandler {
+    fn escape_multi<'source>(&mut self, _: usize, chr: char, iter: &mut CharIndices<'source>) -> Result<Option<Replacement<'source>>, EscapeErrorKind> {
+        if chr == '#' {
+            return consume(iter, unescape_numeric).map(|chr| Some(Replacement::Char(chr)));
+        }
+        if !chr.is_ascii_alphanumeric() {
+            return Err(EscapeErrorKind::UnknownEscape(chr));
+        }
+        // The name starts with the character after the `&`, which is already consumed
+        let rest = iter.as_str();
+        let len = rest.find(|chr: char| !chr.is_ascii_alphanumeric()).unwrap_or(rest.len());
+        let terminator = rest[len ..].chars().next();
+        skip_bytes(iter, len + usize::from(terminator == Some(';')));
+        match terminator {
+            None => Err(EscapeErrorKind::Truncated),
+            Some(';') => lookup(chr, &rest[.. len])
+                .map(|value| Some(Replacement::Str(Cow::Borrowed(value))))
+                .ok_or(EscapeErrorKind::UnknownName),
+            Some(_) => Err(EscapeErrorKind::UnknownName)
+        }
+    }
+}
+
+/// Parses the part of a numeric character reference after the `#`, up to and including the `;`,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn unescape_numeric(
+    string: &str
+) -> (Result<char, EscapeErrorKind>, usize) {
+    let (radix, prefix, invalid) = if string.starts_with('x') || string.starts_with('X') {
+        (16, 1, EscapeErrorKind::InvalidHexDigit)
+    } else {
+        (10, 0, EscapeErrorKind::InvalidDecimalDigit)
+    };
+    let digits = &string[prefix ..];
+    let len = digits.find(|chr: char| !chr.is_digit(radix)).unwrap_or(digits.len());
+    match digits[len ..].chars().next() {
+        None => (Err(EscapeErrorKind::Truncated), string.len()),
+        Some(';') if len > 0 => {
+            // Every digit was checked above, so all that's left to go wrong is the value being too big,
+            // which saturates to a codepoint that isn't valid
+            let codepoint = parse_digits(&digits.as_bytes()[.. len], radix).unwrap_or(u32::MAX);
+            (to_char(codepoint), prefix + len + 1)
+        },
+        Some(_) => (Err(invalid), prefix + len)
+    }
+}
+
+/// Finds the value of the entity whose name is `first` followed by `rest`.
+#[cfg(feature = "html-entities")]
+fn lookup(first: char, rest: &str) -> Option<&'static str> {
+    let mut name = [0; 4];
+    let name = first.encode_utf8(&mut name).as_bytes().iter().chain(rest.as_bytes());
+    entities::ENTITIES.binary_search_by(|(candidate, _)| candidate.as_bytes().iter().cmp(name.clone()))
+        .ok()
+        .map(|idx| entities::ENTITIES[idx].1)
+}
+
+/// Finds the value of the entity whose name is `first` followed by `rest`.
+#[cfg(not(feature = "html-entities"))]
+fn lookup(first: char, rest: &str) -> Option<&'static str> {
+    Some( match (first, rest) {
+        ('a', "mp") => "&",
+        ('l', "t") => "<",
+        ('g', "t") => ">",
+        ('q', "uot") => "\"",
+        ('a', "pos") => "'",
+        _ => return None
+    } )
+}
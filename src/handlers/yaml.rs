@@ -0,0 +1,90 @@
+use core::str::CharIndices;
+
+use crate::{EscapeHandler, EscapeErrorKind, skip_bytes, consume, parse_fixed, unescape_hex, unescape_unicode_long};
+
+/// An escape handler following the rules of YAML double-quoted scalars, as defined in [YAML 1.2](https://yaml.org/spec/1.2.2/#57-escaped-characters).
+///
+/// The following escapes are valid:
+/// - `\\0` -> `\0`
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\t` and a backslash followed by a tab -> `\x09`
+/// - `\\n` -> `\x0A`
+/// - `\\v` -> `\x0B`
+/// - `\\f` -> `\x0C`
+/// - `\\r` -> `\x0D`
+/// - `\\e` -> `\x1B`
+/// - A backslash followed by a space -> ` `
+/// - `\\"` -> `"`
+/// - `\\/` -> `/`
+/// - `\\\\` -> `\\`
+/// - `\\N` -> `\u{85}`, a next line character
+/// - `\\_` -> `\u{A0}`, a non-breaking space
+/// - `\\L` -> `\u{2028}`, a line separator
+/// - `\\P` -> `\u{2029}`, a paragraph separator
+/// - `\\xXX` -> `\u{XX}`
+/// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\UXXXXXXXX` -> `\u{XXXXXXXX}`
+///
+/// A backslash at the end of a line is removed, along with the newline and any spaces or tabs at the start of the next line.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{UnescapeExt, YamlHandler};
+/// let unescaped = r"100\_km\L\ \x41".to_unescaped_with(YamlHandler);
+/// assert_eq!(unescaped.unwrap(), "100\u{A0}km\u{2028} A");
+///
+/// r"\q".to_unescaped_with(YamlHandler).expect_err("YAML has no such escape");
+/// # }
+/// ```
+///
+pub struct YamlHandler;
+
+impl EscapeHandler for YamlHandler {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        Ok(Some( match chr {
+            '0' => '\0',
+            'a' => '\x07',
+            'b' => '\x08',
+            't' | '\t' => '\t',
+            'n' => '\n',
+            'v' => '\x0B',
+            'f' => '\x0C',
+            'r' => '\r',
+            'e' => '\x1B',
+            ' ' => ' ',
+            '"' => '"',
+            '/' => '/',
+            '\\' => '\\',
+            'N' => '\u{85}',
+            '_' => '\u{A0}',
+            'L' => '\u{2028}',
+            'P' => '\u{2029}',
+            'x' => consume(iter, unescape_hex)?,
+            'u' => consume(iter, |rest| parse_fixed(rest, 4))?,
+            'U' => consume(iter, unescape_unicode_long)?,
+            '\r' if iter.as_str().starts_with('\n') => {
+                iter.next();
+                skip_indent(iter);
+                return Ok(None);
+            },
+            '\n' => {
+                skip_indent(iter);
+                return Ok(None);
+            },
+            c => return Err(EscapeErrorKind::UnknownEscape(c))
+        } ))
+    }
+}
+
+/// Skips the spaces and tabs at the start of the line after an escaped line break.
+fn skip_indent(iter: &mut CharIndices) {
+    let rest = iter.as_str();
+    let skip = rest.len() - rest.trim_start_matches(|chr| chr == ' ' || chr == '\t').len();
+    skip_bytes(iter, skip);
+}
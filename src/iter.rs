@@ -0,0 +1,142 @@
+//! Streaming unescaping, one character at a time.
+
+use core::iter::FusedIterator;
+use core::ops::Range;
+use core::str::CharIndices;
+
+use crate::{DefaultHandler, EscapeHandler, EscapeErrorKind, InvalidEscape};
+
+/// An iterator over the unescaped characters of a string.
+///
+/// Created by [`crate::UnescapeExt::unescape_chars`] and [`crate::UnescapeExt::unescape_chars_with`].
+///
+/// Only [`EscapeHandler`]s can be used, as every escape sequence has to map to at most one character.
+/// Escape sequences that the handler removes are skipped over.
+///
+/// After the first invalid escape sequence, the error is yielded, and the iterator stops.
+///
+/// # Examples
+/// ```rust
+/// # use descape::UnescapeExt;
+/// let mut chars = r"a\nb\q".unescape_chars();
+/// assert_eq!(chars.next(), Some(Ok('a')));
+/// assert_eq!(chars.next(), Some(Ok('\n')));
+/// assert_eq!(chars.next(), Some(Ok('b')));
+/// assert_eq!(chars.next().unwrap().unwrap_err().index, 4);
+/// assert_eq!(chars.next(), None);
+/// ```
+pub struct Unescape<'source, H = DefaultHandler> {
+    source: &'source str,
+    iter: CharIndices<'source>,
+    handler: H,
+    done: bool,
+}
+
+impl<'source, H> Unescape<'source, H> {
+    pub(crate) fn new(source: &'source str, handler: H) -> Self {
+        Self {
+            source,
+            iter: source.char_indices(),
+            handler,
+            done: false,
+        }
+    }
+
+    /// Returns the part of the source string that hasn't been unescaped yet.
+    #[must_use]
+    pub fn as_str(&self) -> &'source str {
+        if self.done { "" } else { self.iter.as_str() }
+    }
+}
+
+impl<H: EscapeHandler> Unescape<'_, H> {
+    fn next_spanned(&mut self) -> Option<Result<(Range<usize>, char), InvalidEscape>> {
+        if self.done {
+            return None;
+        }
+        while let Some((index, chr)) = self.iter.next() {
+            if chr != '\\' {
+                return Some(Ok((index .. index + chr.len_utf8(), chr)));
+            }
+            let res = match self.iter.next() {
+                Some((_, chr)) => self.handler.escape_detailed(index, chr, &mut self.iter),
+                None => Err(EscapeErrorKind::Truncated)
+            };
+            let end = self.source.len() - self.iter.as_str().len();
+            match res {
+                Ok(Some(chr)) => return Some(Ok((index .. end, chr))),
+                Ok(None) => {}
+                Err(kind) => {
+                    self.done = true;
+                    return Some(Err(InvalidEscape::with_kind(index, kind).with_len(end - index)));
+                }
+            }
+        }
+        self.done = true;
+        None
+    }
+}
+
+impl<H: EscapeHandler> Iterator for Unescape<'_, H> {
+    type Item = Result<char, InvalidEscape>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_spanned().map(|res| res.map(|(_, chr)| chr))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.done {
+            return (0, Some(0));
+        }
+        // Every escape sequence is at least two characters, and can be removed entirely
+        (0, Some(self.iter.as_str().len()))
+    }
+}
+
+impl<H: EscapeHandler> FusedIterator for Unescape<'_, H> {}
+
+/// An iterator over the unescaped characters of a string, along with the byte range of the source each came from.
+///
+/// Created by [`crate::UnescapeExt::unescape_spans`].
+///
+/// A literal character's range covers just that character, and an escape sequence's range covers the whole sequence.
+/// Escape sequences that the handler removes are skipped over, and aren't part of any range.
+///
+/// After the first invalid escape sequence, the error is yielded, and the iterator stops.
+///
+/// # Examples
+/// ```rust
+/// # use descape::UnescapeExt;
+/// let mut spans = r"a\u{1F600}é".unescape_spans();
+/// assert_eq!(spans.next(), Some(Ok((0..1, 'a'))));
+/// assert_eq!(spans.next(), Some(Ok((1..10, '\u{1F600}'))));
+/// assert_eq!(spans.next(), Some(Ok((10..12, 'é'))));
+/// assert_eq!(spans.next(), None);
+/// ```
+pub struct UnescapeSpans<'source, H = DefaultHandler>(Unescape<'source, H>);
+
+impl<'source, H> UnescapeSpans<'source, H> {
+    pub(crate) fn new(source: &'source str, handler: H) -> Self {
+        Self(Unescape::new(source, handler))
+    }
+
+    /// Returns the part of the source string that hasn't been unescaped yet.
+    #[must_use]
+    pub fn as_str(&self) -> &'source str {
+        self.0.as_str()
+    }
+}
+
+impl<H: EscapeHandler> Iterator for UnescapeSpans<'_, H> {
+    type Item = Result<(Range<usize>, char), InvalidEscape>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next_spanned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+impl<H: EscapeHandler> FusedIterator for UnescapeSpans<'_, H> {}
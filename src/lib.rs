@@ -29,16 +29,40 @@ This library supports many escape sequences:
 - `\\oo` -> `\oo`, for all octal digits `o`
 - `\\ooo` -> `\ooo`, for all octal digits `o`
 - `\\uXXXX` -> `\u{XXXX}`
+- `\\UXXXXXXXX` -> `\u{XXXXXXXX}`
 - `\\u{HEX}` -> `\u{HEX}`
 
 Along with this, you can define your own custom escape handlers! See [`UnescapeExt::to_unescaped_with`] for more information on that.
 
+The same is available without importing a trait through [`unescape`] and [`unescape_with`].
+Alternatively, [`prelude`] imports all of the traits and handlers at once.
+
+Ready-made handlers for other formats are included too, like [`JsonHandler`], [`RustHandler`], [`CHandler`], [`ShellHandler`], and [`PythonStrHandler`], with [`PythonBytesHandler`] for byte strings.
+Line continuations can be added on top of any handler with [`ContinuationHandler`].
+
+The sub-parsers these handlers are built from are available in [`scan`], for writing your own.
+
+Byte strings can be unescaped too via [`UnescapeBytesExt`], where `\\xNN` and octal escapes produce raw bytes.
+
+Going the other way, [`EscapeExt`] turns raw strings back into escaped ones.
+
 This crate supports `no-std`.
+Everything that builds a new string needs an allocator, through the `alloc` feature, which is on by default.
+Without it, [`UnescapeExt::unescape_chars`] and [`UnescapeExt::unescape_to_fmt`] still work with any [`EscapeHandler`],
+so the crate can be used with no heap at all.
 
 Optionally, this crate has the `std` and `core_error` features, 
 to allow the error type of an invalid escape to implement the `Error` trait.
 
 `std` uses `std::error::Error`, and `core_error` depends on `core::error::Error`, which is stable on Rust 1.82.0 or greater.
+*/
+// The reader only exists with `std`, so there's nothing to link to without it
+#![cfg_attr(feature = "std", doc = "`std` also adds [`unescape_reader`], for unescaping a stream without reading all of it into memory first.")]
+#![cfg_attr(not(feature = "std"), doc = "`std` also adds `unescape_reader`, for unescaping a stream without reading all of it into memory first.")]
+/*!
+
+The `unicode-names` feature adds a table of Unicode character names, for `\N{NAME}` escapes in [`PythonStrHandler`] and [`NamedUnicodeHandler`],
+and the `html-entities` feature adds a table of every named character reference in HTML, for [`XmlHandler`].
 
 */
 
@@ -50,51 +74,292 @@ use std::error::Error as ErrorTrait;
 #[cfg(all(feature = "core_error", not(feature = "std")))]
 use core::error::Error as ErrorTrait;
 
+#[cfg(feature = "alloc")]
 extern crate alloc;
 
+#[cfg(feature = "alloc")]
 use alloc::{
     borrow::Cow,
-    string::{
-        String,
-        ToString
-    },
-    str::CharIndices
+    string::String
 };
+#[cfg(feature = "alloc")]
+use core::ops::Range;
+use core::str::CharIndices;
+
+#[cfg(feature = "alloc")]
+mod escape;
+#[cfg(feature = "alloc")]
+pub use escape::{EscapeExt, Escaper, DefaultEscaper, ShortestEscaper, RustEscaper, JsonEscaper, AsciiEscaper};
+#[cfg(feature = "alloc")]
+mod bytes;
+#[cfg(feature = "alloc")]
+pub use bytes::{UnescapeBytesExt, ByteEscapeHandler, ByteIndices};
+mod unescaper;
+pub use unescaper::{Unescaper, Escapes, UnknownEscapePolicy, TrailingPolicy, OctalMode, HexMode};
+mod handlers;
+pub use handlers::{OrElse, MapHandler, IdentityHandler, DropHandler, MapOrDefault, NamedUnicodeHandler, JsonHandler, GraphQLHandler, JavaHandler, KotlinHandler, JsHandler, RustHandler, CHandler, CSharpHandler, GoHandler, LuaHandler, HaskellHandler, OCamlHandler, ElixirHandler, ShellHandler, PowerShellHandler, SqlAnsiHandler, PostgresEStringHandler, CsvHandler, TomlHandler, YamlHandler};
+#[cfg(feature = "alloc")]
+pub use handlers::{ContinuationHandler, ResilientHandler, GraphQLBlockHandler, PercentHandler, PythonStrHandler, PythonBytesHandler, RubyHandler, PhpHandler, XmlHandler};
+mod iter;
+pub use iter::{Unescape, UnescapeSpans};
+#[cfg(feature = "alloc")]
+mod unescaped;
+#[cfg(feature = "alloc")]
+pub use unescaped::{Unescaped, UnescapedString};
+#[cfg(feature = "unicode-names")]
+mod names;
+#[cfg(any(feature = "std", docsrs))]
+mod reader;
+#[cfg(any(feature = "std", docsrs))]
+pub use reader::unescape_reader;
+pub mod prelude;
+pub mod scan;
+use scan::consume;
 
 mod sealed {
+    #[cfg(feature = "alloc")]
+    use alloc::{borrow::Cow, string::String};
+
     pub trait Sealed {}
     impl Sealed for str {}
+    impl Sealed for [u8] {}
+    #[cfg(feature = "alloc")]
+    impl Sealed for String {}
+    #[cfg(feature = "alloc")]
+    impl Sealed for Cow<'_, str> {}
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Default, Hash)]
 /// An error representing an invalid escape sequence in a string.
+///
+/// Errors from this crate always lie within the string they came from,
+/// so `&source[err.index .. err.end()]` never panics, even for strings that aren't escaped properly.
 pub struct InvalidEscape {
     /// The index of the invalid escape sequence.
     pub index: usize,
+    /// The length of the invalid escape sequence in bytes, starting from the backslash.
+    ///
+    /// This covers everything the escape handler consumed before rejecting the sequence.
+    pub len: usize,
+    /// Why the escape sequence was invalid.
+    pub kind: EscapeErrorKind,
+    /// The character after the backslash, if it's known to be the problem.
+    ///
+    /// This is set for [`EscapeErrorKind::UnknownEscape`] errors, and `None` otherwise,
+    /// like when the string ended right after the backslash.
+    pub chr: Option<char>,
 }
 
 impl InvalidEscape {
     /// Constructs an invalid escape error from an index.
+    ///
+    /// The kind of the error is [`EscapeErrorKind::Rejected`], and its length is `0`.
     #[must_use]
     pub const fn new(index: usize) -> Self {
-        Self { index }
+        Self::with_kind(index, EscapeErrorKind::Rejected)
+    }
+
+    /// Constructs an invalid escape error from an index and the reason it's invalid.
+    ///
+    /// The length of the error is `0`.
+    /// The offending character is taken from the kind, if it's [`EscapeErrorKind::UnknownEscape`].
+    #[must_use]
+    pub const fn with_kind(index: usize, kind: EscapeErrorKind) -> Self {
+        let chr = match kind {
+            EscapeErrorKind::UnknownEscape(chr) => Some(chr),
+            _ => None
+        };
+        Self { index, len: 0, kind, chr }
+    }
+
+    /// Sets the length of the invalid escape sequence in bytes.
+    #[must_use]
+    pub const fn with_len(self, len: usize) -> Self {
+        Self { len, ..self }
+    }
+
+    /// Sets the character after the backslash that made the escape sequence invalid.
+    #[must_use]
+    pub const fn with_chr(self, chr: Option<char>) -> Self {
+        Self { chr, ..self }
+    }
+
+    /// Returns the index one past the end of the invalid escape sequence.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::UnescapeExt;
+    /// let source = r"Uh oh! \u{ZZZ} and more";
+    /// let err = source.to_unescaped().unwrap_err();
+    /// assert_eq!(&source[err.index .. err.end()], r"\u{ZZZ}");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn end(&self) -> usize {
+        self.index + self.len
+    }
+
+    /// Finds the 1-based line and column of the invalid escape sequence in the string it came from.
+    ///
+    /// Lines are separated by `\n`, and columns are counted in characters, not bytes.
+    /// An index past the end of `source` is treated as the end of it.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::UnescapeExt;
+    /// let source = "first line\nsecond, with caf\u{E9} \\q";
+    /// let err = source.to_unescaped().unwrap_err();
+    /// assert_eq!(err.line_col(source), (2, 19));
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn line_col(&self, source: &str) -> (usize, usize) {
+        let before = &source.as_bytes()[.. self.index.min(source.len())];
+        let (line_start, line) = before.iter()
+            .enumerate()
+            .filter(|(_, &byte)| byte == b'\n')
+            .fold((0, 1), |(_, line), (idx, _)| (idx + 1, line + 1));
+        // Every character has exactly one byte that isn't a continuation byte
+        let column = before[line_start ..].iter()
+            .filter(|&&byte| byte & 0b1100_0000 != 0b1000_0000)
+            .count();
+        (line, column + 1)
     }
 }
 
 impl core::fmt::Display for InvalidEscape {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        write!(f, "invalid escape sequence at index {}", self.index)?;
+        match self.chr {
+            // This assumes the escape character was a backslash, which it usually is
+            Some(chr) => write!(f, "invalid escape sequence `\\{}` at index {}: {}", chr, self.index, self.kind)?,
+            None => write!(f, "invalid escape sequence at index {}: {}", self.index, self.kind)?,
+        }
         Ok(())
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[non_exhaustive]
+/// The reason an escape sequence was invalid.
+pub enum EscapeErrorKind {
+    /// The character after the backslash doesn't start any known escape sequence.
+    UnknownEscape(char),
+    /// The string ended before the escape sequence did.
+    Truncated,
+    /// A numeric escape sequence had a character that wasn't a hexadecimal digit.
+    InvalidHexDigit,
+    /// A numeric escape sequence had a character that wasn't a decimal digit.
+    InvalidDecimalDigit,
+    /// A numeric escape sequence had a character that wasn't an octal digit.
+    InvalidOctalDigit,
+    /// A numeric escape sequence's value is higher than the highest Unicode scalar value.
+    InvalidCodepoint(u32),
+    /// A numeric escape sequence's value is a UTF-16 surrogate, which isn't a Unicode scalar value on its own.
+    LoneSurrogate(u32),
+    /// A numeric escape sequence's value is outside of the range allowed for it,
+    /// like an octal escape that doesn't fit in a byte.
+    OutOfRange(u32),
+    /// The string ended before the delimiter that should close it.
+    Unterminated,
+    /// A `\N{NAME}` escape sequence didn't name a known character, or wasn't closed.
+    UnknownName,
+    /// An escape handler rejected the sequence without giving a reason.
+    Rejected,
+    /// The unescaped string would have gotten longer than allowed,
+    /// as set by [`crate::Unescaper::max_output_len`].
+    OutputTooLong,
+    /// The range of the string to unescape is out of bounds, backwards, or doesn't lie on character boundaries,
+    /// as given to [`UnescapeExt::to_unescaped_range`].
+    InvalidRange,
+    /// The input to [`escaped_string`] didn't start with the opening quote.
+    MissingQuote,
+}
+
+impl Default for EscapeErrorKind {
+    fn default() -> Self {
+        Self::Rejected
+    }
+}
+
+impl core::fmt::Display for EscapeErrorKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnknownEscape(chr) => write!(f, "unknown escape character {:?}", chr),
+            Self::Truncated => write!(f, "string ended in the middle of the escape"),
+            Self::InvalidHexDigit => write!(f, "invalid hexadecimal digit"),
+            Self::InvalidDecimalDigit => write!(f, "invalid decimal digit"),
+            Self::InvalidOctalDigit => write!(f, "invalid octal digit"),
+            Self::InvalidCodepoint(codepoint) => write!(f, "{:#X} is not a valid codepoint", codepoint),
+            Self::LoneSurrogate(codepoint) => write!(f, "{:#X} is a lone surrogate", codepoint),
+            Self::OutOfRange(value) => write!(f, "{} is out of range for this escape", value),
+            Self::Unterminated => write!(f, "string ended before the closing delimiter"),
+            Self::UnknownName => write!(f, "unknown character name"),
+            Self::Rejected => write!(f, "rejected by the escape handler"),
+            Self::OutputTooLong => write!(f, "unescaped string is longer than allowed"),
+            Self::InvalidRange => write!(f, "range is not a valid part of the string"),
+            Self::MissingQuote => write!(f, "string did not start with the opening quote"),
+        }
+    }
+}
+
 #[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "core_error"))))]
 #[cfg(any(feature = "std", feature = "core_error", docsrs))]
 impl ErrorTrait for InvalidEscape {}
 
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+#[cfg(any(feature = "std", docsrs))]
+impl From<InvalidEscape> for std::io::Error {
+    /// Wraps the error as [`std::io::ErrorKind::InvalidData`], keeping its message.
+    fn from(err: InvalidEscape) -> Self {
+        Self::new(std::io::ErrorKind::InvalidData, err)
+    }
+}
+
+/// An error from unescaping into a [`core::fmt::Write`] with [`UnescapeExt::unescape_to_fmt`].
+///
+/// As [`core::fmt::Write`] always fails with [`core::fmt::Error`], which says nothing about what went wrong,
+/// this isn't generic over the writer's error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnescapeFmtError {
+    /// There was an invalid escape sequence in the string.
+    Invalid(InvalidEscape),
+    /// Writing to the output failed.
+    Write(core::fmt::Error),
+}
+
+impl From<InvalidEscape> for UnescapeFmtError {
+    fn from(err: InvalidEscape) -> Self {
+        Self::Invalid(err)
+    }
+}
+
+impl From<core::fmt::Error> for UnescapeFmtError {
+    fn from(err: core::fmt::Error) -> Self {
+        Self::Write(err)
+    }
+}
+
+impl core::fmt::Display for UnescapeFmtError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Invalid(err) => write!(f, "{}", err),
+            Self::Write(_) => write!(f, "failed to write the unescaped string"),
+        }
+    }
+}
+
+#[cfg_attr(docsrs, doc(cfg(any(feature = "std", feature = "core_error"))))]
+#[cfg(any(feature = "std", feature = "core_error", docsrs))]
+impl ErrorTrait for UnescapeFmtError {}
+
 /// A trait distinguishing an object as a handler for custom escape sequences.
 /// 
 /// For convenience, this trait is **automatically implemented** for all implementors of `FnMut` with the correct signature.
+///
+/// This trait is object-safe, and will stay that way, so handlers can be picked at runtime through
+/// `&mut dyn EscapeHandler` or [`BoxHandler`]. The same goes for every other handler trait in this crate.
 /// 
 pub trait EscapeHandler {
     /// Definition of a custom escape handler.
@@ -104,43 +369,56 @@ pub trait EscapeHandler {
     /// - `idx`: The index of the current character (e.g. `Hello\nthere` gets `5`)
     /// - `chr`: The current character in the string (e.g. `\\n` gets `'n'`)
     /// - `iter`: A mutable reference to the underlying character iterator -
-    ///     use this to get the rest of the string via `CharIndices::as_str`,
-    ///     or get the next characters
+    ///   use this to get the rest of the string via `CharIndices::as_str`,
+    ///   or get the next characters
+    /// 
+    /// `chr` always comes directly after the escape character, so it's at `idx + 1` for a backslash.
+    /// `iter` starts directly after `chr`, and the indices it yields are in the same string as `idx`.
+    /// To find where a character the handler consumed is in any case, count how many bytes of `iter.as_str()` came before it,
+    /// like in the example below.
     /// 
     /// Handlers return a `Result<Option<char>, ()>`.
     /// Returning `Ok(Some(char))` replaces the sequence with the given character,
     /// returning `Ok(None)` removes the sequence entirely,
     /// and returning `Err` errors the unescaping at the index of the escape sequence.
+    /// The error spans everything the handler consumed from `iter` before returning.
     /// 
     /// 
     /// # Examples
-
+    ///
     /// ## Permitting any escape, handing it back raw
     /// ```rust
+    /// # #[cfg(feature = "alloc")] {
     /// # use descape::UnescapeExt; use std::str::CharIndices;
     /// fn raw(idx: usize, chr: char, _: &mut CharIndices) -> Result<Option<char>, ()> {
     ///     Ok(Some(chr))
     /// }
-    
+    ///
     /// let escaped = r"\H\e\l\l\o \n \W\o\r\l\d";
     /// let unescaped = escaped.to_unescaped_with(raw).expect("this is fine");
     /// assert_eq!(unescaped, "Hello n World");
+    /// # }
     /// ```
-
+    /// This handler is also built in as [`crate::IdentityHandler`].
+    ///
     /// ## Removing escape sequences entirely
     /// ```rust
+    /// # #[cfg(feature = "alloc")] {
     /// # use descape::UnescapeExt; use std::str::CharIndices;
     /// fn raw(idx: usize, chr: char, _: &mut CharIndices) -> Result<Option<char>, ()> {
     ///     Ok(None)
     /// }
-
+    ///
     /// let escaped = r"What if I want a \nnewline?";
     /// let unescaped = escaped.to_unescaped_with(raw).expect("this should work");
     /// assert_eq!(unescaped, "What if I want a newline?");
+    /// # }
     /// ```
-
+    /// This handler is also built in as [`crate::DropHandler`].
+    ///
     /// ## Not allowing escape sequences unsupported by Rust
     /// ```rust
+    /// # #[cfg(feature = "alloc")] {
     /// # use descape::{UnescapeExt, EscapeHandler}; use std::str::CharIndices;
     /// fn rust_only(idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
     ///     match chr {
@@ -148,17 +426,132 @@ pub trait EscapeHandler {
     ///         _ => descape::DefaultHandler.escape(idx, chr, iter)
     ///     }
     /// }
-    
+    ///
     /// r"This is \nfine".to_unescaped_with(rust_only).expect(r"\n is valid");
     /// r"This is not \fine".to_unescaped_with(rust_only).expect_err(r"\f is invalid");
+    /// # }
     /// ```
-    
+    ///
+    /// ## Reporting exactly where a sequence went wrong
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::UnescapeExt; use std::str::CharIndices;
+    /// let mut bad_digit = None;
+    /// let mut digits_only = |idx: usize, chr: char, iter: &mut CharIndices| {
+    ///     let start = iter.as_str();
+    ///     let mut value = 0;
+    ///     for (_, digit) in iter.take(chr.to_digit(10).ok_or(())? as usize) {
+    ///         match digit.to_digit(10) {
+    ///             Some(digit) => value = value * 10 + digit,
+    ///             None => {
+    ///                 // Where the digit starts, counted from the start of `iter.as_str()`
+    ///                 let offset = start.len() - iter.as_str().len() - digit.len_utf8();
+    ///                 bad_digit = Some(idx + 1 + chr.len_utf8() + offset);
+    ///                 return Err(());
+    ///             }
+    ///         }
+    ///     }
+    ///     std::char::from_u32(value).map(Some).ok_or(())
+    /// };
+    ///
+    /// assert_eq!(r"\265\3066".to_unescaped_with(&mut digits_only).unwrap(), "AB");
+    /// r"ab \312é4".to_unescaped_with(&mut digits_only).unwrap_err();
+    /// assert_eq!(bad_digit, Some(7));
+    /// # }
+    /// ```
+    ///
     /// # An informal note
     /// Ideally, this trait would return `Result<Option<char>, Option<Box<dyn Error>>>`, but `Error` has only been in `core`
     /// since Rust version `1.82.0`. Using it would bump the MSRV by a tremendous amount,
     /// and as such it has been left out.
+    /// To report why a sequence is invalid, override [`EscapeHandler::escape_detailed`] instead.
     #[allow(clippy::result_unit_err, clippy::missing_errors_doc)]
     fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, ()>;
+
+    /// Like [`EscapeHandler::escape`], but reports why an escape sequence is invalid.
+    ///
+    /// This is what's called while unescaping.
+    /// The default implementation forwards to [`EscapeHandler::escape`],
+    /// reporting any error as [`EscapeErrorKind::Rejected`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{UnescapeExt, EscapeHandler, EscapeErrorKind}; use std::str::CharIndices;
+    /// struct NoBell;
+    ///
+    /// impl EscapeHandler for NoBell {
+    ///     fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+    ///         self.escape_detailed(idx, chr, iter).map_err(drop)
+    ///     }
+    ///
+    ///     fn escape_detailed(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+    ///         match chr {
+    ///             'a' => Err(EscapeErrorKind::UnknownEscape(chr)),
+    ///             _ => descape::DefaultHandler.escape_detailed(idx, chr, iter)
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(
+    ///     r"\a".to_unescaped_with(NoBell).unwrap_err().kind,
+    ///     EscapeErrorKind::UnknownEscape('a')
+    /// );
+    /// # }
+    /// ```
+    #[allow(clippy::missing_errors_doc)]
+    fn escape_detailed(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+        self.escape(idx, chr, iter).map_err(|()| EscapeErrorKind::Rejected)
+    }
+
+    /// Tries this handler first, falling back to `other` if it rejects a sequence.
+    ///
+    /// The iterator is rewound before calling `other`, so it doesn't matter how much this handler consumed.
+    /// See [`crate::OrElse`] for more details.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{UnescapeExt, EscapeHandler, DefaultHandler}; use std::str::CharIndices;
+    /// let smiley = |_: usize, chr: char, _: &mut CharIndices| if chr == 's' { Ok(Some('☺')) } else { Err(()) };
+    /// let unescaped = r"\s\t".to_unescaped_with(smiley.or_else(DefaultHandler));
+    /// assert_eq!(unescaped.unwrap(), "☺\t");
+    /// # }
+    /// ```
+    fn or_else<H: EscapeHandler>(self, other: H) -> crate::OrElse<Self, H>
+        where Self: Sized
+    {
+        crate::OrElse(self, other)
+    }
+
+    /// Borrows this handler, so it can be used for unescaping without being moved, keeping any state it has.
+    ///
+    /// As `EscapeHandler` is already implemented for all closures, `&mut` closures work without this.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{UnescapeExt, EscapeHandler}; use std::str::CharIndices;
+    /// struct Counter(usize);
+    ///
+    /// impl EscapeHandler for Counter {
+    ///     fn escape(&mut self, _: usize, chr: char, _: &mut CharIndices) -> Result<Option<char>, ()> {
+    ///         self.0 += 1;
+    ///         Ok(Some(chr))
+    ///     }
+    /// }
+    ///
+    /// let mut counter = Counter(0);
+    /// r"\a\b".to_unescaped_with(counter.by_ref()).unwrap();
+    /// r"\c".to_unescaped_with(counter.by_ref()).unwrap();
+    /// assert_eq!(counter.0, 3);
+    /// # }
+    /// ```
+    fn by_ref(&mut self) -> &mut (dyn EscapeHandler + '_)
+        where Self: Sized
+    {
+        self
+    }
 }
 
 impl<F> EscapeHandler for F 
@@ -169,7 +562,266 @@ impl<F> EscapeHandler for F
     }
 }
 
+/// A handler chosen at runtime, like from a configuration option.
+///
+/// This can be passed to [`UnescapeExt::to_unescaped_with`] like any other handler.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{UnescapeExt, BoxHandler, DefaultHandler, JsonHandler};
+/// fn handler(json: bool) -> BoxHandler<'static> {
+///     if json { Box::new(JsonHandler) } else { Box::new(DefaultHandler) }
+/// }
+///
+/// assert_eq!(r"\/".to_unescaped_with(handler(true)).unwrap(), "/");
+/// r"\/".to_unescaped_with(handler(false)).expect_err("only JSON has \\/");
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub type BoxHandler<'handler> = alloc::boxed::Box<dyn EscapeHandler + 'handler>;
+
+// A blanket implementation for `&mut T` would overlap with the one for closures,
+// so only handler objects are covered, through `EscapeHandler::by_ref`
+impl EscapeHandler for &mut (dyn EscapeHandler + '_) {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, ()> {
+        (**self).escape(idx, chr, iter)
+    }
+
+    fn escape_detailed(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+        (**self).escape_detailed(idx, chr, iter)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl EscapeHandler for BoxHandler<'_> {
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, ()> {
+        (**self).escape(idx, chr, iter)
+    }
+
+    fn escape_detailed(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+        (**self).escape_detailed(idx, chr, iter)
+    }
+}
+
+/// What an escape sequence is replaced with by a [`MultiEscapeHandler`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub enum Replacement<'source> {
+    /// Replaces the sequence with a single character.
+    Char(char),
+    /// Replaces the sequence with a whole string.
+    ///
+    /// This can borrow from the string being unescaped, or from a `'static` string.
+    Str(Cow<'source, str>)
+}
+
+#[cfg(feature = "alloc")]
+impl From<char> for Replacement<'_> {
+    fn from(chr: char) -> Self {
+        Self::Char(chr)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'source> From<&'source str> for Replacement<'source> {
+    fn from(string: &'source str) -> Self {
+        Self::Str(Cow::Borrowed(string))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<String> for Replacement<'_> {
+    fn from(string: String) -> Self {
+        Self::Str(Cow::Owned(string))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'source> From<Cow<'source, str>> for Replacement<'source> {
+    fn from(string: Cow<'source, str>) -> Self {
+        Self::Str(string)
+    }
+}
+
+/// A trait distinguishing an object as a handler for custom escape sequences
+/// that may expand into more than one character.
+///
+/// This trait is **automatically implemented** for all implementors of [`EscapeHandler`],
+/// which is more convenient to use when every escape maps to at most one character.
+///
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub trait MultiEscapeHandler {
+    /// Definition of a custom multi-character escape handler.
+    ///
+    /// This takes the same arguments as [`EscapeHandler::escape`].
+    /// Returning `Ok(Some(replacement))` replaces the sequence with the given [`Replacement`],
+    /// returning `Ok(None)` removes the sequence entirely,
+    /// and returning `Err` errors the unescaping at the index of the escape sequence,
+    /// with the given reason.
+    ///
+    /// # Examples
+    ///
+    /// ## Expanding tabs into spaces
+    /// ```rust
+    /// # use descape::{UnescapeExt, EscapeHandler, MultiEscapeHandler, Replacement, EscapeErrorKind}; use std::str::CharIndices;
+    /// struct Spaces;
+    ///
+    /// impl MultiEscapeHandler for Spaces {
+    ///     fn escape_multi<'source>(
+    ///         &mut self, idx: usize, chr: char, iter: &mut CharIndices<'source>
+    ///     ) -> Result<Option<Replacement<'source>>, EscapeErrorKind> {
+    ///         match chr {
+    ///             't' => Ok(Some("    ".into())),
+    ///             _ => descape::DefaultHandler.escape_detailed(idx, chr, iter).map(|chr| chr.map(Replacement::Char))
+    ///         }
+    ///     }
+    /// }
+    ///
+    /// let unescaped = r"\tindented\n".to_unescaped_with(Spaces).expect("this is fine");
+    /// assert_eq!(unescaped, "    indented\n");
+    /// ```
+    #[allow(clippy::missing_errors_doc)]
+    fn escape_multi<'source>(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'source>) -> Result<Option<Replacement<'source>>, EscapeErrorKind>;
+}
+
+#[cfg(feature = "alloc")]
+impl<T> MultiEscapeHandler for T
+    where T: EscapeHandler + ?Sized
+{
+    #[inline]
+    fn escape_multi<'source>(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'source>) -> Result<Option<Replacement<'source>>, EscapeErrorKind> {
+        self.escape_detailed(idx, chr, iter).map(|chr| chr.map(Replacement::Char))
+    }
+}
+
+/// A trait distinguishing an object as a handler for custom escape sequences
+/// that writes its replacements straight into the output.
+///
+/// This is the most general kind of handler, and is **automatically implemented** for all implementors of [`MultiEscapeHandler`],
+/// and so for all implementors of [`EscapeHandler`] as well.
+///
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub trait EscapeWriter {
+    /// Definition of a custom escape writer.
+    ///
+    /// This takes the same arguments as [`EscapeHandler::escape`], along with:
+    /// - `out`: The string to push the replacement for the escape sequence onto
+    ///
+    /// This is usually the unescaped string so far, but not always.
+    /// The first pass of [`UnescapeExt::to_unescaped_exact_with`] and [`UnescapeExt::validate_escapes_with`] don't build the output,
+    /// and neither does [`UnescapeExt::to_unescaped_with`] until an escape sequence changes something,
+    /// so there `out` only holds what's been pushed for the current sequence.
+    /// Handlers shouldn't rely on what's already in `out`; use [`EscapeWriter::max_output_len`] to limit its length.
+    ///
+    /// Returning `Ok(())` keeps whatever was pushed onto `out`, so pushing nothing removes the sequence entirely.
+    /// Returning `Err` errors the unescaping at the index of the escape sequence, with the given reason.
+    ///
+    /// Anything pushed onto `out` before returning `Err` is discarded.
+    ///
+    /// # Examples
+    ///
+    /// ## Dumping characters as hexadecimal
+    /// ```rust
+    /// # use descape::{UnescapeExt, EscapeWriter, EscapeErrorKind}; use std::str::CharIndices; use std::fmt::Write;
+    /// struct HexDump;
+    ///
+    /// impl EscapeWriter for HexDump {
+    ///     fn write_escape(
+    ///         &mut self, _: usize, chr: char, _: &mut CharIndices<'_>, out: &mut String
+    ///     ) -> Result<(), EscapeErrorKind> {
+    ///         let mut buf = [0; 4];
+    ///         for byte in chr.encode_utf8(&mut buf).bytes() {
+    ///             write!(out, "<{:02X}>", byte).unwrap();
+    ///         }
+    ///         Ok(())
+    ///     }
+    /// }
+    ///
+    /// let unescaped = r"caf\é".to_unescaped_with(HexDump).expect("this is fine");
+    /// assert_eq!(unescaped, "caf<C3><A9>");
+    /// ```
+    #[allow(clippy::missing_errors_doc)]
+    fn write_escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind>;
+
+    /// Like [`EscapeWriter::write_escape`], but also given the escape character that started the sequence,
+    /// for handlers that write it back out, like [`Unescaper`] keeping an unknown escape.
+    ///
+    /// This is what unescaping calls, with the character it searched for.
+    /// Defaults to calling [`EscapeWriter::write_escape`], so only handlers that need the character have to override it.
+    #[allow(clippy::missing_errors_doc)]
+    fn write_escape_with(
+        &mut self, escape: char, idx: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String
+    ) -> Result<(), EscapeErrorKind> {
+        let _ = escape;
+        self.write_escape(idx, chr, iter, out)
+    }
+
+    /// What to do with an escape character at the very end of the string, which [`EscapeWriter::write_escape`] is never called for.
+    ///
+    /// Defaults to [`TrailingPolicy::Error`], and is only overridden by [`Unescaper::trailing_backslash`]
+    /// and [`GraphQLBlockHandler`] out of the built-in handlers.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use descape::{UnescapeExt, EscapeWriter, EscapeErrorKind, TrailingPolicy}; use std::str::CharIndices;
+    /// struct Lenient;
+    ///
+    /// impl EscapeWriter for Lenient {
+    ///     fn write_escape(
+    ///         &mut self, _: usize, chr: char, _: &mut CharIndices<'_>, out: &mut String
+    ///     ) -> Result<(), EscapeErrorKind> {
+    ///         out.push(chr);
+    ///         Ok(())
+    ///     }
+    ///
+    ///     fn trailing_backslash(&self) -> TrailingPolicy {
+    ///         TrailingPolicy::Literal
+    ///     }
+    /// }
+    ///
+    /// assert_eq!(r"\a\b\".to_unescaped_with(Lenient).unwrap(), r"ab\");
+    /// ```
+    fn trailing_backslash(&self) -> TrailingPolicy {
+        TrailingPolicy::Error
+    }
+
+    /// The longest the unescaped string can get, in bytes,
+    /// before unescaping errors with [`EscapeErrorKind::OutputTooLong`] at the escape sequence that makes it any longer.
+    ///
+    /// This is checked by the unescaping itself against everything written so far, so unlike checking `out`,
+    /// it still works when the output isn't being built.
+    ///
+    /// Defaults to `usize::MAX`, and is only overridden by [`Unescaper::max_output_len`] out of the built-in handlers.
+    fn max_output_len(&self) -> usize {
+        usize::MAX
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<T> EscapeWriter for T
+    where T: MultiEscapeHandler + ?Sized
+{
+    #[inline]
+    fn write_escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind> {
+        match self.escape_multi(idx, chr, iter)? {
+            Some(Replacement::Char(chr)) => out.push(chr),
+            Some(Replacement::Str(string)) => out.push_str(&string),
+            None => {}
+        }
+        Ok(())
+    }
+}
+
 /// An extension trait for [`&str`](str) to allow parsing escape sequences in strings, only copying when needed.
+///
+/// This is also implemented for [`String`] and [`Cow<str>`](alloc::borrow::Cow),
+/// which unescape the string they hold, borrowing from it when there's nothing to unescape.
+///
+/// Only [`UnescapeExt::unescape_chars`], [`UnescapeExt::unescape_to_fmt`], and [`UnescapeExt::validate_escapes`]
+/// are available without the `alloc` feature.
 pub trait UnescapeExt: sealed::Sealed {
 
     /**
@@ -180,7 +832,7 @@ pub trait UnescapeExt: sealed::Sealed {
 
     # Errors
     Errors if there's an invalid escape sequence in the string.
-    Passes back the byte index of the invalid character.
+    Passes back the byte index of the invalid character, and why it was invalid.
 
     # Examples
     ## Parsing an escaped string
@@ -214,195 +866,1557 @@ pub trait UnescapeExt: sealed::Sealed {
     );
     ```
      */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
     fn to_unescaped(&self) -> Result<Cow<'_, str>, InvalidEscape>;
     /**
-    Unescapes a string using a custom escape handler. See the documentation of [`crate::EscapeHandler`] for more details.
+    Unescapes a string like [`UnescapeExt::to_unescaped`], always returning an owned [`String`].
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+
+    # Examples
+    ```rust
+    # use descape::UnescapeExt;
+    let unescaped: String = r"a\tb".to_unescaped_string().unwrap();
+    assert_eq!(unescaped, "a\tb");
+    ```
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_string(&self) -> Result<String, InvalidEscape>;
+    /**
+    Unescapes only the part of a string in `range`, using [`crate::DefaultHandler`].
+
+    Unlike slicing the string first, the index of an invalid escape sequence
+    is relative to the whole string rather than the start of the range.
+    Escape sequences can't reach outside of the range, so one cut off by its end is [`EscapeErrorKind::Truncated`].
+
+    # Errors
+    Errors if there's an invalid escape sequence in the range.
+    Passes back the byte index of the invalid character in the whole string, and why it was invalid.
+
+    Errors with [`EscapeErrorKind::InvalidRange`] at the offending end of the range
+    if it's out of bounds, backwards, or doesn't lie on character boundaries.
+
+    # Examples
+    ```rust
+    # use descape::{UnescapeExt, EscapeErrorKind};
+    let source = r"caf\u{E9} \t \q";
+    assert_eq!(source.to_unescaped_range(0..9).unwrap(), "caf\u{E9}");
+    assert_eq!(source.to_unescaped_range(9..15).unwrap_err().index, 13);
+    assert_eq!(source.to_unescaped_range(4..14).unwrap_err().kind, EscapeErrorKind::Truncated);
+    assert_eq!("\u{E9}".to_unescaped_range(1..2).unwrap_err().kind, EscapeErrorKind::InvalidRange);
+    ```
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_range(&self, range: Range<usize>) -> Result<Cow<'_, str>, InvalidEscape>;
+    /**
+    Unescapes a string like [`UnescapeExt::to_unescaped`], wrapping the result in an [`Unescaped`],
+    which can be compared with `str`s and printed directly.
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+
+    # Examples
+    ```rust
+    # use descape::UnescapeExt;
+    assert_eq!(r"a\tb".unescape_wrapped().unwrap(), "a\tb");
+    ```
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn unescape_wrapped(&self) -> Result<Unescaped<'_>, InvalidEscape>;
+    /**
+    Unescapes a string using a custom escape handler. See the documentation of [`crate::EscapeHandler`] for more details,
+    [`crate::MultiEscapeHandler`] for handlers that expand escapes into multiple characters,
+    and [`crate::EscapeWriter`] for handlers that write directly to the output.
+
+    Will only allocate for the output once it stops matching the string,
+    so a handler that writes every escape sequence back as it was written still borrows it.
+    Until then, replacements are written to a scratch buffer that's reused for every escape sequence.
 
     # Errors
 
     Errors if there's an invalid escape sequence in the string.
-    Passes back the byte index of the invalid character.
+    Passes back the byte index of the invalid character, and why it was invalid.
 
+    # Examples
+    ```rust
+    # use std::borrow::Cow; use descape::{UnescapeExt, Unescaper, UnknownEscapePolicy};
+    let unescaper = Unescaper::new().unknown_escape(UnknownEscapePolicy::KeepBackslash);
+    assert!(matches!(r"\d+\.\d+".to_unescaped_with(unescaper), Ok(Cow::Borrowed(_))));
+    ```
     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
     fn to_unescaped_with(
         &self,
-        callback: impl EscapeHandler
+        callback: impl EscapeWriter
     ) -> Result<Cow<'_, str>, InvalidEscape>;
-}
+    /**
+    Unescapes a string using a handler object, like [`UnescapeExt::to_unescaped_with`].
 
+    This goes straight to the same non-generic code that every handler ends up in,
+    so nothing is instantiated for the concrete type of the handler behind the reference.
 
-impl UnescapeExt for str {
-    #[inline]
-    fn to_unescaped(&self) -> Result<Cow<str>, InvalidEscape> {
-        self.to_unescaped_with(DefaultHandler)
-    }
+    # Errors
 
-    // Put this outside to prevent monomorphization bloat
-    fn to_unescaped_with(
-        &self, 
-        mut callback: impl EscapeHandler
-    ) -> Result<Cow<str>, InvalidEscape> {
-        to_unescaped_with_mono(self, &mut callback)
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+
+    # Examples
+    ```rust
+    # use descape::{UnescapeExt, EscapeHandler, DefaultHandler, JsonHandler};
+    let mut plugins: Vec<Box<dyn EscapeHandler>> = vec![Box::new(DefaultHandler), Box::new(JsonHandler)];
+    for plugin in &mut plugins {
+        assert_eq!(r"\t".to_unescaped_with_dyn(&mut **plugin).unwrap(), "\t");
     }
-}
+    ```
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_with_dyn(&self, handler: &mut dyn EscapeHandler) -> Result<Cow<'_, str>, InvalidEscape>;
+    /**
+    Unescapes a string `times` times over with the same handler, for strings that were escaped more than once.
 
-fn to_unescaped_with_mono<'this, 'cb>(
-    this: &'this str,
-    callback: &'cb mut dyn EscapeHandler
-) -> Result<Cow<'this, str>, InvalidEscape> {
-    // Iterates over each character as a UTF-8 string slice
-    let mut iter = this.char_indices();
-    let mut seen: &'this str = "";
-    let mut owned = None::<String>;
+    This stops early once a pass doesn't change anything, like when there's nothing left to unescape,
+    and borrows the string if the very first pass didn't change it.
+    The buffers for the passes are reused, so at most two strings are ever allocated.
 
-    while let Some((index, chr)) = iter.next() {
-        if chr != '\\' {
-            if let Some(owned) = &mut owned {
-                owned.push(chr);
-            } else {
-                seen = &this[..index + chr.len_utf8()];
-            }
-            continue;
-        }
-        let owned = owned.get_or_insert_with(|| {
-            let mut string = seen.to_string();
-            string.reserve_exact(this.len() - seen.len());
-            string
-        });
-        if let Some((_, chr)) = iter.next() {
-            if let Some(res) = callback.escape(index, chr, &mut iter)
-                .map_err(|()| InvalidEscape { index })?
-            {
-                owned.push(res);
-                continue;
-            }
-        } else {
-            // No matches found
-            return Err(InvalidEscape::new(owned.len()));
-        }
-    }
+    # Errors
 
-    match owned {
-        Some(string) => Ok(Cow::Owned(string)),
-        None => Ok(Cow::Borrowed(this)),
-    }
-}
+    Errors if there's an invalid escape sequence in any of the passes.
+    The byte index passed back is into the string that pass was given, which was the output of the pass before it,
+    so it's only an index into `self` if the first pass failed.
 
-/// The default escape sequence handler. 
-///
-/// The following escapes are valid:
-/// - `\\a` -> `\x07`
-/// - `\\b` -> `\x08`
-/// - `\\t` -> `\x09`
-/// - `\\n` -> `\x0A`
-/// - `\\v` -> `\x0B`
-/// - `\\f` -> `\x0C`
-/// - `\\r` -> `\x0D`
-/// - `\\e` -> `\x1B`
-/// - `\\'` -> `'`
-/// - `\\"` -> `"`
-/// - <code>&bsol;&bsol;&grave;</code> -> <code>&grave;</code>
-/// - `\\\\` -> `\\`
-/// - `\\xNN` -> `\xNN`
+    # Examples
+    ```rust
+    # use descape::{UnescapeExt, DefaultHandler};
+    let twice = r#"say \\\"hi\\\"\\n"#.to_unescaped_n(2, DefaultHandler);
+    assert_eq!(twice.unwrap(), "say \"hi\"\n");
+
+    // `ab\\q` is `ab\q` after the first pass, which is invalid at index 2
+    let err = r"ab\\q".to_unescaped_n(2, DefaultHandler).unwrap_err();
+    assert_eq!(err.index, 2);
+    ```
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_n(&self, times: usize, callback: impl EscapeWriter) -> Result<Cow<'_, str>, InvalidEscape>;
+    /**
+    Unescapes a string using a custom escape handler, with escape sequences starting with `escape` instead of `\`.
+
+    The handler is called with the character after `escape`, exactly like in [`UnescapeExt::to_unescaped_with`].
+
+    # Errors
+
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+
+    # Examples
+    ```rust
+    # use descape::{UnescapeExt, Unescaper};
+    let unescaped = "100%% ^n".to_unescaped_with_escape_char('^', Unescaper::new());
+    assert_eq!(unescaped.unwrap(), "100%% \n");
+    ```
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_with_escape_char(
+        &self,
+        escape: char,
+        callback: impl EscapeWriter
+    ) -> Result<Cow<'_, str>, InvalidEscape>;
+    /**
+    Unescapes a string up to the first `delim` that isn't part of an escape sequence,
+    returning the unescaped contents before it, along with the rest of the string after it.
+
+    This is meant for lexing string literals, where the string starts right after the opening quote.
+    Escaped delimiters, like `\"`, are given to the handler as usual, and don't end the string.
+    Will only allocate if the contents have any escape sequences.
+
+    # Errors
+
+    Errors if there's an invalid escape sequence before the delimiter,
+    passing back the byte index of the invalid character, and why it was invalid.
+
+    If the string ends before the delimiter, this errors with [`EscapeErrorKind::Unterminated`],
+    with the index being the length of the string.
+
+    # Examples
+    ```rust
+    # use descape::{UnescapeExt, DefaultHandler};
+    let source = r#"say \"hi\"\n", rest = 1"#;
+    let (contents, rest) = source.unescape_until('"', DefaultHandler).unwrap();
+    assert_eq!(contents, "say \"hi\"\n");
+    assert_eq!(rest, ", rest = 1");
+    ```
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn unescape_until(
+        &self,
+        delim: char,
+        callback: impl EscapeWriter
+    ) -> Result<(Cow<'_, str>, &str), InvalidEscape>;
+    /**
+    Like [`UnescapeExt::unescape_until`], but returns how many bytes of the string were consumed,
+    including the delimiter, instead of the rest of the string.
+
+    This is the length of the literal in the source, which is usually longer than the unescaped contents,
+    so a lexer can advance past it.
+
+    # Errors
+
+    Errors exactly like [`UnescapeExt::unescape_until`].
+
+    # Examples
+    ```rust
+    # use descape::{UnescapeExt, DefaultHandler};
+    let source = r#"caf\u{E9}" + 1"#;
+    let (contents, consumed) = source.unescape_until_consumed('"', DefaultHandler).unwrap();
+    assert_eq!(contents, "café");
+    assert_eq!(consumed, 10);
+    assert_eq!(&source[consumed ..], " + 1");
+    ```
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn unescape_until_consumed(
+        &self,
+        delim: char,
+        callback: impl EscapeWriter
+    ) -> Result<(Cow<'_, str>, usize), InvalidEscape>;
+    /**
+    Unescapes a string, appending the result to `out` instead of returning a new string.
+
+    Uses [`crate::DefaultHandler`].
+
+    This lets one buffer be reused for many strings, so unescaping in a loop doesn't allocate
+    once the buffer is big enough. Anything already in `out` is left alone.
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+
+    Everything before the invalid escape sequence is still appended to `out`.
+
+    # Examples
+    ```rust
+    # use descape::UnescapeExt;
+    let mut out = String::new();
+    for line in [r"one\ttwo", r"\x41\x42"].iter() {
+        out.clear();
+        line.unescape_into(&mut out).unwrap();
+    }
+    assert_eq!(out, "AB");
+
+    out.clear();
+    let err = r"ok\n\xJJ then more".unescape_into(&mut out).unwrap_err();
+    assert_eq!(err.index, 4);
+    assert_eq!(out, "ok\n");
+    ```
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    fn unescape_into(&self, out: &mut String) -> Result<(), InvalidEscape>;
+    /**
+    Unescapes a string using a custom escape handler, appending the result to `out`,
+    exactly like [`UnescapeExt::unescape_into`].
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+
+    Everything before the invalid escape sequence is still appended to `out`.
+    */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    fn unescape_into_with(&self, out: &mut String, callback: impl EscapeWriter) -> Result<(), InvalidEscape>;
+    /**
+    Unescapes a string using a custom escape handler, writing the result to any [`core::fmt::Write`].
+
+    Unlike the other methods, this never allocates, so it works with fixed-size buffers,
+    or a [`core::fmt::Formatter`] in a `Display` implementation.
+    Escape handlers that write straight to a [`String`] can't be used here,
+    so this takes an [`EscapeHandler`] instead.
+
+    # Errors
+    Errors with [`UnescapeFmtError::Invalid`] if there's an invalid escape sequence in the string,
+    or [`UnescapeFmtError::Write`] if writing to `out` fails.
+
+    Everything before the invalid escape sequence is still written to `out`.
+
+    # Examples
+    ```rust
+    # use descape::{UnescapeExt, DefaultHandler};
+    use std::fmt;
+
+    struct Unescaped<'a>(&'a str);
+
+    impl fmt::Display for Unescaped<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            self.0.unescape_to_fmt(f, DefaultHandler).map_err(|_| fmt::Error)
+        }
+    }
+
+    assert_eq!(Unescaped(r"\x41 \u{1F600}").to_string(), "A \u{1F600}");
+    ```
+    */
+    fn unescape_to_fmt<W: core::fmt::Write + ?Sized>(
+        &self,
+        out: &mut W,
+        callback: impl EscapeHandler
+    ) -> Result<(), UnescapeFmtError>;
+    /**
+    Unescapes a string, replacing any invalid escape sequences with `U+FFFD REPLACEMENT CHARACTER` instead of erroring.
+    Will only allocate if the string has any escape sequences.
+
+    Uses [`crate::DefaultHandler`].
+
+    # Examples
+    ```rust
+    # use descape::UnescapeExt;
+    let unescaped = r"Uh oh! \xJJ\n".to_unescaped_lossy();
+    assert_eq!(unescaped, "Uh oh! \u{FFFD}\n");
+    ```
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_lossy(&self) -> Cow<'_, str>;
+    /**
+    Unescapes a string using a custom escape handler, never erroring.
+
+    Any invalid escape sequence, including everything the handler consumed before rejecting it,
+    is replaced with `substitute`. If `substitute` is `None`, the sequence is kept as it was written instead.
+    Will only allocate once the output stops matching the string,
+    so a string whose escape sequences are all kept as written is borrowed.
+
+    # Examples
+    ```rust
+    # use descape::{UnescapeExt, DefaultHandler};
+    let source = r"\q \u{D800} \t";
+    assert_eq!(source.to_unescaped_lossy_with(Some('?'), DefaultHandler), "? ? \t");
+    assert_eq!(source.to_unescaped_lossy_with(None, DefaultHandler), "\\q \\u{D800} \t");
+    ```
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_lossy_with(
+        &self,
+        substitute: Option<char>,
+        callback: impl EscapeWriter
+    ) -> Cow<'_, str>;
+    /**
+    Unescapes a string, allocating exactly as much memory as the unescaped string needs.
+
+    Uses [`crate::DefaultHandler`]. See [`UnescapeExt::to_unescaped_exact_with`] for more details.
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+
+    # Examples
+    ```rust
+    # use descape::UnescapeExt;
+    let unescaped = r"\u{1F600}\u{1F600}".to_unescaped_exact().unwrap().into_owned();
+    assert_eq!(unescaped, "😀😀");
+    assert_eq!(unescaped.capacity(), 8);
+    ```
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_exact(&self) -> Result<Cow<'_, str>, InvalidEscape>;
+    /**
+    Unescapes a string using a custom escape handler, allocating exactly as much memory as the unescaped string needs.
+
+    This goes over the string twice: once to find out how long the unescaped string is, and once to build it.
+    That's slower than [`UnescapeExt::to_unescaped_with`], but the output never has to grow,
+    and no memory is wasted when escapes make the string shorter.
+    Invalid escape sequences are found in the first pass, before anything is allocated for the output.
+
+    The handler is called twice for every escape sequence, so it should give the same result both times.
+    The first pass doesn't build the output, so the handler only sees its own replacement in `out` there,
+    and a limit on the output's length should come from [`EscapeWriter::max_output_len`] instead.
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use = "this returns the unescaped string, and doesn't modify the original"]
+    fn to_unescaped_exact_with(
+        &self,
+        callback: impl EscapeWriter
+    ) -> Result<Cow<'_, str>, InvalidEscape>;
+    /**
+    Checks that every escape sequence in a string is valid, without building the unescaped string.
+
+    Uses [`crate::DefaultHandler`].
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+
+    # Examples
+    ```rust
+    # use descape::UnescapeExt;
+    assert!(r"All good!\n".validate_escapes().is_ok());
+    assert_eq!(r"Uh oh! \xJJ".validate_escapes().unwrap_err().index, 7);
+    ```
+     */
+    fn validate_escapes(&self) -> Result<(), InvalidEscape>;
+    /**
+    Checks that every escape sequence in a string is valid for a custom escape handler, without building the unescaped string.
+
+    This takes any [`EscapeWriter`], so it fails for exactly the strings that [`UnescapeExt::to_unescaped_with`] fails for,
+    including a trailing backslash the handler allows with [`EscapeWriter::trailing_backslash`].
+    Each replacement is written to a scratch buffer that's cleared before the next one,
+    so a handler that reads `out` only sees what it's written for the current sequence.
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    fn validate_escapes_with(
+        &self,
+        callback: impl EscapeWriter
+    ) -> Result<(), InvalidEscape>;
+    /**
+    Returns an iterator over the unescaped characters of a string, without allocating.
+
+    Uses [`crate::DefaultHandler`]. See [`crate::Unescape`] for more details.
+
+    # Examples
+    ```rust
+    # use descape::UnescapeExt;
+    let unescaped: Result<Vec<char>, _> = r"a\nb".unescape_chars().collect();
+    assert_eq!(unescaped.unwrap(), ['a', '\n', 'b']);
+    ```
+     */
+    #[must_use = "iterators are lazy, and do nothing unless consumed"]
+    fn unescape_chars(&self) -> Unescape<'_>;
+    /**
+    Returns an iterator over the unescaped characters of a string using a custom escape handler, without allocating.
+
+    Only single-character [`crate::EscapeHandler`]s are supported. See [`crate::Unescape`] for more details.
+     */
+    #[must_use = "iterators are lazy, and do nothing unless consumed"]
+    fn unescape_chars_with<H: EscapeHandler>(&self, callback: H) -> Unescape<'_, H>;
+    /**
+    Returns an iterator over the unescaped characters of a string and the byte ranges they came from, without allocating.
+
+    Uses [`crate::DefaultHandler`]. See [`crate::UnescapeSpans`] for more details.
+
+    # Examples
+    ```rust
+    # use descape::UnescapeExt;
+    let spans: Result<Vec<_>, _> = r"a\nb".unescape_spans().collect();
+    assert_eq!(spans.unwrap(), [(0..1, 'a'), (1..3, '\n'), (3..4, 'b')]);
+    ```
+     */
+    #[must_use = "iterators are lazy, and do nothing unless consumed"]
+    fn unescape_spans(&self) -> UnescapeSpans<'_>;
+}
+
+
+impl UnescapeExt for str {
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn to_unescaped(&self) -> Result<Cow<'_, str>, InvalidEscape> {
+        self.to_unescaped_with(DefaultHandler)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_string(&self) -> Result<String, InvalidEscape> {
+        self.to_unescaped().map(Cow::into_owned)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_range(&self, range: Range<usize>) -> Result<Cow<'_, str>, InvalidEscape> {
+        let slice = self.get(range.clone()).ok_or_else(|| {
+            let index = if self.is_char_boundary(range.start) { range.end } else { range.start };
+            InvalidEscape::with_kind(index, EscapeErrorKind::InvalidRange)
+        })?;
+        slice.to_unescaped().map_err(|mut err| { err.index += range.start; err })
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn unescape_wrapped(&self) -> Result<Unescaped<'_>, InvalidEscape> {
+        self.to_unescaped().map(Unescaped)
+    }
+
+    // Put this outside to prevent monomorphization bloat
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_with(
+        &self, 
+        callback: impl EscapeWriter
+    ) -> Result<Cow<'_, str>, InvalidEscape> {
+        self.to_unescaped_with_escape_char('\\', callback)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_n(&self, times: usize, mut callback: impl EscapeWriter) -> Result<Cow<'_, str>, InvalidEscape> {
+        to_unescaped_n_mono(self, times, &mut callback)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_with_dyn(&self, mut handler: &mut dyn EscapeHandler) -> Result<Cow<'_, str>, InvalidEscape> {
+        to_unescaped_with_mono(self, '\\', &mut handler, OnInvalid::Fail, self.len())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_with_escape_char(
+        &self,
+        escape: char,
+        mut callback: impl EscapeWriter
+    ) -> Result<Cow<'_, str>, InvalidEscape> {
+        to_unescaped_with_mono(self, escape, &mut callback, OnInvalid::Fail, self.len())
+    }
+
+    #[cfg(feature = "alloc")]
+    fn unescape_until(
+        &self,
+        delim: char,
+        mut callback: impl EscapeWriter
+    ) -> Result<(Cow<'_, str>, &str), InvalidEscape> {
+        let (contents, end) = unescape_until_mono(self, delim, &mut callback)?;
+        Ok((contents, &self[end + delim.len_utf8() ..]))
+    }
+
+    #[cfg(feature = "alloc")]
+    fn unescape_until_consumed(
+        &self,
+        delim: char,
+        mut callback: impl EscapeWriter
+    ) -> Result<(Cow<'_, str>, usize), InvalidEscape> {
+        let (contents, end) = unescape_until_mono(self, delim, &mut callback)?;
+        Ok((contents, end + delim.len_utf8()))
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn unescape_into(&self, out: &mut String) -> Result<(), InvalidEscape> {
+        self.unescape_into_with(out, DefaultHandler)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn unescape_into_with(&self, out: &mut String, mut callback: impl EscapeWriter) -> Result<(), InvalidEscape> {
+        unescape_into_mono(self, out, &mut callback)
+    }
+
+    fn unescape_to_fmt<W: core::fmt::Write + ?Sized>(
+        &self,
+        mut out: &mut W,
+        mut callback: impl EscapeHandler
+    ) -> Result<(), UnescapeFmtError> {
+        unescape_to_fmt_mono(self, &mut out, &mut callback)
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_lossy(&self) -> Cow<'_, str> {
+        self.to_unescaped_lossy_with(Some(char::REPLACEMENT_CHARACTER), DefaultHandler)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_lossy_with(
+        &self,
+        substitute: Option<char>,
+        mut callback: impl EscapeWriter
+    ) -> Cow<'_, str> {
+        let on_invalid = match substitute {
+            Some(chr) => OnInvalid::Substitute(chr),
+            None => OnInvalid::Keep
+        };
+        // This can't fail, as invalid escapes never stop the unescaping
+        to_unescaped_with_mono(self, '\\', &mut callback, on_invalid, self.len())
+            .unwrap_or(Cow::Borrowed(self))
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_exact(&self) -> Result<Cow<'_, str>, InvalidEscape> {
+        self.to_unescaped_exact_with(DefaultHandler)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn to_unescaped_exact_with(
+        &self,
+        mut callback: impl EscapeWriter
+    ) -> Result<Cow<'_, str>, InvalidEscape> {
+        match unescaped_len_mono(self, &mut callback)? {
+            Some(len) => to_unescaped_with_mono(self, '\\', &mut callback, OnInvalid::Fail, len),
+            None => Ok(Cow::Borrowed(self))
+        }
+    }
+
+    #[inline]
+    fn validate_escapes(&self) -> Result<(), InvalidEscape> {
+        self.unescape_chars().find_map(Result::err).map_or(Ok(()), Err)
+    }
+
+    #[cfg(feature = "alloc")]
+    fn validate_escapes_with(
+        &self,
+        mut callback: impl EscapeWriter
+    ) -> Result<(), InvalidEscape> {
+        // Finding the length already runs every escape through the handler
+        unescaped_len_mono(self, &mut callback).map(drop)
+    }
+
+    #[inline]
+    fn unescape_chars(&self) -> Unescape<'_> {
+        self.unescape_chars_with(DefaultHandler)
+    }
+
+    #[inline]
+    fn unescape_chars_with<H: EscapeHandler>(&self, callback: H) -> Unescape<'_, H> {
+        Unescape::new(self, callback)
+    }
+
+    #[inline]
+    fn unescape_spans(&self) -> UnescapeSpans<'_> {
+        UnescapeSpans::new(self, DefaultHandler)
+    }
+}
+
+/**
+Unescapes a string, returning an [`alloc::borrow::Cow`].
+
+This is the same as [`UnescapeExt::to_unescaped`], for when a free function reads better.
+
+# Errors
+Errors if there's an invalid escape sequence in the string.
+Passes back the byte index of the invalid character, and why it was invalid.
+
+# Examples
+```rust
+let unescaped: Vec<_> = [r"a\tb", r"\u{1F600}"].iter()
+    .map(|string| descape::unescape(string))
+    .collect::<Result<_, _>>()
+    .unwrap();
+assert_eq!(unescaped, ["a\tb", "\u{1F600}"]);
+```
+ */
+#[inline]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub fn unescape(string: &str) -> Result<Cow<'_, str>, InvalidEscape> {
+    string.to_unescaped()
+}
+
+/**
+Unescapes a string using a custom escape handler, returning an [`alloc::borrow::Cow`].
+
+This is the same as [`UnescapeExt::to_unescaped_with`], for when a free function reads better.
+
+# Errors
+Errors if there's an invalid escape sequence in the string.
+Passes back the byte index of the invalid character, and why it was invalid.
+
+# Examples
+```rust
+# use descape::JsonHandler;
+let unescaped = descape::unescape_with(r#"\"\/\""#, JsonHandler);
+assert_eq!(unescaped.unwrap(), r#""/""#);
+```
+ */
+#[inline]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub fn unescape_with(string: &str, callback: impl EscapeWriter) -> Result<Cow<'_, str>, InvalidEscape> {
+    string.to_unescaped_with(callback)
+}
+
+/**
+Parses a quoted string from the start of `input`, returning its unescaped contents and the input after the closing quote.
+
+This is the usual shape of a parser in combinator libraries like `winnow` and `nom`,
+so it can be wrapped into one of theirs without this crate depending on either.
+The contents are unescaped with [`UnescapeExt::unescape_until`], with `quote` as the delimiter.
+
+# Errors
+Errors with [`EscapeErrorKind::MissingQuote`] if `input` doesn't start with `quote`,
+with [`EscapeErrorKind::Unterminated`] if it runs out before the closing quote,
+or if there's an invalid escape sequence in the string.
+Indices are into `input`, counting the opening quote, so they can be added to the parser's own offset.
+
+# Examples
+```rust
+# use descape::{DefaultHandler, EscapeErrorKind};
+let (contents, rest) = descape::escaped_string(r#""say \"hi\"\n", 1"#, '"', DefaultHandler).unwrap();
+assert_eq!(contents, "say \"hi\"\n");
+assert_eq!(rest, ", 1");
+
+let err = descape::escaped_string(r#""bad \q""#, '"', DefaultHandler).unwrap_err();
+assert_eq!(err.index, 5);
+assert_eq!(descape::escaped_string("plain", '"', DefaultHandler).unwrap_err().kind, EscapeErrorKind::MissingQuote);
+```
+ */
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub fn escaped_string(input: &str, quote: char, callback: impl EscapeWriter) -> Result<(Cow<'_, str>, &str), InvalidEscape> {
+    if !input.starts_with(quote) {
+        return Err(InvalidEscape::with_kind(0, EscapeErrorKind::MissingQuote));
+    }
+    let start = quote.len_utf8();
+    let (contents, consumed) = input[start ..].unescape_until_consumed(quote, callback)
+        .map_err(|err| InvalidEscape { index: start + err.index, ..err })?;
+    Ok((contents, &input[start + consumed ..]))
+}
+
+// Owned strings just unescape what they point to,
+// which still lets a borrow of the original be returned when there's nothing to unescape
+#[cfg(feature = "alloc")]
+macro_rules! delegate_unescape_ext {
+    ($($ty: ty),+) => {$(
+        impl UnescapeExt for $ty {
+            #[inline]
+            fn to_unescaped(&self) -> Result<Cow<'_, str>, InvalidEscape> {
+                str::to_unescaped(self)
+            }
+
+            #[inline]
+            fn to_unescaped_string(&self) -> Result<String, InvalidEscape> {
+                str::to_unescaped_string(self)
+            }
+
+            #[inline]
+            fn to_unescaped_range(&self, range: Range<usize>) -> Result<Cow<'_, str>, InvalidEscape> {
+                str::to_unescaped_range(self, range)
+            }
+
+            #[inline]
+            fn unescape_wrapped(&self) -> Result<Unescaped<'_>, InvalidEscape> {
+                str::unescape_wrapped(self)
+            }
+
+            #[inline]
+            fn to_unescaped_with(&self, callback: impl EscapeWriter) -> Result<Cow<'_, str>, InvalidEscape> {
+                str::to_unescaped_with(self, callback)
+            }
+
+            #[inline]
+            fn to_unescaped_n(&self, times: usize, callback: impl EscapeWriter) -> Result<Cow<'_, str>, InvalidEscape> {
+                str::to_unescaped_n(self, times, callback)
+            }
+
+            #[inline]
+            fn to_unescaped_with_dyn(&self, handler: &mut dyn EscapeHandler) -> Result<Cow<'_, str>, InvalidEscape> {
+                str::to_unescaped_with_dyn(self, handler)
+            }
+
+            #[inline]
+            fn to_unescaped_with_escape_char(&self, escape: char, callback: impl EscapeWriter) -> Result<Cow<'_, str>, InvalidEscape> {
+                str::to_unescaped_with_escape_char(self, escape, callback)
+            }
+
+            #[inline]
+            fn unescape_until(&self, delim: char, callback: impl EscapeWriter) -> Result<(Cow<'_, str>, &str), InvalidEscape> {
+                str::unescape_until(self, delim, callback)
+            }
+
+            #[inline]
+            fn unescape_until_consumed(&self, delim: char, callback: impl EscapeWriter) -> Result<(Cow<'_, str>, usize), InvalidEscape> {
+                str::unescape_until_consumed(self, delim, callback)
+            }
+
+            #[inline]
+            fn unescape_into(&self, out: &mut String) -> Result<(), InvalidEscape> {
+                str::unescape_into(self, out)
+            }
+
+            #[inline]
+            fn unescape_into_with(&self, out: &mut String, callback: impl EscapeWriter) -> Result<(), InvalidEscape> {
+                str::unescape_into_with(self, out, callback)
+            }
+
+            #[inline]
+            fn unescape_to_fmt<W: core::fmt::Write + ?Sized>(&self, out: &mut W, callback: impl EscapeHandler) -> Result<(), UnescapeFmtError> {
+                str::unescape_to_fmt(self, out, callback)
+            }
+
+            #[inline]
+            fn to_unescaped_lossy(&self) -> Cow<'_, str> {
+                str::to_unescaped_lossy(self)
+            }
+
+            #[inline]
+            fn to_unescaped_lossy_with(&self, substitute: Option<char>, callback: impl EscapeWriter) -> Cow<'_, str> {
+                str::to_unescaped_lossy_with(self, substitute, callback)
+            }
+
+            #[inline]
+            fn to_unescaped_exact(&self) -> Result<Cow<'_, str>, InvalidEscape> {
+                str::to_unescaped_exact(self)
+            }
+
+            #[inline]
+            fn to_unescaped_exact_with(&self, callback: impl EscapeWriter) -> Result<Cow<'_, str>, InvalidEscape> {
+                str::to_unescaped_exact_with(self, callback)
+            }
+
+            #[inline]
+            fn validate_escapes(&self) -> Result<(), InvalidEscape> {
+                str::validate_escapes(self)
+            }
+
+            #[inline]
+            fn validate_escapes_with(&self, callback: impl EscapeWriter) -> Result<(), InvalidEscape> {
+                str::validate_escapes_with(self, callback)
+            }
+
+            #[inline]
+            fn unescape_chars(&self) -> Unescape<'_> {
+                str::unescape_chars(self)
+            }
+
+            #[inline]
+            fn unescape_chars_with<H: EscapeHandler>(&self, callback: H) -> Unescape<'_, H> {
+                str::unescape_chars_with(self, callback)
+            }
+
+            #[inline]
+            fn unescape_spans(&self) -> UnescapeSpans<'_> {
+                str::unescape_spans(self)
+            }
+        }
+    )+};
+}
+
+#[cfg(feature = "alloc")]
+delegate_unescape_ext!(String, Cow<'_, str>);
+
+/// Writes an escape character with nothing after it, if the handler allows it.
+#[cfg(feature = "alloc")]
+fn write_trailing(callback: &dyn EscapeWriter, escape: char, out: &mut String) -> Result<(), EscapeErrorKind> {
+    match callback.trailing_backslash() {
+        TrailingPolicy::Error => Err(EscapeErrorKind::Truncated),
+        TrailingPolicy::Literal => {
+            out.push(escape);
+            Ok(())
+        }
+    }
+}
+
+/// Checks that copying `this[start .. end]` onto an output that's `len` bytes long keeps it within `max` bytes,
+/// pointing at the first character that doesn't fit if it doesn't.
+#[cfg(feature = "alloc")]
+fn check_literal(this: &str, start: usize, end: usize, len: usize, max: usize) -> Result<(), InvalidEscape> {
+    let room = max.saturating_sub(len);
+    if end - start <= room {
+        return Ok(());
+    }
+    let mut index = start + room;
+    while !this.is_char_boundary(index) {
+        index -= 1;
+    }
+    let chr_len = this[index ..].chars().next().map_or(0, char::len_utf8);
+    Err(InvalidEscape::with_kind(index, EscapeErrorKind::OutputTooLong).with_len(chr_len))
+}
+
+/// Finds the length of the unescaped string, or `None` if there's nothing to unescape.
+#[cfg(feature = "alloc")]
+fn unescaped_len_mono(
+    this: &str,
+    callback: &mut dyn EscapeWriter
+) -> Result<Option<usize>, InvalidEscape> {
+    let mut iter = this.char_indices();
+    let max = callback.max_output_len();
+    // How long the output would be up to the end of the last escape sequence
+    let mut len = 0;
+    let mut escaped = false;
+    // Reused for every escape, so this only allocates for the biggest replacement,
+    // which is at most 4 bytes for the default handler
+    let mut scratch = String::new();
+
+    while let Some(offset) = iter.as_str().find('\\') {
+        let start = this.len() - iter.as_str().len();
+        let index = start + offset;
+        check_literal(this, start, index, len, max)?;
+        skip_bytes(&mut iter, offset + 1);
+        scratch.clear();
+        let res = match iter.next() {
+            Some((_, chr)) => callback.write_escape_with('\\', index, chr, &mut iter, &mut scratch),
+            None => write_trailing(callback, '\\', &mut scratch)
+        };
+        let end = this.len() - iter.as_str().len();
+        if let Err(kind) = res {
+            return Err(InvalidEscape::with_kind(index, kind).with_len(end - index));
+        }
+        len += index - start + scratch.len();
+        if len > max {
+            return Err(InvalidEscape::with_kind(index, EscapeErrorKind::OutputTooLong).with_len(end - index));
+        }
+        escaped = true;
+    }
+
+    let start = this.len() - iter.as_str().len();
+    check_literal(this, start, this.len(), len, max)?;
+    Ok(if escaped { Some(len + iter.as_str().len()) } else { None })
+}
+
+/// What to do with an invalid escape sequence.
+#[derive(Clone, Copy)]
+#[cfg(feature = "alloc")]
+enum OnInvalid {
+    /// Stop unescaping, and return the error.
+    Fail,
+    /// Replace the sequence with a character, and keep going.
+    Substitute(char),
+    /// Keep the sequence as written, and keep going.
+    Keep,
+}
+
+#[cfg(feature = "alloc")]
+fn to_unescaped_with_mono<'this>(
+    this: &'this str,
+    escape: char,
+    callback: &mut dyn EscapeWriter,
+    on_invalid: OnInvalid,
+    capacity: usize
+) -> Result<Cow<'this, str>, InvalidEscape> {
+    // Iterates over each character as a UTF-8 string slice
+    let mut iter = this.char_indices();
+    // Only built once the output stops matching the input, so it can't be borrowed anymore
+    let mut owned = None::<String>;
+    // Holds the replacement for each escape sequence until then
+    let mut scratch = String::new();
+    // Lossy unescaping has no way to report text that doesn't fit, only sequences it can replace
+    let max = callback.max_output_len();
+    let check = matches!(on_invalid, OnInvalid::Fail);
+
+    // Searching for the escape character is a lot faster than checking every character,
+    // and everything before it can be copied over in one go
+    while let Some(offset) = iter.as_str().find(escape) {
+        let start = this.len() - iter.as_str().len();
+        let index = start + offset;
+        let building = owned.is_some();
+        if check {
+            // Until the output is built, everything before the sequence is the same as in the input
+            check_literal(this, start, index, owned.as_ref().map_or(start, String::len), max)?;
+        }
+        let out = if let Some(owned) = owned.as_mut() {
+            owned.push_str(&this[start .. index]);
+            owned
+        } else {
+            scratch.clear();
+            &mut scratch
+        };
+        skip_bytes(&mut iter, offset + escape.len_utf8());
+
+        let written = out.len();
+        let mut res = match iter.next() {
+            Some((_, chr)) => callback.write_escape_with(escape, index, chr, &mut iter, out),
+            None => write_trailing(callback, escape, out)
+        };
+        let len = if building { out.len() } else { index + out.len() };
+        if res.is_ok() && len > max {
+            res = Err(EscapeErrorKind::OutputTooLong);
+        }
+        // Whatever the handler consumed is part of the sequence
+        let end = this.len() - iter.as_str().len();
+        if let Err(kind) = res {
+            // ...but nothing it wrote before failing is
+            out.truncate(written);
+            match on_invalid {
+                OnInvalid::Fail => return Err(InvalidEscape::with_kind(index, kind).with_len(end - index)),
+                OnInvalid::Substitute(chr) => out.push(chr),
+                OnInvalid::Keep => out.push_str(&this[index..end]),
+            }
+        }
+        // A sequence that was written back as-is still lets the string be borrowed
+        if owned.is_none() && scratch != this[index..end] {
+            let mut string = String::with_capacity(capacity);
+            string.push_str(&this[.. index]);
+            string.push_str(&scratch);
+            owned = Some(string);
+        }
+    }
+
+    let start = this.len() - iter.as_str().len();
+    if check {
+        check_literal(this, start, this.len(), owned.as_ref().map_or(start, String::len), max)?;
+    }
+    match owned {
+        Some(mut string) => {
+            string.push_str(iter.as_str());
+            Ok(Cow::Owned(string))
+        },
+        None => Ok(Cow::Borrowed(this)),
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn to_unescaped_n_mono<'this>(
+    this: &'this str,
+    times: usize,
+    callback: &mut dyn EscapeWriter
+) -> Result<Cow<'this, str>, InvalidEscape> {
+    let mut current = Cow::Borrowed(this);
+    // The string from the pass before last, kept around to write the next pass into
+    let mut spare = String::new();
+
+    for _ in 0 .. times {
+        if !current.contains('\\') {
+            break;
+        }
+        spare.clear();
+        unescape_into_mono(&current, &mut spare, callback)?;
+        if spare == *current {
+            break;
+        }
+        let previous = core::mem::replace(&mut current, Cow::Owned(core::mem::take(&mut spare)));
+        if let Cow::Owned(string) = previous {
+            spare = string;
+        }
+    }
+
+    Ok(current)
+}
+
+#[cfg(feature = "alloc")]
+fn unescape_into_mono(
+    this: &str,
+    out: &mut String,
+    callback: &mut dyn EscapeWriter
+) -> Result<(), InvalidEscape> {
+    let mut iter = this.char_indices();
+    let max = callback.max_output_len();
+    // Only what's written here counts towards the limit, not what was in `out` already
+    let base = out.len();
+
+    while let Some(offset) = iter.as_str().find('\\') {
+        let start = this.len() - iter.as_str().len();
+        let index = start + offset;
+        check_literal(this, start, index, out.len() - base, max)?;
+        out.push_str(&this[start .. index]);
+        skip_bytes(&mut iter, offset + 1);
+
+        let written = out.len();
+        let mut res = match iter.next() {
+            Some((_, chr)) => callback.write_escape_with('\\', index, chr, &mut iter, out),
+            None => write_trailing(callback, '\\', out)
+        };
+        if res.is_ok() && out.len() - base > max {
+            res = Err(EscapeErrorKind::OutputTooLong);
+        }
+        if let Err(kind) = res {
+            // Nothing the handler wrote before failing is part of the output
+            out.truncate(written);
+            let end = this.len() - iter.as_str().len();
+            return Err(InvalidEscape::with_kind(index, kind).with_len(end - index));
+        }
+    }
+
+    let start = this.len() - iter.as_str().len();
+    check_literal(this, start, this.len(), out.len() - base, max)?;
+    out.push_str(iter.as_str());
+    Ok(())
+}
+
+fn unescape_to_fmt_mono(
+    this: &str,
+    out: &mut dyn core::fmt::Write,
+    callback: &mut dyn EscapeHandler
+) -> Result<(), UnescapeFmtError> {
+    let mut iter = this.char_indices();
+
+    while let Some(offset) = iter.as_str().find('\\') {
+        let start = this.len() - iter.as_str().len();
+        let index = start + offset;
+        out.write_str(&this[start .. index])?;
+        skip_bytes(&mut iter, offset + 1);
+
+        let res = match iter.next() {
+            Some((_, chr)) => callback.escape_detailed(index, chr, &mut iter),
+            None => Err(EscapeErrorKind::Truncated)
+        };
+        match res {
+            Ok(Some(chr)) => out.write_char(chr)?,
+            Ok(None) => {},
+            Err(kind) => {
+                let end = this.len() - iter.as_str().len();
+                return Err(InvalidEscape::with_kind(index, kind).with_len(end - index).into());
+            }
+        }
+    }
+
+    out.write_str(iter.as_str())?;
+    Ok(())
+}
+
+/// Unescapes a string up to `delim`, returning the contents and the index of the delimiter.
+#[cfg(feature = "alloc")]
+fn unescape_until_mono<'this>(
+    this: &'this str,
+    delim: char,
+    callback: &mut dyn EscapeWriter
+) -> Result<(Cow<'this, str>, usize), InvalidEscape> {
+    let mut iter = this.char_indices();
+    let mut owned = None::<String>;
+    let max = callback.max_output_len();
+
+    loop {
+        let rest = iter.as_str();
+        let start = this.len() - rest.len();
+        // The delimiter is checked first, so a backslash delimiter can't start an escape
+        let (offset, chr) = rest.char_indices()
+            .find(|&(_, chr)| chr == delim || chr == '\\')
+            .ok_or(InvalidEscape::with_kind(this.len(), EscapeErrorKind::Unterminated))?;
+        let index = start + offset;
+        check_literal(this, start, index, owned.as_ref().map_or(start, String::len), max)?;
+        skip_bytes(&mut iter, offset + chr.len_utf8());
+
+        if chr == delim {
+            let contents = match owned {
+                Some(mut string) => {
+                    string.push_str(&this[start .. index]);
+                    Cow::Owned(string)
+                },
+                None => Cow::Borrowed(&this[.. index])
+            };
+            return Ok((contents, index));
+        }
+
+        let owned = owned.get_or_insert_with(|| String::with_capacity(this.len()));
+        owned.push_str(&this[start .. index]);
+        let mut res = match iter.next() {
+            Some((_, chr)) => callback.write_escape_with('\\', index, chr, &mut iter, owned),
+            None => write_trailing(callback, '\\', owned)
+        };
+        if res.is_ok() && owned.len() > max {
+            res = Err(EscapeErrorKind::OutputTooLong);
+        }
+        if let Err(kind) = res {
+            // Whatever the handler consumed is part of the invalid sequence
+            let end = this.len() - iter.as_str().len();
+            return Err(InvalidEscape::with_kind(index, kind).with_len(end - index));
+        }
+    }
+}
+
+/// The default escape sequence handler. 
+///
+/// The following escapes are valid:
+/// - `\\a` -> `\x07`
+/// - `\\b` -> `\x08`
+/// - `\\t` -> `\x09`
+/// - `\\n` -> `\x0A`
+/// - `\\v` -> `\x0B`
+/// - `\\f` -> `\x0C`
+/// - `\\r` -> `\x0D`
+/// - `\\e` -> `\x1B`
+/// - `\\'` -> `'`
+/// - `\\"` -> `"`
+/// - <code>&bsol;&bsol;&grave;</code> -> <code>&grave;</code>
+/// - `\\\\` -> `\\`
+/// - `\\xNN` -> `\xNN`
 /// - `\\o` -> `\o`, for all octal digits `o`
 /// - `\\oo` -> `\oo`, for all octal digits `o`
 /// - `\\ooo` -> `\ooo`, for all octal digits `o`
 /// - `\\uXXXX` -> `\u{XXXX}`
+/// - `\\UXXXXXXXX` -> `\u{XXXXXXXX}`
 /// - `\\u{HEX}` -> `\u{HEX}`
 ///
+/// A `\\uXXXX` high surrogate immediately followed by a `\\uXXXX` low surrogate
+/// is combined into the character the pair encodes, like in JSON.
+///
+/// To tweak which of these are valid, see [`Unescaper`].
+///
 pub struct DefaultHandler;
 
+impl DefaultHandler {
+    /// Returns an [`Unescaper`] that starts out behaving exactly like this handler, for turning escapes on and off.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{DefaultHandler, Escapes};
+    /// let unescaper = DefaultHandler::builder().escapes(Escapes::ALL - Escapes::OCTAL);
+    /// unescaper.unescape(r"\11").expect_err("octal is disabled");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn builder() -> Unescaper {
+        Unescaper::new()
+    }
+
+    /**
+    Returns an [`Unescaper`] that only accepts the single-character escapes listed in `chars`,
+    as the characters that come after the backslash.
+
+    Numeric escapes like `\\x41` are all turned off, and can be turned back on with [`Unescaper::escapes`].
+
+    # Errors
+    Errors with [`EscapeErrorKind::UnknownEscape`] for the first character that isn't a single-character escape,
+    as parsed by [`Escapes`].
+
+    # Examples
+    ```rust
+    # #[cfg(feature = "alloc")] {
+    # use descape::DefaultHandler;
+    let unescaper = DefaultHandler::from_allowed(r#"ntr\""#).unwrap();
+    assert_eq!(unescaper.unescape(r#"\"a\tb\""#).unwrap(), "\"a\tb\"");
+    unescaper.unescape(r"\e").expect_err("\\e wasn't allowed");
+    unescaper.unescape(r"\x41").expect_err("numeric escapes are off");
+    # }
+    ```
+     */
+    pub fn from_allowed(chars: &str) -> Result<Unescaper, EscapeErrorKind> {
+        chars.parse().map(|escapes| Unescaper::new().escapes(escapes))
+    }
+}
+
 impl EscapeHandler for DefaultHandler {
-    fn escape(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
-        Ok( match chr {
-            'a' => Some('\x07'),
-            'b' => Some('\x08'),
-            't' => Some('\x09'),
-            'n' => Some('\x0A'),
-            'v' => Some('\x0B'),
-            'f' => Some('\x0C'),
-            'r' => Some('\x0D'),
-            'e' => Some('\x1B'),
-            '`' => Some('`'),
-            '\'' => Some('\''),
-            '"' => Some('"'),
-            '\\' => Some('\\'),
-            'u' => {
-                let (chr, skip) = unescape_unicode(iter).ok_or(())?;
-                // Skip the needed amount of characters
-                for _ in 0..skip { iter.next(); }
-                Some(chr)
-            },
-            'x' => {
-                // Skip two characters
-                let res = unescape_hex(iter).ok_or(())?;
-                iter.next();
-                iter.next();
-                Some(res)
-            },
-            c if c.is_digit(8) => {
-                let (chr, skip) = unescape_oct(c, iter).ok_or(())?;
-                for _ in 0..skip { iter.next(); }
-                Some(chr)
-            },
-            _ => return Err(()),
-        } )
+    fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+        self.escape_detailed(idx, chr, iter).map_err(|_| ())
+    }
+
+    #[inline]
+    fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        match Unescaper::new().unescape_known(chr, iter)? {
+            Some(chr) => Ok(Some(chr)),
+            None => Err(EscapeErrorKind::UnknownEscape(chr))
+        }
+    }
+}
+
+/**
+Parses the character after a `\c` control escape, consuming it from the iterator.
+
+Returns the control character it stands for, which is the uppercase version of the character with bit 6 flipped,
+so `\cA` and `\ca` become `\x01`, `\c@` becomes `\0`, `\c[` becomes `\x1B`, and `\c?` becomes `\x7F`.
+
+Returns `None` if the string is empty, or if the character doesn't stand for a control character.
+The character is still consumed in the latter case.
+
+# Examples
+```rust
+# #[cfg(feature = "alloc")] {
+# use descape::{UnescapeExt, DefaultHandler, EscapeHandler, EscapeErrorKind, unescape_control};
+# use std::str::CharIndices;
+fn caret(idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+    if chr == 'c' {
+        return unescape_control(iter).map(Some).ok_or(EscapeErrorKind::Rejected);
     }
+    DefaultHandler.escape_detailed(idx, chr, iter)
 }
 
+let mut handler = |idx: usize, chr: char, iter: &mut CharIndices<'_>| caret(idx, chr, iter).map_err(|_| ());
+assert_eq!(r"\cG\cz\n".to_unescaped_with(&mut handler).unwrap(), "\x07\x1A\n");
+r"\c1".to_unescaped_with(&mut handler).expect_err("1 isn't a control character");
+# }
+```
+ */
+pub fn unescape_control(iter: &mut CharIndices<'_>) -> Option<char> {
+    let (_, chr) = iter.next()?;
+    if !chr.is_ascii() {
+        return None;
+    }
+    let control = char::from(chr.to_ascii_uppercase() as u8 ^ 0x40);
+    if control.is_ascii_control() { Some(control) } else { None }
+}
+
+/**
+The characters that [`DefaultHandler`] unescapes single-character escape sequences to,
+indexed by the ASCII character after the backslash.
+
+These are `\\a`, `\\b`, `\\t`, `\\n`, `\\v`, `\\f`, `\\r`, `\\e`, ``\\` ``, `\\'`, `\\"`, and `\\\\`,
+and every other entry is `None`.
+The escapes that take more than one character, like `\\x41`, aren't in here.
+
+# Examples
+```rust
+# #[cfg(feature = "alloc")] {
+# use descape::{UnescapeExt, ESCAPE_TABLE, EscapeErrorKind};
+# use std::str::CharIndices;
+fn quick(_: usize, chr: char, _: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+    match ESCAPE_TABLE.get(chr as usize) {
+        Some(Some(unescaped)) => Ok(Some(*unescaped)),
+        _ => Err(EscapeErrorKind::UnknownEscape(chr))
+    }
+}
+
+let mut handler = |idx: usize, chr: char, iter: &mut CharIndices<'_>| quick(idx, chr, iter).map_err(|_| ());
+assert_eq!(r"\t\e\\".to_unescaped_with(&mut handler).unwrap(), "\t\x1B\\");
+r"\x41".to_unescaped_with(&mut handler).expect_err("only single characters are in the table");
+# }
+```
+ */
+pub const ESCAPE_TABLE: [Option<char>; 128] = {
+    let mut table = [None; 128];
+    let mut byte = 0_u8;
+    while byte < 128 {
+        table[byte as usize] = single_escape(byte as char);
+        byte += 1;
+    }
+    table
+};
+
+/// Maps the escape sequences that consist of only a single character, through [`ESCAPE_TABLE`].
+const fn unescape_single(chr: char) -> Option<char> {
+    if chr.is_ascii() { ESCAPE_TABLE[chr as usize] } else { None }
+}
+
+/// Maps the escape sequences that consist of only a single character, for building [`ESCAPE_TABLE`].
+const fn single_escape(chr: char) -> Option<char> {
+    Some( match chr {
+        'a' => '\x07',
+        'b' => '\x08',
+        't' => '\x09',
+        'n' => '\x0A',
+        'v' => '\x0B',
+        'f' => '\x0C',
+        'r' => '\x0D',
+        'e' => '\x1B',
+        '`' => '`',
+        '\'' => '\'',
+        '"' => '"',
+        '\\' => '\\',
+        _ => return None
+    } )
+}
+
+/// Parses a non-empty run of ASCII digits in the given radix, saturating on overflow.
+fn parse_digits(digits: &[u8], radix: u32) -> Option<u32> {
+    if digits.is_empty() {
+        return None;
+    }
+    digits.iter().try_fold(0u32, |acc, &digit| {
+        let digit = char::from(digit).to_digit(radix)?;
+        Some(acc.saturating_mul(radix).saturating_add(digit))
+    })
+}
+
+/// Converts a codepoint to a character, reporting why it's invalid if it isn't one.
+fn to_char(codepoint: u32) -> Result<char, EscapeErrorKind> {
+    char::from_u32(codepoint).ok_or(
+        if (0xD800 ..= 0xDFFF).contains(&codepoint) {
+            EscapeErrorKind::LoneSurrogate(codepoint)
+        } else {
+            EscapeErrorKind::InvalidCodepoint(codepoint)
+        }
+    )
+}
+
+/// Advances the iterator until at least `bytes` bytes have been consumed.
+fn skip_bytes(iter: &mut CharIndices, bytes: usize) {
+    let target = iter.as_str().len().saturating_sub(bytes);
+    while iter.as_str().len() > target {
+        iter.next();
+    }
+}
+
+/// Parses the part of a Unicode escape after the `u`,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
 fn unescape_unicode(
-    iter: &mut CharIndices
-) -> Option<(char, usize)> {
-    let string = iter.as_str();
-    let (_, next) = iter.next()?;
-    if next == '{' {
+    string: &str
+) -> (Result<char, EscapeErrorKind>, usize) {
+    if string.starts_with('{') {
         // \u{HEX}
-        let end = string[1 ..].find('}')?;
-        let num = &string[1 ..= end];
-        let codepoint = u32::from_str_radix(num, 16).ok()?;
-        char::from_u32(codepoint).map(|v| (v, end + 1))
+        unescape_braced(string)
     } else {
-        // \uNNNN
-        // If any of these are non-ASCII, then it's already invalid,
-        // so a direct slice is fine
-        let next_four = string.get( ..4 )?;
-        let codepoint = u32::from_str_radix(next_four, 16).ok()?;
-        // Encode the u32
-        char::from_u32(codepoint).map(|v| (v, 3))
+        unescape_utf16(string)
+    }
+}
+
+/// Parses a `{HEX}` codepoint from the start of the string,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn unescape_braced(
+    string: &str
+) -> (Result<char, EscapeErrorKind>, usize) {
+    let rest = &string[1 ..];
+    let end = match rest.find('}') {
+        Some(end) => end,
+        None => return (Err(EscapeErrorKind::Truncated), string.len())
+    };
+    let res = parse_digits(&rest.as_bytes()[.. end], 16)
+        .ok_or(EscapeErrorKind::InvalidHexDigit)
+        .and_then(to_char);
+    (res, end + 2)
+}
+
+/// Parses a `{HEX HEX ...}` run of whitespace-separated codepoints from the start of the string,
+/// pushing each character onto `out`, and returning the amount of bytes that are part of the sequence.
+#[cfg(feature = "alloc")]
+fn unescape_braced_multi(
+    string: &str,
+    out: &mut String
+) -> (Result<(), EscapeErrorKind>, usize) {
+    let rest = &string[1 ..];
+    let end = match rest.find('}') {
+        Some(end) => end,
+        None => return (Err(EscapeErrorKind::Truncated), string.len())
+    };
+    let mut groups = rest[.. end]
+        .split(|chr| chr == ' ' || chr == '\t')
+        .filter(|group| !group.is_empty())
+        .peekable();
+    // There has to be at least one codepoint
+    if groups.peek().is_none() {
+        return (Err(EscapeErrorKind::InvalidHexDigit), end + 2);
+    }
+    let res = groups.try_for_each(|group| {
+        let chr = parse_digits(group.as_bytes(), 16)
+            .ok_or(EscapeErrorKind::InvalidHexDigit)
+            .and_then(to_char)?;
+        out.push(chr);
+        Ok(())
+    });
+    (res, end + 2)
+}
+
+/// Parses the part of a `\uNNNN` escape after the `u`, combining it with a following low surrogate if needed,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn unescape_utf16(
+    string: &str
+) -> (Result<char, EscapeErrorKind>, usize) {
+    let (res, len) = parse_fixed_digits(string, 4);
+    match res {
+        Ok(high @ 0xD800 ..= 0xDBFF) => {
+            // This might be the start of a UTF-16 surrogate pair, like in JSON
+            match low_surrogate(&string[len ..]) {
+                Some(low) => (to_char(combine_surrogates(high, low)), len + 6),
+                None => (Err(EscapeErrorKind::LoneSurrogate(high)), len)
+            }
+        },
+        res => (res.and_then(to_char), len)
     }
 }
 
-// FIXME: This could be factored out along with part of unescape_unicode into its own function.
+/// Parses a `\uNNNN` low surrogate from the start of the string, if there is one.
+fn low_surrogate(string: &str) -> Option<u32> {
+    let num = string.strip_prefix("\\u")?.get(..4)?;
+    parse_digits(num.as_bytes(), 16)
+        .filter(|low| (0xDC00 ..= 0xDFFF).contains(low))
+}
+
+/// Combines a UTF-16 surrogate pair into the codepoint it encodes.
+const fn combine_surrogates(high: u32, low: u32) -> u32 {
+    0x1_0000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+}
+
+/// Parses the part of an 8-digit Unicode escape after the `U`,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn unescape_unicode_long(
+    string: &str
+) -> (Result<char, EscapeErrorKind>, usize) {
+    // Must be \UNNNNNNNN
+    parse_fixed(string, 8)
+}
+
+/// Parses the part of a `\xNN` escape after the `x`,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
 fn unescape_hex(
-    iter: &mut CharIndices
-) -> Option<char> {
+    string: &str
+) -> (Result<char, EscapeErrorKind>, usize) {
+    parse_fixed(string, 2)
+}
 
-    // Must be \xNN
-    let codepoint = iter.as_str()
-        .get(..2)
-        .and_then(|num| u32::from_str_radix(num, 16).ok())?;
-    char::from_u32(codepoint)
+/// Parses as many hexadecimal digits from the start of the string as the mode says to,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn unescape_hex_mode(string: &str, mode: HexMode) -> (Result<char, EscapeErrorKind>, usize) {
+    let max_digits = match mode {
+        HexMode::Exact(digits) => return parse_fixed(string, digits),
+        HexMode::UpTo(digits) => digits,
+        HexMode::Greedy => usize::MAX,
+    };
+    let len = string.bytes()
+        .take(max_digits)
+        .take_while(u8::is_ascii_hexdigit)
+        .count();
+    // Anything too big to fit is too big to be a character anyways
+    match parse_digits(&string.as_bytes()[.. len], 16) {
+        Some(value) => (to_char(value), len),
+        None if string.is_empty() => (Err(EscapeErrorKind::Truncated), 0),
+        None => (Err(EscapeErrorKind::InvalidHexDigit), 0),
+    }
 }
 
-#[allow(clippy::cast_possible_truncation)] // Can't actually happen
-fn unescape_oct(
-    chr: char,
-    iter: &mut CharIndices
-) -> Option<(char, usize)> {
+/// Parses exactly `digits` hexadecimal digits from the start of the string,
+/// returning the parsed character and the amount of bytes that are part of the sequence.
+fn parse_fixed(string: &str, digits: usize) -> (Result<char, EscapeErrorKind>, usize) {
+    let (res, len) = parse_fixed_digits(string, digits);
+    (res.and_then(to_char), len)
+}
+
+/// Parses exactly `digits` hexadecimal digits from the start of the string,
+/// returning the parsed number and the amount of bytes that are part of the sequence.
+///
+/// The sequence is always the next `digits` characters, or the rest of the string if it's shorter,
+/// so a multibyte character counts as one bad digit instead of splitting the slice.
+fn parse_fixed_digits(string: &str, digits: usize) -> (Result<u32, EscapeErrorKind>, usize) {
+    let len = string.char_indices()
+        .nth(digits)
+        .map_or(string.len(), |(idx, _)| idx);
+    let num = &string[ .. len];
+    if !num.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return (Err(EscapeErrorKind::InvalidHexDigit), len);
+    }
+    // Every digit is a single byte, so a short slice means the string ended early
+    if len < digits {
+        return (Err(EscapeErrorKind::Truncated), len);
+    }
+    let res = parse_digits(num.as_bytes(), 16)
+        .ok_or(EscapeErrorKind::InvalidHexDigit);
+    (res, len)
+}
 
+/// Parses an octal escape starting with the digit `first`, followed by the rest of the string,
+/// returning the parsed codepoint and the amount of bytes after `first` that are part of the sequence.
+fn unescape_oct(
+    first: char,
+    rest: &str
+) -> (u32, usize) {
     // Could be \o, \oo, or \ooo
-    let str = iter.as_str();
-    let end = iter.clone() // Cloning this is pretty cheap
-        .take(2)
-        .take_while(|(_, c)| c.is_digit(8))
-        .enumerate()
-        .last()
-        .map_or(0, |(idx, _)| idx + 1);
-    let num = &str[ .. end];
+    scan_number(first, rest, 8, 3)
+}
+
+/// Parses a decimal escape starting with the digit `first`, followed by the rest of the string,
+/// returning the parsed codepoint and the amount of bytes after `first` that are part of the sequence.
+fn unescape_dec(
+    first: char,
+    rest: &str
+) -> (u32, usize) {
+    // Could be \d, \dd, or \ddd
+    scan_number(first, rest, 10, 3)
+}
+
+/// Parses a number of up to `max_digits` digits in the given radix, starting with the digit `first`,
+/// followed by the rest of the string, returning the parsed value and the amount of bytes after `first` that are part of it.
+#[allow(clippy::cast_possible_truncation)] // Can't actually happen
+fn scan_number(first: char, rest: &str, radix: u32, max_digits: usize) -> (u32, usize) {
+    let end = rest.bytes()
+        .take(max_digits - 1)
+        .take_while(|&byte| char::from(byte).is_digit(radix))
+        .count();
     // These are the characters _after_ the first
-    let mut codepoint = if num.is_empty() { 0 } else { u32::from_str_radix(num, 8).ok()? };
+    let mut value = parse_digits(&rest.as_bytes()[ .. end], radix).unwrap_or(0);
     // Add the first character at the top of the number
-    codepoint += (chr as u32 - '0' as u32) * 8u32.pow(end as u32);
-    char::from_u32(codepoint).map(|chr| (chr, end))
+    value += first.to_digit(radix).unwrap_or(0) * radix.pow(end as u32);
+    (value, end)
 }
-
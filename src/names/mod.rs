@@ -0,0 +1,41 @@
+//! Looking up characters by their Unicode name, for `\N{NAME}` escapes.
+
+use crate::EscapeErrorKind;
+
+mod table;
+
+/// Finds the character with the given name, ignoring ASCII case, like `LATIN SMALL LETTER A`.
+///
+/// Only formal names are known, not aliases.
+pub(crate) fn lookup(name: &str) -> Option<char> {
+    let upper = || name.bytes().map(|byte| byte.to_ascii_uppercase());
+    if let Ok(idx) = table::NAMES.binary_search_by(|(candidate, _)| candidate.bytes().cmp(upper())) {
+        return char::from_u32(table::NAMES[idx].1);
+    }
+    // Names like `CJK UNIFIED IDEOGRAPH-4E00` are made of their codepoint
+    table::DERIVED.iter().find_map(|(prefix, ranges)| {
+        let digits = name.get(prefix.len() ..)?;
+        if !name[ .. prefix.len()].eq_ignore_ascii_case(prefix) || !(4 ..= 5).contains(&digits.len()) {
+            return None;
+        }
+        let codepoint = crate::parse_digits(digits.as_bytes(), 16)?;
+        if !ranges.iter().any(|(start, end)| (start ..= end).contains(&&codepoint)) {
+            return None;
+        }
+        char::from_u32(codepoint)
+    })
+}
+
+/// Parses the part of a `\N{NAME}` escape after the `N`,
+/// returning the named character and the amount of bytes that are part of the sequence.
+pub(crate) fn unescape_named(
+    string: &str
+) -> (Result<char, EscapeErrorKind>, usize) {
+    if !string.starts_with('{') {
+        return (Err(EscapeErrorKind::UnknownName), 0);
+    }
+    match string.find('}') {
+        Some(end) => (lookup(&string[1 .. end]).ok_or(EscapeErrorKind::UnknownName), end + 1),
+        None => (Err(EscapeErrorKind::UnknownName), string.len())
+    }
+}
@@ -0,0 +1,28 @@
+/*!
+Re-exports the extension traits, handler traits, and built-in handlers, for glob importing.
+
+```rust
+# #[cfg(feature = "alloc")] {
+use descape::prelude::*;
+
+assert_eq!(r"\u{1F600}".to_unescaped().unwrap(), "😀");
+assert_eq!(r"\/".to_unescaped_with(JsonHandler).unwrap(), "/");
+assert_eq!(b"%20".to_unescaped_bytes_with_escape_byte(b'%', PercentHandler).unwrap(), &b" "[..]);
+assert_eq!("\n".to_escaped(), r"\n");
+# }
+```
+*/
+
+pub use crate::{
+    UnescapeExt, EscapeHandler, DefaultHandler, Unescaper, Escapes, UnknownEscapePolicy, TrailingPolicy, OctalMode, HexMode,
+    OrElse, MapHandler, IdentityHandler, DropHandler, MapOrDefault, NamedUnicodeHandler, JsonHandler, GraphQLHandler, JavaHandler, KotlinHandler, JsHandler, RustHandler, CHandler, CSharpHandler, GoHandler, LuaHandler, HaskellHandler, OCamlHandler, ElixirHandler, ShellHandler, PowerShellHandler, SqlAnsiHandler, PostgresEStringHandler, CsvHandler, TomlHandler, YamlHandler,
+    InvalidEscape, EscapeErrorKind, UnescapeFmtError
+};
+#[cfg(feature = "alloc")]
+pub use crate::{
+    UnescapeBytesExt, EscapeExt,
+    MultiEscapeHandler, EscapeWriter, ByteEscapeHandler, Escaper,
+    BoxHandler, DefaultEscaper, ShortestEscaper, RustEscaper, JsonEscaper, AsciiEscaper,
+    ContinuationHandler, ResilientHandler, GraphQLBlockHandler, PercentHandler, PythonStrHandler, PythonBytesHandler, RubyHandler, PhpHandler, XmlHandler,
+    Replacement, Unescaped, UnescapedString
+};
@@ -0,0 +1,155 @@
+//! Streaming unescaping from an [`std::io::Read`] into an [`std::io::Write`].
+
+use alloc::{string::String, vec::Vec};
+use std::io::{self, Read, Write};
+
+use crate::{EscapeWriter, EscapeErrorKind, InvalidEscape, check_literal, skip_bytes, write_trailing};
+
+/// How many bytes are read from the reader at once.
+const CHUNK_LEN: usize = 8 * 1024;
+/// How many bytes must follow the start of an escape sequence before it's unescaped,
+/// unless the reader has run out.
+///
+/// This is for handlers that look ahead without consuming anything,
+/// like checking for the low half of a surrogate pair after `\uD83D`.
+const LOOKAHEAD: usize = 16;
+
+/**
+Unescapes everything read from `reader`, writing the unescaped text to `writer` as it goes.
+
+This works like [`crate::UnescapeExt::to_unescaped_with`], but without holding the whole string in memory.
+
+# Buffering
+Input is read in chunks, and only the text up to the last escape sequence that might be incomplete
+is unescaped and written out. The rest is kept and carried over to the next read, so an escape sequence
+split across reads, like a `\u{1F600}` cut off after `\u{1F`, is unescaped once the rest of it arrives.
+The same goes for UTF-8 characters split across reads.
+
+An escape sequence is only unescaped once at least 16 bytes follow its backslash,
+and the handler stopped before the end of what's been read so far.
+When the handler runs into the end of the buffer instead, even by succeeding on a shorter sequence
+like `\1` out of `\101`, the sequence is carried over too, and the handler is called on it again
+after the next read. Handlers that keep state between calls should keep this in mind.
+
+The indices in errors are byte indices into the whole stream.
+The ones given to the handler count from the start of the text that's currently held in memory instead,
+so that they line up with the indices from its iterator.
+
+# Errors
+Errors if reading or writing fails, if the input isn't valid UTF-8,
+or if there's an invalid escape sequence, which is converted into an [`io::Error`] wrapping the [`InvalidEscape`].
+Anything before the invalid escape sequence has already been written by then.
+
+# Examples
+```rust
+# use descape::DefaultHandler;
+let mut out = Vec::new();
+descape::unescape_reader(&br"a\tb\u{1F600}"[..], &mut out, DefaultHandler).unwrap();
+assert_eq!(out, "a\tb\u{1F600}".as_bytes());
+
+let err = descape::unescape_reader(&br"a\qb"[..], &mut out, DefaultHandler).unwrap_err();
+assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+```
+*/
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+pub fn unescape_reader<R: Read, W: Write>(
+    mut reader: R,
+    mut writer: W,
+    mut callback: impl EscapeWriter
+) -> io::Result<()> {
+    unescape_reader_mono(&mut reader, &mut writer, &mut callback)
+}
+
+fn unescape_reader_mono(
+    reader: &mut dyn Read,
+    writer: &mut dyn Write,
+    callback: &mut dyn EscapeWriter
+) -> io::Result<()> {
+    let mut buf = Vec::with_capacity(CHUNK_LEN);
+    let mut out = String::new();
+    // How many bytes of the stream have already been unescaped, and how many they were unescaped into
+    let mut offset = 0;
+    let mut written = 0;
+    let mut eof = false;
+
+    while !eof {
+        let len = buf.len();
+        buf.resize(len + CHUNK_LEN, 0);
+        let read = loop {
+            match reader.read(&mut buf[len ..]) {
+                Ok(read) => break read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {},
+                Err(err) => return Err(err),
+            }
+        };
+        buf.truncate(len + read);
+        eof = read == 0;
+
+        let text = match core::str::from_utf8(&buf) {
+            Ok(text) => text,
+            // The rest of the character is still to come
+            Err(err) if err.error_len().is_none() && !eof =>
+                core::str::from_utf8(&buf[.. err.valid_up_to()]).unwrap_or_default(),
+            Err(_) => return Err(io::Error::new(io::ErrorKind::InvalidData, "stream did not contain valid UTF-8")),
+        };
+        let done = unescape_chunk(text, offset, written, eof, callback, &mut out);
+        writer.write_all(out.as_bytes())?;
+        written += out.len();
+        out.clear();
+        let done = done?;
+        buf.drain(.. done);
+        offset += done;
+    }
+    Ok(())
+}
+
+/// Unescapes as much of a chunk as can be unescaped, returning how many bytes of it were.
+fn unescape_chunk(
+    text: &str,
+    offset: usize,
+    written: usize,
+    eof: bool,
+    callback: &mut dyn EscapeWriter,
+    out: &mut String
+) -> Result<usize, InvalidEscape> {
+    let mut iter = text.char_indices();
+    let max = callback.max_output_len();
+    // Errors point into the whole stream
+    let at_offset = |err: InvalidEscape| InvalidEscape { index: offset + err.index, ..err };
+    loop {
+        let rest = iter.as_str();
+        let start = text.len() - rest.len();
+        let pos = if let Some(pos) = rest.find('\\') { pos } else {
+            check_literal(text, start, text.len(), written + out.len(), max).map_err(at_offset)?;
+            out.push_str(rest);
+            return Ok(text.len());
+        };
+        let index = start + pos;
+        check_literal(text, start, index, written + out.len(), max).map_err(at_offset)?;
+        out.push_str(&rest[.. pos]);
+        if !eof && text.len() - index < LOOKAHEAD {
+            return Ok(index);
+        }
+
+        skip_bytes(&mut iter, pos + 1);
+        let before = out.len();
+        let mut res = match iter.next() {
+            Some((_, chr)) => callback.write_escape_with('\\', index, chr, &mut iter, out),
+            None if eof => write_trailing(callback, '\\', out),
+            // The rest of the sequence is still to come, so this is carried over below
+            None => Ok(())
+        };
+        if !eof && iter.as_str().is_empty() {
+            // The handler might have stopped short because it ran out of input
+            out.truncate(before);
+            return Ok(index);
+        }
+        if res.is_ok() && written + out.len() > max {
+            res = Err(EscapeErrorKind::OutputTooLong);
+        }
+        if let Err(kind) = res {
+            let end = text.len() - iter.as_str().len();
+            return Err(InvalidEscape::with_kind(offset + index, kind).with_len(end - index));
+        }
+    }
+}
@@ -0,0 +1,190 @@
+//! The sub-parsers used by the built-in handlers, for reusing in custom ones.
+//!
+//! Every scanner takes the rest of the string after the character that introduced the sequence, like the `x` in `\xNN`,
+//! and returns the decoded value along with the amount of **bytes** of that string that are part of the sequence.
+//! The length is returned even on failure, so that the error spans the whole sequence.
+//!
+//! The iterator an escape handler is given has to be advanced past those bytes by the handler,
+//! which [`consume`] does in one step.
+//!
+//! # Examples
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! # use std::str::CharIndices;
+//! # use descape::{UnescapeExt, EscapeHandler, EscapeErrorKind, scan};
+//! struct HexOnly;
+//!
+//! impl EscapeHandler for HexOnly {
+//!     fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, ()> {
+//!         self.escape_detailed(idx, chr, iter).map_err(|_| ())
+//!     }
+//!
+//!     fn escape_detailed(&mut self, _: usize, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+//!         match chr {
+//!             'x' => scan::consume(iter, scan::hex).map(Some),
+//!             'u' => scan::consume(iter, scan::unicode).map(Some),
+//!             c => Err(EscapeErrorKind::UnknownEscape(c))
+//!         }
+//!     }
+//! }
+//!
+//! assert_eq!(r"\x41\u{1F600}".to_unescaped_with(HexOnly).unwrap(), "A\u{1F600}");
+//! r"\n".to_unescaped_with(HexOnly).expect_err("only hex escapes are allowed");
+//! # }
+//! ```
+
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::str::CharIndices;
+
+use crate::{EscapeErrorKind, skip_bytes, unescape_hex, unescape_oct, unescape_dec, unescape_unicode};
+#[cfg(feature = "alloc")]
+use crate::unescape_braced_multi;
+
+/**
+Runs a scanner on the rest of the string, then advances the iterator past everything it consumed,
+returning the scanner's result.
+
+# Examples
+```rust
+# use descape::scan;
+let mut iter = "41 and more".char_indices();
+assert_eq!(scan::consume(&mut iter, scan::hex), Ok('A'));
+assert_eq!(iter.as_str(), " and more");
+```
+ */
+pub fn consume<T>(iter: &mut CharIndices, scanner: impl FnOnce(&str) -> (T, usize)) -> T {
+    let (res, len) = scanner(iter.as_str());
+    skip_bytes(iter, len);
+    res
+}
+
+/**
+Scans the part of a `\xNN` escape after the `x`: exactly two hexadecimal digits.
+
+Returns the character and the amount of bytes that are part of the sequence,
+which is always the next two characters, or the rest of the string if it's shorter.
+
+# Errors
+Errors with [`EscapeErrorKind::InvalidHexDigit`] if either character isn't a hexadecimal digit,
+or [`EscapeErrorKind::Truncated`] if the string ends first.
+
+# Examples
+```rust
+# use descape::{scan, EscapeErrorKind};
+assert_eq!(scan::hex("7Fabc"), (Ok('\x7F'), 2));
+assert_eq!(scan::hex("G1"), (Err(EscapeErrorKind::InvalidHexDigit), 2));
+assert_eq!(scan::hex("4"), (Err(EscapeErrorKind::Truncated), 1));
+```
+ */
+pub fn hex(rest: &str) -> (Result<char, EscapeErrorKind>, usize) {
+    unescape_hex(rest)
+}
+
+/**
+Scans the part of a Unicode escape after the `u`: either `{HEX}`, or `NNNN`.
+
+A `NNNN` high surrogate immediately followed by a `\uNNNN` low surrogate is combined into the character the pair encodes,
+in which case the low surrogate is part of the sequence too.
+
+Returns the character and the amount of bytes that are part of the sequence.
+
+# Errors
+Errors if the digits are invalid, the string ends first,
+or the value isn't a Unicode scalar value.
+
+# Examples
+```rust
+# use descape::{scan, EscapeErrorKind};
+assert_eq!(scan::unicode("{1F600}!"), (Ok('\u{1F600}'), 7));
+assert_eq!(scan::unicode("0041!"), (Ok('A'), 4));
+assert_eq!(scan::unicode(r"D83D\uDE00!"), (Ok('\u{1F600}'), 10));
+assert_eq!(scan::unicode("D83D!"), (Err(EscapeErrorKind::LoneSurrogate(0xD83D)), 4));
+```
+ */
+pub fn unicode(rest: &str) -> (Result<char, EscapeErrorKind>, usize) {
+    unescape_unicode(rest)
+}
+
+/**
+Scans the part of a Unicode escape after the `u` like [`unicode`] does,
+but also allows several codepoints in one pair of braces, separated by spaces or tabs, like `{61 62 63}`.
+
+Each character is pushed onto `out`, and the amount of bytes that are part of the sequence is returned.
+Spaces or tabs are also allowed around the codepoints.
+
+# Errors
+Errors if there are no codepoints between the braces, or if any of them is invalid, like [`unicode`].
+Some of the characters may have been pushed onto `out` by then, which [`crate::EscapeWriter`] discards on error.
+
+# Examples
+```rust
+# use descape::{scan, EscapeErrorKind};
+let mut out = String::new();
+assert_eq!(scan::unicode_multi("{61 62\t63}!", &mut out), (Ok(()), 10));
+assert_eq!(out, "abc");
+assert_eq!(scan::unicode_multi("{ }", &mut out), (Err(EscapeErrorKind::InvalidHexDigit), 3));
+assert_eq!(scan::unicode_multi("{61 D83D}", &mut out), (Err(EscapeErrorKind::LoneSurrogate(0xD83D)), 9));
+```
+ */
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[cfg(feature = "alloc")]
+pub fn unicode_multi(rest: &str, out: &mut String) -> (Result<(), EscapeErrorKind>, usize) {
+    if rest.starts_with('{') {
+        return unescape_braced_multi(rest, out);
+    }
+    let (res, len) = unescape_unicode(rest);
+    (res.map(|chr| out.push(chr)), len)
+}
+
+/**
+Scans an octal escape of one to three digits, where `first` is the digit the escape handler was called with,
+and `rest` is the string after it.
+
+Returns the value and the amount of bytes of `rest` that are part of the sequence, from 0 to 2.
+The value can be up to `0o777`, so it's up to the caller whether that fits.
+
+# Errors
+Errors with [`EscapeErrorKind::UnknownEscape`] if `first` isn't an octal digit, consuming nothing.
+
+# Examples
+```rust
+# use descape::{scan, EscapeErrorKind};
+assert_eq!(scan::octal('1', "019"), (Ok(0o101), 2));
+assert_eq!(scan::octal('7', "8"), (Ok(0o7), 0));
+assert_eq!(scan::octal('8', "00"), (Err(EscapeErrorKind::UnknownEscape('8')), 0));
+```
+ */
+pub fn octal(first: char, rest: &str) -> (Result<u32, EscapeErrorKind>, usize) {
+    if !first.is_digit(8) {
+        return (Err(EscapeErrorKind::UnknownEscape(first)), 0);
+    }
+    let (value, len) = unescape_oct(first, rest);
+    (Ok(value), len)
+}
+
+/**
+Scans a decimal escape of one to three digits, where `first` is the digit the escape handler was called with,
+and `rest` is the string after it.
+
+Returns the value and the amount of bytes of `rest` that are part of the sequence, from 0 to 2.
+The value can be up to `999`, so it's up to the caller whether that fits.
+
+# Errors
+Errors with [`EscapeErrorKind::UnknownEscape`] if `first` isn't a decimal digit, consuming nothing.
+
+# Examples
+```rust
+# use descape::{scan, EscapeErrorKind};
+assert_eq!(scan::decimal('0', "659"), (Ok(65), 2));
+assert_eq!(scan::decimal('9', "x"), (Ok(9), 0));
+assert_eq!(scan::decimal('x', "00"), (Err(EscapeErrorKind::UnknownEscape('x')), 0));
+```
+ */
+pub fn decimal(first: char, rest: &str) -> (Result<u32, EscapeErrorKind>, usize) {
+    if !first.is_ascii_digit() {
+        return (Err(EscapeErrorKind::UnknownEscape(first)), 0);
+    }
+    let (value, len) = unescape_dec(first, rest);
+    (Ok(value), len)
+}
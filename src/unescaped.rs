@@ -0,0 +1,246 @@
+//! Wrappers around unescaped strings that are easy to compare and print.
+
+use alloc::{
+    borrow::Cow,
+    string::String
+};
+use core::{convert::TryFrom, fmt, ops::Deref};
+
+use crate::{InvalidEscape, UnescapeExt};
+
+/// An unescaped string, made by [`crate::UnescapeExt::unescape_wrapped`] or [`TryFrom<&str>`](TryFrom).
+///
+/// This is a thin wrapper around the [`Cow`] that [`crate::UnescapeExt::to_unescaped`] returns,
+/// which derefs to `str`, prints like one, and compares equal to `str`s and `String`s directly,
+/// without having to name the `Cow` or call `as_deref` first.
+///
+/// # Examples
+/// ```rust
+/// # use descape::UnescapeExt;
+/// # fn main() -> Result<(), descape::InvalidEscape> {
+/// let unescaped = r"caf\u{E9}\n".unescape_wrapped()?;
+/// assert_eq!(unescaped, "caf\u{E9}\n");
+/// assert_eq!(unescaped.len(), 6);
+/// assert_eq!(unescaped.to_string(), "caf\u{E9}\n");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Converting with [`TryFrom`] unescapes with [`crate::DefaultHandler`], keeping the borrow if nothing had to be unescaped:
+/// ```rust
+/// # use std::convert::TryFrom; use descape::Unescaped;
+/// let lines = [r"a\tb", "plain"].iter().copied().map(Unescaped::try_from).collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(lines, ["a\tb", "plain"]);
+/// assert!(lines[1].is_borrowed());
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Unescaped<'source>(pub Cow<'source, str>);
+
+impl<'source> Unescaped<'source> {
+    /// Unwraps the underlying [`Cow`].
+    #[must_use]
+    pub fn into_inner(self) -> Cow<'source, str> {
+        self.0
+    }
+
+    /// Turns this into an owned `String`, copying it only if it's still borrowed.
+    #[must_use]
+    pub fn into_owned_string(self) -> String {
+        self.0.into_owned()
+    }
+
+    /// Returns whether this still borrows from the original string, meaning nothing had to be unescaped.
+    #[must_use]
+    pub fn is_borrowed(&self) -> bool {
+        matches!(self.0, Cow::Borrowed(_))
+    }
+}
+
+impl Deref for Unescaped<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Unescaped<'_> {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Unescaped<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'source> From<Cow<'source, str>> for Unescaped<'source> {
+    fn from(string: Cow<'source, str>) -> Self {
+        Self(string)
+    }
+}
+
+impl<'source> TryFrom<&'source str> for Unescaped<'source> {
+    type Error = InvalidEscape;
+
+    fn try_from(string: &'source str) -> Result<Self, Self::Error> {
+        string.unescape_wrapped()
+    }
+}
+
+impl<'source> From<Unescaped<'source>> for Cow<'source, str> {
+    fn from(unescaped: Unescaped<'source>) -> Self {
+        unescaped.0
+    }
+}
+
+impl From<Unescaped<'_>> for String {
+    fn from(unescaped: Unescaped<'_>) -> Self {
+        unescaped.into_owned_string()
+    }
+}
+
+impl PartialEq<str> for Unescaped<'_> {
+    fn eq(&self, other: &str) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl PartialEq<&str> for Unescaped<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<String> for Unescaped<'_> {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<Unescaped<'_>> for str {
+    fn eq(&self, other: &Unescaped<'_>) -> bool {
+        *self == *other.0
+    }
+}
+
+impl PartialEq<Unescaped<'_>> for &str {
+    fn eq(&self, other: &Unescaped<'_>) -> bool {
+        **self == *other.0
+    }
+}
+
+impl PartialEq<Unescaped<'_>> for String {
+    fn eq(&self, other: &Unescaped<'_>) -> bool {
+        **self == *other.0
+    }
+}
+
+/// An owned unescaped string, made with [`TryFrom<&str>`](TryFrom).
+///
+/// This is like [`Unescaped`], but always owns its string, so it doesn't borrow from the original.
+/// It derefs to `str`, prints like one, and compares equal to `str`s and `String`s directly.
+///
+/// # Examples
+/// ```rust
+/// # use std::convert::TryFrom; use descape::UnescapedString;
+/// # fn main() -> Result<(), descape::InvalidEscape> {
+/// let unescaped = UnescapedString::try_from(r"caf\u{E9}\n")?;
+/// assert_eq!(unescaped, "caf\u{E9}\n");
+/// assert_eq!(unescaped.len(), 6);
+///
+/// let words: Vec<UnescapedString> = r"one\ttwo three".split(' ').map(UnescapedString::try_from).collect::<Result<_, _>>()?;
+/// assert_eq!(words, ["one\ttwo", "three"]);
+/// # Ok(())
+/// # }
+/// ```
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct UnescapedString(pub String);
+
+impl UnescapedString {
+    /// Unwraps the underlying [`String`].
+    #[must_use]
+    pub fn into_inner(self) -> String {
+        self.0
+    }
+}
+
+impl TryFrom<&str> for UnescapedString {
+    type Error = InvalidEscape;
+
+    fn try_from(string: &str) -> Result<Self, Self::Error> {
+        string.to_unescaped_string().map(Self)
+    }
+}
+
+impl Deref for UnescapedString {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for UnescapedString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for UnescapedString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<Unescaped<'_>> for UnescapedString {
+    fn from(unescaped: Unescaped<'_>) -> Self {
+        Self(unescaped.into_owned_string())
+    }
+}
+
+impl From<UnescapedString> for String {
+    fn from(unescaped: UnescapedString) -> Self {
+        unescaped.0
+    }
+}
+
+impl PartialEq<str> for UnescapedString {
+    fn eq(&self, other: &str) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl PartialEq<&str> for UnescapedString {
+    fn eq(&self, other: &&str) -> bool {
+        *self.0 == **other
+    }
+}
+
+impl PartialEq<String> for UnescapedString {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<UnescapedString> for str {
+    fn eq(&self, other: &UnescapedString) -> bool {
+        *self == *other.0
+    }
+}
+
+impl PartialEq<UnescapedString> for &str {
+    fn eq(&self, other: &UnescapedString) -> bool {
+        **self == *other.0
+    }
+}
+
+impl PartialEq<UnescapedString> for String {
+    fn eq(&self, other: &UnescapedString) -> bool {
+        *self == other.0
+    }
+}
@@ -0,0 +1,749 @@
+//! A configurable version of [`crate::DefaultHandler`].
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    borrow::Cow,
+    string::String
+};
+use core::str::CharIndices;
+
+use crate::{
+    EscapeErrorKind,
+    unescape_single, unescape_unicode, unescape_unicode_long, unescape_hex_mode, unescape_oct, unescape_dec, consume, unescape_control, unescape_braced
+};
+#[cfg(feature = "alloc")]
+use crate::{EscapeWriter, InvalidEscape, UnescapeExt, scan};
+
+/// What to do with an escape sequence that isn't recognized at all.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum UnknownEscapePolicy {
+    /// Error with [`EscapeErrorKind::UnknownEscape`].
+    Error,
+    /// Keep the sequence as it was written, so `\d` stays as `\d`.
+    KeepBackslash,
+    /// Remove the escape character, and keep the character after it, so `\d` becomes `d`.
+    DropBackslash,
+}
+
+impl Default for UnknownEscapePolicy {
+    fn default() -> Self {
+        Self::Error
+    }
+}
+
+/// What to do with an escape character at the very end of a string, with nothing after it.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum TrailingPolicy {
+    /// Error with [`EscapeErrorKind::Truncated`].
+    Error,
+    /// Keep the escape character as it was written, so `abc\` stays as `abc\`.
+    Literal,
+}
+
+impl Default for TrailingPolicy {
+    fn default() -> Self {
+        Self::Error
+    }
+}
+
+/// How octal escapes that don't fit in a byte, from `\400` to `\777`, are treated.
+///
+/// This applies to decimal escapes from [`Unescaper::decimal`] too, from `\256` to `\999`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum OctalMode {
+    /// Use the value as a codepoint, so `\777` becomes `\u{1FF}`.
+    Codepoint,
+    /// Error with [`EscapeErrorKind::OutOfRange`], like a byte string would.
+    Strict,
+    /// Wrap the value around to fit in a byte, like C does, so `\777` becomes `\u{FF}`.
+    Wrap,
+}
+
+impl Default for OctalMode {
+    fn default() -> Self {
+        Self::Codepoint
+    }
+}
+
+/// How many hexadecimal digits a `\xNN` escape reads, for [`Unescaper::hex_digits`].
+///
+/// However many digits are read, the value has to be a valid character, and `\x{HEX}` is unaffected.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum HexMode {
+    /// Read exactly this many digits, like the two of Rust's `\xNN`,
+    /// erroring with [`EscapeErrorKind::InvalidHexDigit`] or [`EscapeErrorKind::Truncated`] if there are fewer.
+    Exact(usize),
+    /// Read at least one digit, and up to this many, like the four of C#'s `\x`.
+    UpTo(usize),
+    /// Read every digit there is, like C does.
+    Greedy,
+}
+
+impl Default for HexMode {
+    fn default() -> Self {
+        Self::Exact(2)
+    }
+}
+
+/// A set of the escape sequences [`crate::DefaultHandler`] accepts, for turning them on and off with [`Unescaper::escapes`].
+///
+/// Sets can be combined with `|`, and removed from each other with `-`.
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::{Unescaper, Escapes};
+/// let unescaper = Unescaper::new().escapes(Escapes::ALL - Escapes::BACKTICK - Escapes::OCTAL);
+/// assert_eq!(unescaper.unescape(r"\t\x41").unwrap(), "\tA");
+/// unescaper.unescape(r"\11").expect_err("octal is disabled");
+/// unescaper.unescape(r"\`").expect_err("backticks are disabled");
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Escapes(u32);
+
+impl Escapes {
+    /// No escape sequences at all.
+    pub const NONE: Self = Self(0);
+    /// `\a`
+    pub const ALERT: Self = Self(1 << 0);
+    /// `\b`
+    pub const BACKSPACE: Self = Self(1 << 1);
+    /// `\t`
+    pub const TAB: Self = Self(1 << 2);
+    /// `\n`
+    pub const NEWLINE: Self = Self(1 << 3);
+    /// `\v`
+    pub const VERTICAL_TAB: Self = Self(1 << 4);
+    /// `\f`
+    pub const FORM_FEED: Self = Self(1 << 5);
+    /// `\r`
+    pub const CARRIAGE_RETURN: Self = Self(1 << 6);
+    /// `\e`
+    pub const ESCAPE: Self = Self(1 << 7);
+    /// `\'`
+    pub const SINGLE_QUOTE: Self = Self(1 << 8);
+    /// `\"`
+    pub const DOUBLE_QUOTE: Self = Self(1 << 9);
+    /// <code>&bsol;&grave;</code>
+    pub const BACKTICK: Self = Self(1 << 10);
+    /// `\\`, or two of the escape character in a row.
+    pub const BACKSLASH: Self = Self(1 << 11);
+    /// `\xNN`
+    pub const HEX: Self = Self(1 << 12);
+    /// `\o`, `\oo`, and `\ooo`, or their decimal versions with [`Unescaper::decimal`]
+    pub const OCTAL: Self = Self(1 << 13);
+    /// `\uXXXX`
+    pub const UNICODE_FIXED: Self = Self(1 << 14);
+    /// `\u{HEX}`
+    pub const UNICODE_BRACED: Self = Self(1 << 15);
+    /// `\UXXXXXXXX`
+    pub const UNICODE_LONG: Self = Self(1 << 16);
+    /// Every escape sequence [`crate::DefaultHandler`] accepts.
+    pub const ALL: Self = Self((1 << 17) - 1);
+    /// The escape sequences that are only one character after the backslash.
+    const SINGLE: Self = Self((1 << 12) - 1);
+
+    /// Checks whether every escape sequence in `other` is in this set.
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the escape sequences in either set.
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns the escape sequences in this set that aren't in `other`.
+    #[must_use]
+    pub const fn difference(self, other: Self) -> Self {
+        Self(self.0 & !other.0)
+    }
+
+    /// Finds which set the escape sequence starting with `chr` belongs to,
+    /// or [`Escapes::NONE`] if it isn't one of these.
+    fn of(chr: char, escape: char, rest: &str) -> Self {
+        match chr {
+            // The escape character escapes itself, and a backslash is only special when it's the escape character
+            c if c == escape => Self::BACKSLASH,
+            'a' => Self::ALERT,
+            'b' => Self::BACKSPACE,
+            't' => Self::TAB,
+            'n' => Self::NEWLINE,
+            'v' => Self::VERTICAL_TAB,
+            'f' => Self::FORM_FEED,
+            'r' => Self::CARRIAGE_RETURN,
+            'e' => Self::ESCAPE,
+            '\'' => Self::SINGLE_QUOTE,
+            '"' => Self::DOUBLE_QUOTE,
+            '`' => Self::BACKTICK,
+            // \x{HEX} is covered by `Unescaper::braced_x` instead
+            'x' if !rest.starts_with('{') => Self::HEX,
+            '0' ..= '9' => Self::OCTAL,
+            'u' if rest.starts_with('{') => Self::UNICODE_BRACED,
+            'u' => Self::UNICODE_FIXED,
+            'U' => Self::UNICODE_LONG,
+            _ => Self::NONE
+        }
+    }
+}
+
+impl Default for Escapes {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+/// Parses a set of single-character escapes from the characters that come after the backslash,
+/// so `ntr\"` is the set of `\n`, `\t`, `\r`, `\\`, and `\"`.
+///
+/// Errors with [`EscapeErrorKind::UnknownEscape`] for the first character that isn't a single-character escape,
+/// including the ones that start numeric escapes, like `x`. Those can be added with `|` instead.
+///
+/// # Examples
+/// ```rust
+/// # use descape::{Escapes, EscapeErrorKind};
+/// let escapes: Escapes = r#"ntr\""#.parse().unwrap();
+/// assert_eq!(escapes, Escapes::NEWLINE | Escapes::TAB | Escapes::CARRIAGE_RETURN | Escapes::BACKSLASH | Escapes::DOUBLE_QUOTE);
+/// assert_eq!("nx".parse::<Escapes>(), Err(EscapeErrorKind::UnknownEscape('x')));
+/// ```
+impl core::str::FromStr for Escapes {
+    type Err = EscapeErrorKind;
+
+    fn from_str(chars: &str) -> Result<Self, Self::Err> {
+        chars.chars().try_fold(Self::NONE, |set, chr| {
+            let escape = Self::of(chr, '\\', "");
+            if escape == Self::NONE || !Self::SINGLE.contains(escape) {
+                return Err(EscapeErrorKind::UnknownEscape(chr));
+            }
+            Ok(set | escape)
+        })
+    }
+}
+
+impl core::ops::BitOr for Escapes {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+impl core::ops::Sub for Escapes {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.difference(other)
+    }
+}
+
+/// A builder for tweaking the escape sequences [`crate::DefaultHandler`] accepts,
+/// without writing a whole [`crate::EscapeHandler`].
+///
+/// An `Unescaper` is an [`EscapeWriter`] itself, so it can be passed to [`UnescapeExt::to_unescaped_with`],
+/// or used directly through [`Unescaper::unescape`].
+///
+/// # Examples
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// # use descape::Unescaper;
+/// let unescaper = Unescaper::new()
+///     .allow_octal(false)
+///     .strict_x_ascii(true);
+///
+/// assert_eq!(unescaper.unescape(r"\x41\n").unwrap(), "A\n");
+/// unescaper.unescape(r"\101").expect_err("octal is disabled");
+/// unescaper.unescape(r"\xFF").expect_err("\\xFF isn't ASCII");
+/// # }
+/// ```
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[allow(clippy::struct_excessive_bools)] // These are all independent switches
+pub struct Unescaper {
+    escape: char,
+    escapes: Escapes,
+    octal_mode: OctalMode,
+    decimal: bool,
+    strict_nul: bool,
+    strict_x_ascii: bool,
+    hex_digits: HexMode,
+    braced_x: bool,
+    control: bool,
+    #[cfg(feature = "alloc")]
+    multi_codepoint: bool,
+    #[cfg(feature = "alloc")]
+    max_output_len: usize,
+    #[cfg(feature = "alloc")]
+    normalize_newlines: bool,
+    #[cfg(feature = "alloc")]
+    trailing: TrailingPolicy,
+    unknown: UnknownEscapePolicy,
+}
+
+impl Default for Unescaper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Unescaper {
+    /// Creates an unescaper that behaves exactly like [`crate::DefaultHandler`].
+    #[must_use]
+    pub const fn new() -> Self {
+        Self {
+            escape: '\\',
+            escapes: Escapes::ALL,
+            octal_mode: OctalMode::Codepoint,
+            decimal: false,
+            strict_nul: false,
+            strict_x_ascii: false,
+            hex_digits: HexMode::Exact(2),
+            braced_x: false,
+            control: false,
+            #[cfg(feature = "alloc")]
+            multi_codepoint: false,
+            #[cfg(feature = "alloc")]
+            max_output_len: usize::MAX,
+            #[cfg(feature = "alloc")]
+            normalize_newlines: false,
+            #[cfg(feature = "alloc")]
+            trailing: TrailingPolicy::Error,
+            unknown: UnknownEscapePolicy::Error,
+        }
+    }
+
+    /// Sets the character that starts an escape sequence.
+    /// Two of them in a row unescape to a single one, as with `\\`.
+    ///
+    /// Defaults to `\`.
+    ///
+    /// Only [`Unescaper::unescape`] looks for this character.
+    /// Passing an `Unescaper` as a handler, like to [`UnescapeExt::to_unescaped_with`], [`UnescapeExt::unescape_until`],
+    /// [`UnescapeExt::unescape_into_with`], or `unescape_reader`, still looks for `\`;
+    /// use [`UnescapeExt::to_unescaped_with_escape_char`] with the same character to unescape with both.
+    ///
+    /// A backslash after the escape character isn't an escape sequence, so `^\` is unknown, and `^^` is a literal `^`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::Unescaper;
+    /// let unescaper = Unescaper::new().escape_char('^');
+    /// assert_eq!(unescaper.unescape(r"C:\Users ^^ ^x41^t").unwrap(), "C:\\Users ^ A\t");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn escape_char(self, escape: char) -> Self {
+        Self { escape, ..self }
+    }
+
+    /// Sets which of the escape sequences [`crate::DefaultHandler`] accepts are valid.
+    /// Any others are treated like unknown escapes, as set by [`Unescaper::unknown_escape`].
+    ///
+    /// This doesn't affect the escapes that are off by default, like [`Unescaper::braced_x`].
+    ///
+    /// Defaults to [`Escapes::ALL`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{Unescaper, Escapes};
+    /// let unescaper = Unescaper::new().escapes(Escapes::NEWLINE | Escapes::TAB | Escapes::BACKSLASH);
+    /// assert_eq!(unescaper.unescape(r"\t\\\n").unwrap(), "\t\\\n");
+    /// unescaper.unescape(r"\x41").expect_err("only \\n, \\t, and \\\\ are enabled");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn escapes(self, escapes: Escapes) -> Self {
+        Self { escapes, ..self }
+    }
+
+    /// Sets whether octal escapes (`\o`, `\oo`, and `\ooo`) are valid.
+    ///
+    /// Defaults to `true`.
+    #[must_use]
+    pub const fn allow_octal(self, allow: bool) -> Self {
+        let escapes = if allow {
+            self.escapes.union(Escapes::OCTAL)
+        } else {
+            self.escapes.difference(Escapes::OCTAL)
+        };
+        Self { escapes, ..self }
+    }
+
+    /// Sets how octal escapes above `\377` are treated.
+    ///
+    /// Defaults to [`OctalMode::Codepoint`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{Unescaper, OctalMode};
+    /// assert_eq!(Unescaper::new().unescape(r"\777").unwrap(), "\u{1FF}");
+    /// assert_eq!(Unescaper::new().octal_mode(OctalMode::Wrap).unescape(r"\777").unwrap(), "\u{FF}");
+    /// Unescaper::new().octal_mode(OctalMode::Strict).unescape(r"\777").expect_err("\\777 doesn't fit in a byte");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn octal_mode(self, mode: OctalMode) -> Self {
+        Self { octal_mode: mode, ..self }
+    }
+
+    /// Sets whether escapes made of digits are decimal instead of octal, so `\065` is `A`,
+    /// like in some printer languages and old Pascal dialects.
+    /// These take one to three digits, just like octal escapes, and follow [`Unescaper::octal_mode`] too.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{Unescaper, OctalMode};
+    /// let decimal = Unescaper::new().decimal(true);
+    /// assert_eq!(decimal.unescape(r"\065\66\9").unwrap(), "AB\t");
+    /// assert_eq!(decimal.octal_mode(OctalMode::Wrap).unescape(r"\321").unwrap(), "A");
+    /// decimal.octal_mode(OctalMode::Strict).unescape(r"\999").expect_err("\\999 doesn't fit in a byte");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn decimal(self, decimal: bool) -> Self {
+        Self { decimal, ..self }
+    }
+
+    /// Sets whether `\0` can only stand for NUL, like in C++ and strict mode JavaScript.
+    /// If set, `\0` followed by another octal digit, or decimal digit with [`Unescaper::decimal`],
+    /// is invalid, erroring with [`EscapeErrorKind::UnknownEscape`],
+    /// instead of being the start of a longer octal escape like `\012`.
+    ///
+    /// `\0` followed by anything else, like `\08`, is still NUL followed by that character.
+    /// Other escapes made of digits aren't affected; they can be turned off with [`Unescaper::allow_octal`],
+    /// which turns off `\0` as well.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{Unescaper, EscapeErrorKind};
+    /// let strict = Unescaper::new().strict_nul(true);
+    /// assert_eq!(strict.unescape(r"\0\08\101").unwrap(), "\0\08A");
+    ///
+    /// let err = strict.unescape(r"ab\012").unwrap_err();
+    /// assert_eq!((err.index, err.kind), (2, EscapeErrorKind::UnknownEscape('0')));
+    ///
+    /// assert_eq!(Unescaper::new().unescape(r"\012").unwrap(), "\n");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn strict_nul(self, strict: bool) -> Self {
+        Self { strict_nul: strict, ..self }
+    }
+
+    /// Sets whether `\xNN` escapes must be ASCII, like in Rust string literals.
+    /// If set, any escape above `\x7F` is invalid, erroring with [`EscapeErrorKind::OutOfRange`].
+    ///
+    /// This only affects strings; byte strings use `\xNN` for raw bytes, where `\xFF` is always valid.
+    /// [`crate::RustHandler`] always behaves like this.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{Unescaper, EscapeErrorKind};
+    /// let strict = Unescaper::new().strict_x_ascii(true);
+    /// assert_eq!(strict.unescape(r"\x7F").unwrap(), "\x7F");
+    ///
+    /// let err = strict.unescape(r"ab\xFF").unwrap_err();
+    /// assert_eq!((err.index, err.kind), (2, EscapeErrorKind::OutOfRange(0xFF)));
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn strict_x_ascii(self, strict: bool) -> Self {
+        Self { strict_x_ascii: strict, ..self }
+    }
+
+    /// Sets how many hexadecimal digits a `\xNN` escape reads.
+    ///
+    /// Defaults to [`HexMode::Exact`] with 2 digits.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{Unescaper, HexMode};
+    /// let unescaper = Unescaper::new().hex_digits(HexMode::UpTo(4));
+    /// assert_eq!(unescaper.unescape(r"\x41\x7\x20AC!").unwrap(), "A\x07\u{20AC}!");
+    /// assert_eq!(Unescaper::new().hex_digits(HexMode::Greedy).unescape(r"\x0000041").unwrap(), "A");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn hex_digits(self, mode: HexMode) -> Self {
+        Self { hex_digits: mode, ..self }
+    }
+
+    /// Sets whether `\x{HEX}` escapes are valid, like in Perl.
+    /// These work exactly like `\u{HEX}`, and aren't affected by [`Unescaper::strict_x_ascii`].
+    /// `\xNN` escapes stay valid either way.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::Unescaper;
+    /// let unescaper = Unescaper::new().braced_x(true);
+    /// assert_eq!(unescaper.unescape(r"\x{1F600}\x41").unwrap(), "\u{1F600}A");
+    /// unescaper.unescape(r"\x{}").expect_err("there's no codepoint");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn braced_x(self, allow: bool) -> Self {
+        Self { braced_x: allow, ..self }
+    }
+
+    /// Sets whether `\cX` control character escapes are valid, as parsed by [`crate::unescape_control`].
+    ///
+    /// Defaults to `false`.
+    #[must_use]
+    pub const fn control_escapes(self, allow: bool) -> Self {
+        Self { control: allow, ..self }
+    }
+
+    /// Sets whether `\u{HEX}` escapes can hold several codepoints, separated by spaces or tabs,
+    /// so `\u{61 62 63}` is `abc`, like in Ruby. These are parsed by [`crate::scan::unicode_multi`].
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use descape::Unescaper;
+    /// let unescaper = Unescaper::new().multi_codepoint(true);
+    /// assert_eq!(unescaper.unescape(r"\u{48 69}\u{21}").unwrap(), "Hi!");
+    /// unescaper.unescape(r"\u{ }").expect_err("there's no codepoint");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub const fn multi_codepoint(self, allow: bool) -> Self {
+        Self { multi_codepoint: allow, ..self }
+    }
+
+    /// Sets the longest the unescaped string can get, in bytes,
+    /// erroring with [`EscapeErrorKind::OutputTooLong`] at whatever makes it any longer.
+    ///
+    /// This is checked before copying over each run of text between escape sequences, and after writing each sequence,
+    /// against only what's been written while unescaping this string, so the output never grows much past the limit.
+    /// Text that doesn't fit is reported at the first character that doesn't, while a sequence is reported as a whole.
+    /// Lossy unescaping replaces sequences that don't fit like any other invalid sequence, and doesn't check the text between them.
+    ///
+    /// Defaults to `usize::MAX`, which is never reached.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use descape::{Unescaper, EscapeErrorKind};
+    /// let unescaper = Unescaper::new().multi_codepoint(true).max_output_len(8);
+    /// assert_eq!(unescaper.unescape(r"\u{61 62 63}").unwrap(), "abc");
+    /// let err = unescaper.unescape(r"\u{61 62 63}\u{1F600 1F600}").unwrap_err();
+    /// assert_eq!(err.kind, EscapeErrorKind::OutputTooLong);
+    /// assert_eq!(err.index, 12);
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub const fn max_output_len(self, max: usize) -> Self {
+        Self { max_output_len: max, ..self }
+    }
+
+    /// Sets whether every `\r\n` in the unescaped string is turned into a single `\n`.
+    ///
+    /// This happens after unescaping, so it applies to line endings written out in the string,
+    /// ones made of escapes like `\\r\\n`, and ones made of both, like `\\r` followed by a newline.
+    /// A `\r` on its own is kept either way.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// Only [`Unescaper::unescape`] normalizes newlines;
+    /// passing an `Unescaper` to [`UnescapeExt::to_unescaped_with`] leaves them as they are.
+    /// [`Unescaper::max_output_len`] is checked while unescaping, so it counts every `\r\n` as two bytes.
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use descape::Unescaper;
+    /// let unescaper = Unescaper::new().normalize_newlines(true);
+    /// assert_eq!(unescaper.unescape("one\r\ntwo\\r\\nthree\\rfour").unwrap(), "one\ntwo\nthree\rfour");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub const fn normalize_newlines(self, normalize: bool) -> Self {
+        Self { normalize_newlines: normalize, ..self }
+    }
+
+    /// Sets what happens to an escape character at the very end of the string.
+    ///
+    /// Defaults to [`TrailingPolicy::Error`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # use descape::{Unescaper, TrailingPolicy};
+    /// let unescaper = Unescaper::new().trailing_backslash(TrailingPolicy::Literal);
+    /// assert_eq!(unescaper.unescape(r"one\ttwo\").unwrap(), "one\ttwo\\");
+    /// ```
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    #[must_use]
+    pub const fn trailing_backslash(self, policy: TrailingPolicy) -> Self {
+        Self { trailing: policy, ..self }
+    }
+
+    /// Sets what happens to escape sequences that aren't recognized.
+    ///
+    /// Defaults to [`UnknownEscapePolicy::Error`].
+    ///
+    /// # Examples
+    /// ```rust
+    /// # #[cfg(feature = "alloc")] {
+    /// # use descape::{Unescaper, UnknownEscapePolicy};
+    /// let unescaper = Unescaper::new().unknown_escape(UnknownEscapePolicy::KeepBackslash);
+    /// assert_eq!(unescaper.unescape(r"\d+\t\w").unwrap(), "\\d+\t\\w");
+    /// # }
+    /// ```
+    #[must_use]
+    pub const fn unknown_escape(self, policy: UnknownEscapePolicy) -> Self {
+        Self { unknown: policy, ..self }
+    }
+
+    /**
+    Unescapes a string with this configuration, returning an [`alloc::borrow::Cow`].
+    Will only allocate if the string has any escape sequences, or any line endings to normalize.
+
+    # Errors
+    Errors if there's an invalid escape sequence in the string.
+    Passes back the byte index of the invalid character, and why it was invalid.
+     */
+    #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+    #[cfg(feature = "alloc")]
+    pub fn unescape<'source>(&self, string: &'source str) -> Result<Cow<'source, str>, InvalidEscape> {
+        let unescaped = string.to_unescaped_with_escape_char(self.escape, *self)?;
+        if self.normalize_newlines && unescaped.contains("\r\n") {
+            return Ok(Cow::Owned(unescaped.replace("\r\n", "\n")));
+        }
+        Ok(unescaped)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl EscapeWriter for Unescaper {
+    fn write_escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind> {
+        self.write_escape_with('\\', idx, chr, iter, out)
+    }
+
+    fn write_escape_with(
+        &mut self, escape: char, _: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String
+    ) -> Result<(), EscapeErrorKind> {
+        let multi = self.multi_codepoint
+            && chr == 'u'
+            && self.escapes.contains(Escapes::UNICODE_BRACED)
+            && iter.as_str().starts_with('{');
+        if multi {
+            // This can be more than one character, so it's handled here instead of in `unescape_known`
+            return consume(iter, |rest| scan::unicode_multi(rest, out));
+        }
+        match self.unescape_known(chr, iter)? {
+            Some(chr) => out.push(chr),
+            None => match self.unknown {
+                UnknownEscapePolicy::Error => return Err(EscapeErrorKind::UnknownEscape(chr)),
+                UnknownEscapePolicy::KeepBackslash => {
+                    // The sequence is kept as it was written, whatever `self.escape` is
+                    out.push(escape);
+                    out.push(chr);
+                },
+                UnknownEscapePolicy::DropBackslash => out.push(chr),
+            }
+        }
+        Ok(())
+    }
+
+    fn trailing_backslash(&self) -> TrailingPolicy {
+        self.trailing
+    }
+
+    fn max_output_len(&self) -> usize {
+        self.max_output_len
+    }
+}
+
+impl Unescaper {
+    /// Unescapes the escape sequence starting with `chr`,
+    /// returning `None` if the sequence isn't recognized at all.
+    pub(crate) fn unescape_known(self, chr: char, iter: &mut CharIndices) -> Result<Option<char>, EscapeErrorKind> {
+        if !self.escapes.contains(Escapes::of(chr, self.escape, iter.as_str())) {
+            return Ok(None);
+        }
+        if chr == self.escape {
+            return Ok(Some(chr));
+        }
+        if chr == '\\' {
+            // With another escape character, `^\` isn't how a backslash is written
+            return Ok(None);
+        }
+        if let Some(chr) = unescape_single(chr) {
+            return Ok(Some(chr));
+        }
+        Ok( match chr {
+            'u' => {
+                Some(consume(iter, unescape_unicode)?)
+            },
+            'U' => {
+                Some(consume(iter, unescape_unicode_long)?)
+            },
+            'x' if self.braced_x && iter.as_str().starts_with('{') => {
+                Some(consume(iter, unescape_braced)?)
+            },
+            'x' => {
+                let res = consume(iter, |rest| unescape_hex_mode(rest, self.hex_digits))?;
+                if self.strict_x_ascii && !res.is_ascii() {
+                    return Err(EscapeErrorKind::OutOfRange(res as u32));
+                }
+                Some(res)
+            },
+            'c' if self.control => {
+                let rest = iter.as_str();
+                match (unescape_control(iter), rest.chars().next()) {
+                    (Some(chr), _) => Some(chr),
+                    (None, Some(chr)) => return Err(EscapeErrorKind::UnknownEscape(chr)),
+                    (None, None) => return Err(EscapeErrorKind::Truncated),
+                }
+            },
+            '0' if self.strict_nul && iter.as_str().starts_with(|next: char| next.is_digit(self.radix())) => {
+                return Err(EscapeErrorKind::UnknownEscape('0'));
+            },
+            c if self.decimal && c.is_ascii_digit() => {
+                let codepoint = consume(iter, |rest| unescape_dec(c, rest));
+                Some(self.numeric(codepoint)?)
+            },
+            c if c.is_digit(8) => {
+                let codepoint = consume(iter, |rest| unescape_oct(c, rest));
+                Some(self.numeric(codepoint)?)
+            },
+            _ => None,
+        } )
+    }
+
+    /// The radix of escapes made of digits.
+    const fn radix(self) -> u32 {
+        if self.decimal { 10 } else { 8 }
+    }
+
+    /// Turns the value of an octal or decimal escape into a character, following [`Unescaper::octal_mode`].
+    fn numeric(self, codepoint: u32) -> Result<char, EscapeErrorKind> {
+        let codepoint = match self.octal_mode {
+            OctalMode::Strict if codepoint > 0xFF => return Err(EscapeErrorKind::OutOfRange(codepoint)),
+            OctalMode::Wrap => codepoint & 0xFF,
+            OctalMode::Codepoint | OctalMode::Strict => codepoint,
+        };
+        // At most \999, which is always a valid codepoint
+        Ok(char::from_u32(codepoint).unwrap_or(char::REPLACEMENT_CHARACTER))
+    }
+}
@@ -0,0 +1,207 @@
+#![cfg(feature = "alloc")]
+
+use std::borrow::Cow;
+use descape::{UnescapeBytesExt, ByteIndices, EscapeErrorKind};
+
+macro_rules! ensure_err {
+    ($($name: ident),+) => {$(
+        assert_eq!($name.to_unescaped_bytes().map_err(|err| err.index), Err(0), "{} parsed successfully when invalid", stringify!($name));
+    )+};
+}
+
+#[test]
+fn test_escapes() {
+    static ESCAPED: &[u8] =
+        br#"\a \b \t \n \v\xC3\f \r \e \' \" \` \\ \u{0} \u{21}\u{433} ! \x7E \xFF \0 \11 \100 \377 \U0001F600"#;
+    static UNESCAPED: &[u8] =
+        b"\x07 \x08 \t \n \x0B\xC3\x0C \x0D \x1B \' \" ` \\ \0 !\xD0\xB3 ! \x7E \xFF \0 \t @ \xFF \xF0\x9F\x98\x80";
+    static NO_ESCAPES: &[u8] = b"No escapes here! \xFF\xFE";
+    static BAD_ESCAPE: &[u8] = br"\Z";
+    static CUT_ESCAPE: &[u8] = br"\";
+    static BAD_UNICODE: &[u8] = br"\u{+41}";
+    static EMPTY_UNICODE: &[u8] = br"\u{}";
+    static BAD_HEX: &[u8] = br"\x+F";
+    static CUT_HEX: &[u8] = br"\xA";
+    static CUT_LONG_UNICODE: &[u8] = br"\U0001F60";
+    static BIG_OCTAL: &[u8] = br"\400";
+    static NON_UNICODE: &[u8] = br"\u{D800}";
+
+    assert_eq!(
+        ESCAPED.to_unescaped_bytes()
+            .expect("should not reject legal escaped string"),
+        Cow::Owned::<'_, [u8]>(UNESCAPED.to_vec())
+    );
+
+    assert_eq!(
+        NO_ESCAPES.to_unescaped_bytes()
+            .expect("should not reject legal escaped string"),
+        Cow::Borrowed(NO_ESCAPES)
+    );
+
+    ensure_err!(
+        BAD_ESCAPE,
+        CUT_ESCAPE,
+        BAD_UNICODE,
+        EMPTY_UNICODE,
+        BAD_HEX,
+        CUT_HEX,
+        CUT_LONG_UNICODE,
+        BIG_OCTAL,
+        NON_UNICODE
+    );
+
+    assert_eq!(
+        br"ab\n\".to_unescaped_bytes().map_err(|err| err.index),
+        Err(4),
+        "trailing backslash reported at the wrong index"
+    );
+
+    assert_eq!(
+        BIG_OCTAL.to_unescaped_bytes().unwrap_err().kind,
+        EscapeErrorKind::OutOfRange(0o400),
+        "octal escape out of range gave the wrong error"
+    );
+
+    assert_eq!(
+        br"\uD83D\uDE00".to_unescaped_bytes().expect("surrogate pairs should be valid"),
+        Cow::Owned::<'_, [u8]>(b"\xF0\x9F\x98\x80".to_vec())
+    );
+    assert_eq!(
+        br"\uD83DA".to_unescaped_bytes().unwrap_err().kind,
+        EscapeErrorKind::LoneSurrogate(0xD83D),
+        "unpaired high surrogate gave the wrong error"
+    );
+
+    for (string, len) in [
+        (&br"\q"[..], 2),
+        (br"\", 1),
+        (br"\xA", 3),
+        (br"\xGG\n", 4),
+        (br"\u{ZZZ}\n", 7),
+        (br"\u{41", 5),
+        (br"\u12", 4),
+        (br"\777", 4),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_bytes().unwrap_err().len,
+            *len,
+            "{:?} gave the wrong span", string
+        );
+    }
+}
+
+fn custom_esc(_: usize, byte: u8, iter: &mut ByteIndices<'_>, out: &mut Vec<u8>) -> Result<(), ()> {
+    if byte == b'T' {
+        let (_, next) = iter.next().ok_or(())?;
+        out.extend_from_slice(match next {
+            b'a' => b"gg",
+            b'o' => b"p",
+            _ => Err(())?
+        });
+    }
+    Ok(())
+}
+
+#[test]
+fn test_customs() {
+    br"Hello \T world".to_unescaped_bytes_with(custom_esc)
+        .expect_err(r"custom escape should fail for \T");
+    assert_eq!(
+        br"Spam E\Tas".to_unescaped_bytes_with(custom_esc).expect(r"custom escape should succeed for \Ta"),
+        Cow::<'static, [u8]>::Owned(b"Spam Eggs".to_vec()),
+        "custom escape gave incorrect result"
+    );
+    assert_eq!(
+        br"Bee\To \n Boop".to_unescaped_bytes_with(custom_esc).expect(r"custom escape should succeed for \To"),
+        Cow::<'static, [u8]>::Owned(b"Beep  Boop".to_vec()),
+        "custom escape gave incorrect result"
+    );
+}
+
+#[test]
+fn test_percent() {
+    use descape::{PercentHandler, InvalidEscape};
+
+    assert_eq!(
+        b"a%20b%2Fc%2fd%00%FF".to_unescaped_bytes_with_escape_byte(b'%', PercentHandler).expect("percent escapes should be valid"),
+        Cow::Owned::<'_, [u8]>(b"a b/c/d\0\xFF".to_vec()),
+        "percent escapes gave incorrect result"
+    );
+    assert_eq!(
+        br"no\npercents".to_unescaped_bytes_with_escape_byte(b'%', PercentHandler).expect("backslashes aren't escapes here"),
+        Cow::Borrowed(br"no\npercents")
+    );
+    for (bytes, index, kind, len) in [
+        (&b"ab%"[..], 2, EscapeErrorKind::Truncated, 1),
+        (b"ab%4", 2, EscapeErrorKind::Truncated, 2),
+        (b"ab%4G", 2, EscapeErrorKind::InvalidHexDigit, 3),
+        (b"ab%G4", 2, EscapeErrorKind::InvalidHexDigit, 3),
+        (b"ab%+1", 2, EscapeErrorKind::InvalidHexDigit, 3),
+        (b"%41%", 3, EscapeErrorKind::Truncated, 1),
+    ].iter() {
+        assert_eq!(
+            bytes.to_unescaped_bytes_with_escape_byte(b'%', PercentHandler),
+            Err(InvalidEscape::with_kind(*index, *kind).with_len(*len)),
+            "{:?} gave the wrong error", bytes
+        );
+    }
+}
+
+#[test]
+fn test_escape_byte() {
+    assert_eq!(
+        b"^x41^^^n\\n".to_unescaped_bytes_with_escape_byte(b'^', |_, byte, _: &mut ByteIndices, out: &mut Vec<u8>| {
+            out.push(if byte == b'n' { b'\n' } else { byte });
+            Ok(())
+        }).expect("custom escape byte should be valid"),
+        Cow::Owned::<'_, [u8]>(b"x41^\n\\n".to_vec())
+    );
+}
+
+#[test]
+fn test_strict_hex_is_str_only() {
+    use descape::{UnescapeExt, Unescaper};
+
+    // Strict \x only applies to strings, as \xFF is a perfectly fine byte
+    Unescaper::new().strict_x_ascii(true).unescape(r"\xFF").expect_err(r"\xFF isn't ASCII");
+    assert_eq!(br"\xFF\x80".to_unescaped_bytes().as_deref(), Ok(&b"\xFF\x80"[..]));
+    assert_eq!(r"\xFF".to_unescaped().as_deref(), Ok("\u{FF}"));
+}
+
+#[test]
+fn test_python_bytes() {
+    use descape::{PythonBytesHandler, InvalidEscape};
+
+    assert_eq!(
+        br"\x00\xff".to_unescaped_bytes_with(PythonBytesHandler).expect("Python escapes should be valid"),
+        Cow::Owned::<'_, [u8]>(vec![0x00, 0xFF]),
+        "Python hex escapes gave incorrect result"
+    );
+    for (bytes, unescaped) in [
+        (&br#"\a\b\f\n\r\t\v\\\'\""#[..], &b"\x07\x08\x0C\n\r\t\x0B\\'\""[..]),
+        (br"\101\0\18\777\400", b"A\0\x018\xFF\0"),
+        // Unicode escapes only mean something in str literals
+        (br"A\U0001F600\N{BULLET}", br"A\U0001F600\N{BULLET}"),
+        (br"\d\e\?\xff", b"\\d\\e\\?\xFF"),
+        (b"a\\\nb\\\r\nc", b"abc"),
+    ].iter() {
+        assert_eq!(
+            bytes.to_unescaped_bytes_with(PythonBytesHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", bytes
+        );
+    }
+
+    for (bytes, kind, len) in [
+        (&br"ab\x"[..], EscapeErrorKind::Truncated, 2),
+        (br"ab\x4", EscapeErrorKind::Truncated, 3),
+        (br"ab\x4G", EscapeErrorKind::InvalidHexDigit, 4),
+        (br"ab\", EscapeErrorKind::Truncated, 1),
+    ].iter() {
+        assert_eq!(
+            bytes.to_unescaped_bytes_with(PythonBytesHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", bytes
+        );
+    }
+}
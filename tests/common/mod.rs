@@ -0,0 +1,43 @@
+//! Helpers shared between the tests that run on generated input.
+
+// Not every test uses every helper
+#![allow(dead_code)]
+
+/// A small xorshift generator, so the cases don't need any dependencies.
+pub struct Rng(pub u64);
+
+impl Rng {
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn below(&mut self, max: usize) -> usize {
+        (self.next() % max as u64) as usize
+    }
+
+    /// Picks a character, favoring ASCII, which is where most of the escapes are.
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn char(&mut self) -> char {
+        let max = match self.below(4) {
+            0 | 1 => 0x80,
+            2 => 0x800,
+            _ => 0x11_0000,
+        };
+        // Surrogates aren't characters, so try again on those
+        loop {
+            if let Some(chr) = core::char::from_u32(self.below(max) as u32) {
+                return chr;
+            }
+        }
+    }
+
+    /// Makes a string of up to `max_len` random characters.
+    pub fn string(&mut self, max_len: usize) -> String {
+        let len = self.below(max_len + 1);
+        (0 .. len).map(|_| self.char()).collect()
+    }
+}
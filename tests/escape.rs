@@ -0,0 +1,176 @@
+#![cfg(feature = "alloc")]
+
+use std::borrow::Cow;
+use descape::{EscapeExt, UnescapeExt, DefaultEscaper, ShortestEscaper, RustHandler, JsonHandler, JsonEscaper, AsciiEscaper};
+
+#[test]
+fn test_escapes() {
+    static RAW: &str =
+        "\x07 \x08 \t \n \x0B❤️\x0C \r \x1B \' \" ` \\ \0 \u{7F} \u{85} \u{FFFD}";
+    static ESCAPED: &str =
+        r#"\a \b \t \n \v❤️\f \r \e ' \" ` \\ \x00 \x7F \x85 �"#;
+    static NO_ESCAPES: &str = "No escapes here! ❤️";
+
+    assert_eq!(
+        RAW.to_escaped(),
+        Cow::Owned::<'_, str>(ESCAPED.to_string())
+    );
+
+    assert_eq!(
+        NO_ESCAPES.to_escaped(),
+        Cow::Borrowed(NO_ESCAPES)
+    );
+
+    assert_eq!(
+        RAW.to_escaped().to_unescaped().expect("escaped output should be valid"),
+        RAW,
+        "escaping did not round trip"
+    );
+}
+
+#[test]
+fn test_shortest_escaped() {
+    // Every character gets the shortest escape DefaultHandler understands
+    for (raw, escaped) in [
+        ("\x07\x08\t\n\x0B\x0C\r\x1B", r"\a\b\t\n\v\f\r\e"),
+        ("\0\x01\x1F\x7F\u{80}\u{9F}", r"\x00\x01\x1F\x7F\x80\x9F"),
+        ("\u{A0}\u{2028}é", "\u{A0}\u{2028}é"),
+    ].iter() {
+        assert_eq!(raw.to_escaped(), *escaped, "{:?} gave incorrect result", raw);
+        assert_eq!(escaped.to_unescaped().as_deref(), Ok(*raw), "{:?} did not round trip", escaped);
+    }
+
+    static QUOTES: &str = r#""'`\"#;
+    for (escaper, escaped) in [
+        (ShortestEscaper::new(), r#"\"'`\\"#),
+        (ShortestEscaper::new().double_quotes(false), r#""'`\\"#),
+        (ShortestEscaper::new().double_quotes(false).single_quotes(true), r#""\'`\\"#),
+        (ShortestEscaper::new().single_quotes(true).backticks(true), r#"\"\'\`\\"#),
+    ].iter() {
+        let result = QUOTES.to_escaped_with(*escaper);
+        assert_eq!(result, *escaped, "{:?} gave incorrect result", escaper);
+        assert_eq!(result.to_unescaped().as_deref(), Ok(QUOTES), "{:?} did not round trip", escaper);
+    }
+    assert_eq!(ShortestEscaper::default(), ShortestEscaper::new());
+}
+
+fn json_ish(chr: char, out: &mut String) -> bool {
+    match chr {
+        '/' => out.push_str("\\/"),
+        // Deletes the character entirely
+        '\0' => {},
+        _ => return false
+    }
+    true
+}
+
+#[test]
+fn test_customs() {
+    assert_eq!(
+        "a/b".to_escaped_with(json_ish),
+        Cow::<'static, str>::Owned(String::from(r"a\/b")),
+        "custom escaper gave incorrect result"
+    );
+    assert_eq!(
+        "no slashes".to_escaped_with(json_ish),
+        Cow::Borrowed("no slashes"),
+        "custom escaper allocated without escaping"
+    );
+    assert_eq!(
+        "\0abc\0".to_escaped_with(json_ish),
+        Cow::<'static, str>::Owned(String::from("abc")),
+        "custom escaper did not remove characters"
+    );
+    assert_eq!(
+        "ab\0".to_escaped_with(json_ish),
+        Cow::<'static, str>::Owned(String::from("ab")),
+        "custom escaper did not remove trailing character"
+    );
+}
+
+#[test]
+fn test_escaped_default() {
+    assert_eq!("No escapes here!".to_escaped_default(), Cow::Borrowed("No escapes here!"));
+
+    // Every ASCII character, then a spread of the rest of the scalar values
+    let chars = (0 ..= 0x10FFFF).filter(|codepoint| *codepoint < 0x80 || codepoint % 97 == 0).filter_map(char::from_u32);
+    for chr in chars {
+        let raw = format!("a{}b", chr);
+        let escaped = raw.to_escaped_default();
+        assert_eq!(escaped, raw.escape_default().to_string(), "{:?} was escaped differently from the standard library", chr);
+        assert_eq!(
+            escaped.to_unescaped_with(RustHandler).as_deref(),
+            Ok(&*raw),
+            "{:?} did not round trip", chr
+        );
+    }
+}
+
+#[test]
+fn test_shell_escaped() {
+    for (raw, quoted) in [
+        ("", "''"),
+        ("two words", "'two words'"),
+        ("it's", r"'it'\''s'"),
+        ("''", r"''\'''\'''"),
+        ("$(rm -rf ~)", "'$(rm -rf ~)'"),
+        ("tab\there", "'tab\there'"),
+        ("café", "'café'"),
+    ].iter() {
+        assert_eq!(raw.to_shell_escaped(), Cow::<'static, str>::Owned(quoted.to_string()), "{:?} was quoted incorrectly", raw);
+    }
+    assert_eq!("/usr/bin/env-1.0_x".to_shell_escaped(), Cow::Borrowed("/usr/bin/env-1.0_x"));
+}
+
+#[test]
+fn test_json_escaped() {
+    static RAW: &str = "\"quoted\" \\ \x08\x0C\n\r\t \0\x1F\x7F caf\u{E9} \u{1F600}";
+    assert_eq!(
+        RAW.to_json_escaped(),
+        Cow::<'static, str>::Owned(String::from(r#"\"quoted\" \\ \b\f\n\r\t \u0000\u001f"#) + "\x7F caf\u{E9} \u{1F600}")
+    );
+    assert_eq!(
+        RAW.to_escaped_with(JsonEscaper::new().ascii_only(true)),
+        Cow::<'static, str>::Owned(String::from(r#"\"quoted\" \\ \b\f\n\r\t \u0000\u001f"#) + "\x7F " + r"caf\u00e9 \ud83d\ude00")
+    );
+    assert_eq!("no escapes here".to_json_escaped(), Cow::Borrowed("no escapes here"));
+    assert_eq!("caf\u{E9}".to_escaped_with(JsonEscaper::new().ascii_only(true)), "caf\\u00e9");
+
+    for escaper in [JsonEscaper::new(), JsonEscaper::new().ascii_only(true)].iter() {
+        assert_eq!(
+            RAW.to_escaped_with(*escaper).to_unescaped_with(JsonHandler).as_deref(),
+            Ok(RAW),
+            "{:?} did not round trip", escaper
+        );
+    }
+}
+
+#[test]
+fn test_escaped_with_limit() {
+    assert_eq!("a\nb".to_escaped_with_limit(DefaultEscaper, 4), Some(Cow::Owned(String::from(r"a\nb"))));
+    assert_eq!("a\nb".to_escaped_with_limit(DefaultEscaper, 3), None);
+    assert_eq!("\n\n\n".to_escaped_with_limit(DefaultEscaper, 1), None);
+    assert_eq!("plain".to_escaped_with_limit(DefaultEscaper, 5), Some(Cow::Borrowed("plain")));
+    assert_eq!("plain".to_escaped_with_limit(DefaultEscaper, 4), None);
+}
+
+#[test]
+fn test_escaped_ascii() {
+    // A combining acute accent, a flag made of two regional indicators, and a skin tone modifier
+    static RAW: &str = "e\u{301} \u{1F1FA}\u{1F1F8} \u{1F44B}\u{1F3FD} \"tab\t\" \x7F\u{85} caf\u{E9}";
+    assert_eq!(
+        RAW.to_escaped_ascii(),
+        Cow::<'static, str>::Owned(String::from(r#"e\u{301} \u{1F1FA}\u{1F1F8} \u{1F44B}\u{1F3FD} \"tab\t\" \x7F\u{85} caf\u{E9}"#))
+    );
+    assert_eq!(
+        RAW.to_escaped_with(AsciiEscaper::new().surrogate_pairs(true)),
+        Cow::<'static, str>::Owned(String::from(r#"e\u0301 \uD83C\uDDFA\uD83C\uDDF8 \uD83D\uDC4B\uD83C\uDFFD \"tab\t\" \x7F\u0085 caf\u00E9"#))
+    );
+    assert_eq!("printable ASCII ~".to_escaped_ascii(), Cow::Borrowed("printable ASCII ~"));
+
+    for escaper in [AsciiEscaper::new(), AsciiEscaper::new().surrogate_pairs(true)].iter() {
+        let escaped = RAW.to_escaped_with(*escaper);
+        assert!(escaped.bytes().all(|byte| (b' ' ..= b'~').contains(&byte)), "{:?} isn't printable ASCII", escaped);
+        assert_eq!(escaped.to_unescaped().as_deref(), Ok(RAW), "{:?} didn't round trip", escaper);
+    }
+}
@@ -0,0 +1,1223 @@
+#![cfg(feature = "alloc")]
+
+use std::borrow::Cow;
+use std::str::CharIndices;
+use descape::{UnescapeExt, EscapeHandler, EscapeWriter, BoxHandler, ContinuationHandler, ResilientHandler, MapHandler, IdentityHandler, DropHandler, MapOrDefault, NamedUnicodeHandler, DefaultHandler, JsonHandler, GraphQLHandler, GraphQLBlockHandler, JavaHandler, KotlinHandler, JsHandler, RustHandler, CHandler, CSharpHandler, GoHandler, LuaHandler, HaskellHandler, OCamlHandler, ElixirHandler, ShellHandler, PowerShellHandler, PythonStrHandler, RubyHandler, PhpHandler, SqlAnsiHandler, PostgresEStringHandler, CsvHandler, TomlHandler, YamlHandler, XmlHandler, EscapeErrorKind, InvalidEscape};
+
+#[test]
+fn test_continuation() {
+    assert_eq!(
+        "a\\\nb\\\r\nc\\n".to_unescaped_with(ContinuationHandler(DefaultHandler)).expect("line continuations should be valid"),
+        Cow::<'static, str>::Owned(String::from("abc\n")),
+        "line continuations gave incorrect result"
+    );
+    // Only the newline directly after the backslash is removed
+    assert_eq!(
+        "a\\\n\n  b\\\r\n\r\nc".to_unescaped_with(ContinuationHandler(DefaultHandler)).expect("line continuations should be valid"),
+        Cow::<'static, str>::Owned(String::from("a\n  b\r\nc")),
+        "line continuations removed too much"
+    );
+    // A lone carriage return isn't a line continuation
+    assert_eq!(
+        "a\\\rb".to_unescaped_with(ContinuationHandler(DefaultHandler)),
+        Err(InvalidEscape::with_kind(1, EscapeErrorKind::UnknownEscape('\r')).with_len(2))
+    );
+    // Byte indices after a continuation should still point into the source
+    assert_eq!(
+        "a\\\r\nb\\q".to_unescaped_with(ContinuationHandler(DefaultHandler)),
+        Err(InvalidEscape::with_kind(5, EscapeErrorKind::UnknownEscape('q')).with_len(2))
+    );
+    assert_eq!(
+        "a\\\r\nb\\".to_unescaped_with(ContinuationHandler(DefaultHandler)),
+        Err(InvalidEscape::with_kind(5, EscapeErrorKind::Truncated).with_len(1))
+    );
+
+    // Wrapping a custom handler
+    let only_tabs = |_: usize, chr: char, _: &mut CharIndices<'_>| if chr == 't' { Ok(Some('\t')) } else { Err(()) };
+    assert_eq!(
+        "\\t\\\n\\t".to_unescaped_with(ContinuationHandler(only_tabs)).expect("line continuations should be valid"),
+        Cow::<'static, str>::Owned(String::from("\t\t")),
+        "line continuations gave incorrect result with a custom handler"
+    );
+    "\\n".to_unescaped_with(ContinuationHandler(only_tabs))
+        .expect_err("the wrapped handler should still reject escapes");
+}
+
+#[test]
+fn test_resilient() {
+    for (source, unescaped) in [
+        (r"a\qb", r"a\qb"),
+        (r"a\x4zb\n", "a\\x4zb\n"),
+        (r"\u{110000}\t", "\\u{110000}\t"),
+        (r"\\\q\\", r"\\q\"),
+        (r"\u{D800\n", "\\u{D800\n"),
+    ].iter() {
+        assert_eq!(
+            source.to_unescaped_with(ResilientHandler(DefaultHandler)).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", source
+        );
+    }
+    assert_eq!(r"\e\f".to_unescaped_with(ResilientHandler(JsonHandler)).as_deref(), Ok("\\e\x0C"));
+
+    // Nothing the wrapped handler wrote before failing is kept
+    struct Partial;
+    impl EscapeWriter for Partial {
+        fn write_escape(&mut self, _: usize, _: char, _: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind> {
+            out.push_str("junk");
+            Err(EscapeErrorKind::Rejected)
+        }
+    }
+    assert_eq!(r"a\zb".to_unescaped_with(ResilientHandler(Partial)).as_deref(), Ok(r"a\zb"));
+    assert_eq!(
+        r"ab\".to_unescaped_with(ResilientHandler(DefaultHandler)),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::Truncated).with_len(1))
+    );
+    // Going over the wrapped handler's limit is still an error
+    let limited = ResilientHandler(descape::Unescaper::new().max_output_len(4));
+    assert_eq!(r"\qa\t".to_unescaped_with(limited).as_deref(), Ok("\\qa\t"));
+    assert_eq!(
+        r"\qa\tb".to_unescaped_with(limited),
+        Err(InvalidEscape::with_kind(5, EscapeErrorKind::OutputTooLong).with_len(1))
+    );
+}
+
+#[test]
+fn test_json() {
+    assert_eq!(
+        r#"\"\\\/\b\f\n\r\t\u0041\u00e9 \uD83D\uDE00"#.to_unescaped_with(JsonHandler).expect("JSON escapes should be valid"),
+        Cow::<'static, str>::Owned(String::from("\"\\/\x08\x0C\n\r\tA\u{E9} \u{1F600}")),
+        "JSON escapes gave incorrect result"
+    );
+    assert_eq!(r"a\/b".to_unescaped_with(JsonHandler).expect(r"\/ should be valid"), "a/b");
+
+    for (string, kind, len) in [
+        (r"ab\x41", EscapeErrorKind::UnknownEscape('x'), 2),
+        (r"ab\0", EscapeErrorKind::UnknownEscape('0'), 2),
+        (r"ab\101", EscapeErrorKind::UnknownEscape('1'), 2),
+        (r"ab\e", EscapeErrorKind::UnknownEscape('e'), 2),
+        (r"ab\a", EscapeErrorKind::UnknownEscape('a'), 2),
+        (r"ab\'", EscapeErrorKind::UnknownEscape('\''), 2),
+        (r"ab\u{41}", EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\U00000041", EscapeErrorKind::UnknownEscape('U'), 2),
+        (r"ab\u004", EscapeErrorKind::Truncated, 5),
+        (r"ab\uD83D", EscapeErrorKind::LoneSurrogate(0xD83D), 6),
+        (r"ab\uDE00", EscapeErrorKind::LoneSurrogate(0xDE00), 6),
+        (r"ab\", EscapeErrorKind::Truncated, 1),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(JsonHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_graphql() {
+    for (escaped, unescaped) in [
+        (r#"\"\\\/\b\f\n\r\t"#, "\"\\/\x08\x0C\n\r\t"),
+        (r"\u0041\u00e9", "A\u{E9}"),
+        (r"\uD83D\uDE00", "\u{1F600}"),
+        (r"\u{41}\u{1F600}", "A\u{1F600}"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(GraphQLHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\x41", EscapeErrorKind::UnknownEscape('x'), 2),
+        (r"ab\'", EscapeErrorKind::UnknownEscape('\''), 2),
+        (r"ab\u{110000}", EscapeErrorKind::InvalidCodepoint(0x0011_0000), 10),
+        (r"ab\u{41", EscapeErrorKind::Truncated, 5),
+        (r"ab\uD83D", EscapeErrorKind::LoneSurrogate(0xD83D), 6),
+        (r"ab\", EscapeErrorKind::Truncated, 1),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(GraphQLHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_graphql_block() {
+    for (escaped, unescaped) in [
+        (r#"\""""#, r#"""""#),
+        (r#"a\"b"#, r#"a\"b"#),
+        (r#"\"""#, r#"\"""#),
+        (r"\n\tA", r"\n\tA"),
+        (r#"\\""""#, r#"\""""#),
+        (r"a\\", r"a\\"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(GraphQLBlockHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+    assert_eq!(r"ab\".to_unescaped_with(GraphQLBlockHandler).as_deref(), Ok(r"ab\"));
+    assert_eq!(r"ab\".validate_escapes_with(GraphQLBlockHandler), Ok(()));
+}
+
+macro_rules! rust_parity {
+    ($($literal: literal),+) => {$(
+        let source = stringify!($literal);
+        assert_eq!(
+            source[1 .. source.len() - 1].to_unescaped_with(RustHandler).as_deref(),
+            Ok($literal),
+            "RustHandler didn't match rustc for {}", stringify!($literal)
+        );
+    )+};
+}
+
+#[test]
+fn test_js() {
+    for (escaped, unescaped) in [
+        (r#"\b\t\n\v\f\r\"\'\\"#, "\x08\t\n\x0B\x0C\r\"'\\"),
+        (r"\x41B\u{43}\u{1F600}", "ABC\u{1F600}"),
+        (r"😀", "\u{1F600}"),
+        (r"\q\-\😀", "q-😀"),
+        ("a\\\nb\\\r\nc\\\rd\\\u{2028}e", "abcde"),
+        (r"\0", "\0"),
+        (r"\08", "\08"),
+        (r"\101\0101\12a", "A\u{8}1\na"),
+        (r"\377\400\777", "\u{FF} 0?7"),
+        (r"\8\9", "89"),
+        (r"\1é\12", "\u{1}é\n"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(JsHandler::new()).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    let strict = JsHandler::new().strict(true);
+    assert_eq!(r"\0\x00".to_unescaped_with(strict).as_deref(), Ok("\0\0"));
+    assert_eq!(r"\q\u{41}".to_unescaped_with(strict).as_deref(), Ok("qA"));
+
+    for (string, handler, kind, len) in [
+        (r"ab\101", strict, EscapeErrorKind::UnknownEscape('1'), 2),
+        (r"ab\01", strict, EscapeErrorKind::UnknownEscape('0'), 2),
+        (r"ab\8", strict, EscapeErrorKind::UnknownEscape('8'), 2),
+        (r"ab\x4", JsHandler::new(), EscapeErrorKind::Truncated, 3),
+        (r"ab\uD83D", JsHandler::new(), EscapeErrorKind::LoneSurrogate(0xD83D), 6),
+        (r"ab\u{110000}", JsHandler::new(), EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(*handler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_rust() {
+    rust_parity!(
+        "plain",
+        "\n\r\t\\\0\'\"",
+        "\x00\x41\x7F",
+        "\u{0}\u{41}\u{1F600}\u{10FFFF}\u{1_F6_00}\u{00_0041}",
+        "caf\u{e9}",
+        "one \
+         two \
+            three"
+    );
+
+    for (string, kind, len) in [
+        (r"ab\a", EscapeErrorKind::UnknownEscape('a'), 2),
+        (r"ab\b", EscapeErrorKind::UnknownEscape('b'), 2),
+        (r"ab\v", EscapeErrorKind::UnknownEscape('v'), 2),
+        (r"ab\f", EscapeErrorKind::UnknownEscape('f'), 2),
+        (r"ab\e", EscapeErrorKind::UnknownEscape('e'), 2),
+        (r"ab\`", EscapeErrorKind::UnknownEscape('`'), 2),
+        (r"ab\1", EscapeErrorKind::UnknownEscape('1'), 2),
+        (r"ab\101", EscapeErrorKind::UnknownEscape('1'), 2),
+        (r"ab\x80", EscapeErrorKind::OutOfRange(0x80), 4),
+        (r"ab\xFF", EscapeErrorKind::OutOfRange(0xFF), 4),
+        (r"ab\x4", EscapeErrorKind::Truncated, 3),
+        (r"ab\uA", EscapeErrorKind::InvalidHexDigit, 2),
+        (r"ab\u", EscapeErrorKind::Truncated, 2),
+        (r"ab\u{41", EscapeErrorKind::Truncated, 5),
+        (r"ab\u{}", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"ab\u{_41}", EscapeErrorKind::InvalidHexDigit, 7),
+        (r"ab\u{0000041}", EscapeErrorKind::OutOfRange(0x41), 11),
+        (r"ab\u{110000}", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+        (r"ab\u{D800}", EscapeErrorKind::LoneSurrogate(0xD800), 8),
+        ("ab\\\r", EscapeErrorKind::UnknownEscape('\r'), 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(RustHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+    assert_eq!("a\\\r\n  b".to_unescaped_with(RustHandler).as_deref(), Ok("ab"));
+}
+
+#[test]
+fn test_c() {
+    assert_eq!(
+        r#"\a\b\f\n\r\t\v\\\'\"\?"#.to_unescaped_with(CHandler).expect("C escapes should be valid"),
+        Cow::<'static, str>::Owned(String::from("\x07\x08\x0C\n\r\t\x0B\\'\"?")),
+        "C escapes gave incorrect result"
+    );
+    for (string, unescaped) in [
+        // Octal escapes are at most three digits
+        (r"\0\7\101\1012", "\0\x07AA2"),
+        (r"\08\18", "\08\x018"),
+        // ...and wrap around
+        (r"\400\777", "\0\u{FF}"),
+        // Hex escapes are greedy
+        (r"\x41\x1g", "A\x01g"),
+        (r"\x00000041", "A"),
+        // ...and wrap around too
+        (r"\x141\xFFFF", "A\u{FF}"),
+        (r"\u00E9\U0001F600", "\u{E9}\u{1F600}"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(CHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\e", EscapeErrorKind::UnknownEscape('e'), 2),
+        (r"ab\8", EscapeErrorKind::UnknownEscape('8'), 2),
+        (r"ab\x", EscapeErrorKind::Truncated, 2),
+        (r"ab\xg", EscapeErrorKind::InvalidHexDigit, 2),
+        (r"ab\u{41}", EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\U0000D800", EscapeErrorKind::LoneSurrogate(0xD800), 10),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(CHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_java() {
+    for (escaped, unescaped) in [
+        (r#"\b\s\t\n\f\r\"\'\\"#, "\x08 \t\n\x0C\r\"'\\"),
+        (r"\u0041\uuuu0042\uD83D\uDE00", "AB\u{1F600}"),
+        (r"\0\7\77\377\400\08", "\0\x07?\u{FF} 0\08"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(JavaHandler::new()).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+    assert_eq!(r"\$x".to_unescaped_with(JavaHandler::new().dollar(true)).as_deref(), Ok("$x"));
+
+    for (string, kind, len) in [
+        (r"ab\u{41}", EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\x41", EscapeErrorKind::UnknownEscape('x'), 2),
+        (r"ab\U0001F600", EscapeErrorKind::UnknownEscape('U'), 2),
+        (r"ab\$", EscapeErrorKind::UnknownEscape('$'), 2),
+        (r"ab\8", EscapeErrorKind::UnknownEscape('8'), 2),
+        (r"ab\uD800", EscapeErrorKind::LoneSurrogate(0xD800), 6),
+        (r"ab\uuu41", EscapeErrorKind::Truncated, 6),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(JavaHandler::new()),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_kotlin() {
+    assert_eq!(
+        r#"\b\t\n\r\"\'\\\$\u0041\uD83D\uDE00"#.to_unescaped_with(KotlinHandler).as_deref(),
+        Ok("\x08\t\n\r\"'\\$A\u{1F600}")
+    );
+
+    for (string, kind, len) in [
+        (r"ab\u{41}", EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\f", EscapeErrorKind::UnknownEscape('f'), 2),
+        (r"ab\0", EscapeErrorKind::UnknownEscape('0'), 2),
+        (r"ab\uu0041", EscapeErrorKind::InvalidHexDigit, 6),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(KotlinHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_csharp() {
+    for (escaped, unescaped) in [
+        (r#"\0\a\b\e\f\n\r\t\v\\\'\""#, "\0\x07\x08\x1B\x0C\n\r\t\x0B\\'\""),
+        (r"\x41\x041g\x0041\x00041\x4", "AAgA\x041\x04"),
+        (r"\xe9\x20AC\x1F600", "\u{E9}\u{20AC}\u{1F60}0"),
+        (r"A😀\U0001F600", "A\u{1F600}\u{1F600}"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(CSharpHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\x", EscapeErrorKind::Truncated, 2),
+        (r"ab\xg", EscapeErrorKind::InvalidHexDigit, 2),
+        (r"ab\xD800", EscapeErrorKind::LoneSurrogate(0xD800), 6),
+        (r"ab\u{41}", EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\u004", EscapeErrorKind::Truncated, 5),
+        (r"ab\U00110000", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+        (r"ab\q", EscapeErrorKind::UnknownEscape('q'), 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(CSharpHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_go() {
+    for (escaped, unescaped) in [
+        (r#"\a\b\f\n\r\t\v\\\'\""#, "\x07\x08\x0C\n\r\t\x0B\\'\""),
+        (r"\000\101\377", "\0A\u{FF}"),
+        (r"\x41\xffé\U0001F600", "A\u{FF}\u{E9}\u{1F600}"),
+        (r"\1010", "A0"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(GoHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\7", EscapeErrorKind::Truncated, 2),
+        (r"ab\77", EscapeErrorKind::Truncated, 3),
+        (r"ab\7x", EscapeErrorKind::InvalidOctalDigit, 3),
+        (r"ab\18", EscapeErrorKind::InvalidOctalDigit, 3),
+        (r"ab\400", EscapeErrorKind::OutOfRange(0o400), 4),
+        (r"ab\777", EscapeErrorKind::OutOfRange(0o777), 4),
+        (r"ab\8", EscapeErrorKind::UnknownEscape('8'), 2),
+        (r"ab\e", EscapeErrorKind::UnknownEscape('e'), 2),
+        (r"ab\u{41}", EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\uD800", EscapeErrorKind::LoneSurrogate(0xD800), 6),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(GoHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_haskell() {
+    for (escaped, unescaped) in [
+        (r#"\a\b\f\n\r\t\v\\\"\'"#, "\x07\x08\x0C\n\r\t\x0B\\\"'"),
+        (r"\ESC[1m\NUL\DEL\SP\DC4\SOH\SO\&H\SOx", "\x1B[1m\0\x7F \x14\x01\x0EH\x0Ex"),
+        (r"\^@\^A\^Z\^[\^\\^_", "\0\x01\x1A\x1B\x1C\x1F"),
+        (r"\65\1234\&5\137\&9\o101\o7\x41\x1F600\xaBc", "A\u{4D2}5\u{89}9A\x07A\u{1F600}\u{ABC}"),
+        (r"\1114111\&\&", "\u{10FFFF}"),
+        ("one \\\n   \\two\\ \\three\\\t\r\n\\", "one twothree"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(HaskellHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\1114112", EscapeErrorKind::InvalidCodepoint(0x11_0000), 8),
+        (r"ab\99999999999999", EscapeErrorKind::InvalidCodepoint(u32::MAX), 15),
+        (r"ab\xD800", EscapeErrorKind::LoneSurrogate(0xD800), 6),
+        (r"ab\x", EscapeErrorKind::Truncated, 2),
+        (r"ab\xg", EscapeErrorKind::InvalidHexDigit, 2),
+        (r"ab\o8", EscapeErrorKind::InvalidOctalDigit, 2),
+        (r"ab\^a", EscapeErrorKind::UnknownEscape('a'), 3),
+        (r"ab\^", EscapeErrorKind::Truncated, 2),
+        (r"ab\ES", EscapeErrorKind::UnknownEscape('E'), 2),
+        (r"ab\z", EscapeErrorKind::UnknownEscape('z'), 2),
+        ("ab\\  x", EscapeErrorKind::UnknownEscape('x'), 4),
+        ("ab\\  ", EscapeErrorKind::Truncated, 3),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(HaskellHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_ocaml() {
+    for (escaped, unescaped) in [
+        (r#"\b\n\r\t\ \\\"\'"#, "\x08\n\r\t \\\"'"),
+        (r"\065\0651\255\000\o101\o377\o0000\x41\xe9", "AA1\u{FF}\0A\u{FF}\x000A\u{E9}"),
+        (r"\u{41}\u{1F600}", "A\u{1F600}"),
+        ("one \\\n \t two\\\r\n\\\nthree", "one twothree"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(OCamlHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\12", EscapeErrorKind::Truncated, 3),
+        (r"ab\12x", EscapeErrorKind::InvalidDecimalDigit, 3),
+        (r"ab\1", EscapeErrorKind::Truncated, 2),
+        (r"ab\256", EscapeErrorKind::OutOfRange(256), 4),
+        (r"ab\o400", EscapeErrorKind::OutOfRange(0o400), 5),
+        (r"ab\o8", EscapeErrorKind::InvalidOctalDigit, 3),
+        (r"ab\o17", EscapeErrorKind::Truncated, 4),
+        (r"ab\o", EscapeErrorKind::Truncated, 2),
+        (r"ab\x4", EscapeErrorKind::Truncated, 3),
+        (r"ab\u41", EscapeErrorKind::InvalidHexDigit, 2),
+        (r"ab\u{D800}", EscapeErrorKind::LoneSurrogate(0xD800), 8),
+        ("ab\\\rx", EscapeErrorKind::UnknownEscape('\r'), 3),
+        (r"ab\a", EscapeErrorKind::UnknownEscape('a'), 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(OCamlHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_elixir() {
+    for (escaped, unescaped) in [
+        (r#"\a\b\d\e\f\n\r\s\t\v\0\\\"\'"#, "\x07\x08\x7F\x1B\x0C\n\r \t\x0B\0\\\"'"),
+        (r"\x41\x7F\x{41}\x{1F600}\x{0000041}", "A\x7FA\u{1F600}A"),
+        (r"\u0041\u20AC\u{1F600}", "A\u{20AC}\u{1F600}"),
+        (r"\01\q\#{name}", "\x001q#{name}"),
+        ("one\\\ntwo\\\r\nthree", "onetwothree"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(ElixirHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\x4", EscapeErrorKind::Truncated, 3),
+        (r"ab\xG1", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"ab\x{}", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"ab\x{110000}", EscapeErrorKind::InvalidCodepoint(0x0011_0000), 10),
+        (r"ab\x{41", EscapeErrorKind::Truncated, 5),
+        (r"ab\u004", EscapeErrorKind::Truncated, 5),
+        (r"ab\uD83D\uDE00", EscapeErrorKind::LoneSurrogate(0xD83D), 6),
+        (r"ab\", EscapeErrorKind::Truncated, 1),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(ElixirHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_lua() {
+    for (escaped, unescaped) in [
+        (r#"\a\b\f\n\r\t\v\\\"\'"#, "\x07\x08\x0C\n\r\t\x0B\\\"'"),
+        (r"\122\0\65\0655\255", "z\0AA5\u{FF}"),
+        (r"\x41\u{1F600}", "A\u{1F600}"),
+        ("a\\z  \\n b", "a\n b"),
+        ("a\\z \n\t\x0B\x0C\r b", "ab"),
+        ("a\\z", "a"),
+        ("a\\\nb\\\r\nc\\\n\rd", "a\nb\nc\nd"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(LuaHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\256", EscapeErrorKind::OutOfRange(256), 4),
+        (r"ab\999", EscapeErrorKind::OutOfRange(999), 4),
+        (r"ab\x4", EscapeErrorKind::Truncated, 3),
+        (r"ab\u0041", EscapeErrorKind::InvalidHexDigit, 2),
+        (r"ab\u", EscapeErrorKind::Truncated, 2),
+        (r"ab\u{110000}", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+        (r"ab\e", EscapeErrorKind::UnknownEscape('e'), 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(LuaHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_shell() {
+    assert_eq!(
+        r#"\a\b\e\E\f\n\r\t\v\\\'\"\?"#.to_unescaped_with(ShellHandler).expect("shell escapes should be valid"),
+        Cow::<'static, str>::Owned(String::from("\x07\x08\x1B\x1B\x0C\n\r\t\x0B\\'\"?")),
+        "shell escapes gave incorrect result"
+    );
+    for (string, unescaped) in [
+        (r"\cA\ca\cZ\c@\c[\c?", "\x01\x01\x1A\0\x1B\x7F"),
+        (r"\c1", "\x11"),
+        (r"\101\0\18\400", "A\0\x018\0"),
+        (r"\x41\x7\x414", "A\x07A4"),
+        (r"\u41☺A1", "A\u{263A}A1"),
+        (r"\U1F600\U0001F6000", "\u{1F600}\u{1F600}0"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(ShellHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\c", EscapeErrorKind::Truncated, 2),
+        (r"ab\c☺", EscapeErrorKind::UnknownEscape('☺'), 5),
+        (r"ab\x", EscapeErrorKind::Truncated, 2),
+        (r"ab\xg", EscapeErrorKind::InvalidHexDigit, 2),
+        (r"ab\U110000", EscapeErrorKind::InvalidCodepoint(0x11_0000), 8),
+        (r"ab\uD800", EscapeErrorKind::LoneSurrogate(0xD800), 6),
+        (r"ab\8", EscapeErrorKind::UnknownEscape('8'), 2),
+        (r"ab\z", EscapeErrorKind::UnknownEscape('z'), 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(ShellHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_python_str() {
+    assert_eq!(
+        r#"\a\b\f\n\r\t\v\\\'\""#.to_unescaped_with(PythonStrHandler).expect("Python escapes should be valid"),
+        Cow::<'static, str>::Owned(String::from("\x07\x08\x0C\n\r\t\x0B\\'\"")),
+        "Python escapes gave incorrect result"
+    );
+    for (string, unescaped) in [
+        (r"\101\0\18\777", "A\0\x018\u{1FF}"),
+        (r"\x41\xe9", "A\u{E9}"),
+        (r"é\U0001F600", "\u{E9}\u{1F600}"),
+        // Unknown escapes are kept as they were written
+        (r"\d+\w\e\?", "\\d+\\w\\e\\?"),
+        (r"\8\☺", "\\8\\☺"),
+        ("a\\\nb\\\r\nc", "abc"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(PythonStrHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\x4", EscapeErrorKind::Truncated, 3),
+        (r"ab\xg1", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"ab\u{41}", EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\uD800", EscapeErrorKind::LoneSurrogate(0xD800), 6),
+        (r"ab\U00110000", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+        (r"ab\", EscapeErrorKind::Truncated, 1),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(PythonStrHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_powershell() {
+    for (escaped, unescaped) in [
+        (r"`0`a`b`e`f`n`r`t`v", "\0\x07\x08\x1B\x0C\n\r\t\x0B"),
+        ("`\"`$`'``", "\"$'`"),
+        (r"C:\Users\n`n", "C:\\Users\\n\n"),
+        (r"`u{41}`u{1F600}`u", "A\u{1F600}u"),
+        (r"\`\", "\\\\"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with_escape_char('`', PowerShellHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+    assert_eq!(
+        r"no escapes\n".to_unescaped_with_escape_char('`', PowerShellHandler),
+        Ok(Cow::Borrowed(r"no escapes\n"))
+    );
+
+    for (string, kind, len) in [
+        (r"ab`u{}", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"ab`u{41", EscapeErrorKind::Truncated, 5),
+        (r"ab`u{D800}", EscapeErrorKind::LoneSurrogate(0xD800), 8),
+        ("ab`", EscapeErrorKind::Truncated, 1),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with_escape_char('`', PowerShellHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[cfg(feature = "unicode-names")]
+#[test]
+fn test_python_names() {
+    for (string, unescaped) in [
+        (r"\N{LATIN SMALL LETTER A}", "a"),
+        (r"\N{greek small letter alpha}\N{GRINNING FACE}", "\u{3B1}\u{1F600}"),
+        (r"\N{CJK UNIFIED IDEOGRAPH-4E00}\N{HANGUL SYLLABLE GA}", "\u{4E00}\u{AC00}"),
+        (r"\N{TANGUT IDEOGRAPH-17000}\N{TANGUT IDEOGRAPH-18D08}", "\u{17000}\u{18D08}"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(PythonStrHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+
+    for (string, len) in [
+        (r"ab\N{NOT A REAL NAME}", 19),
+        (r"ab\N{LATIN SMALL LETTER A", 23),
+        (r"ab\NA", 2),
+        (r"ab\N{CJK UNIFIED IDEOGRAPH-0041}", 30),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(PythonStrHandler),
+            Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownName).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[cfg(not(feature = "unicode-names"))]
+#[test]
+fn test_python_no_names() {
+    assert_eq!(
+        r"ab\N{LATIN SMALL LETTER A}".to_unescaped_with(PythonStrHandler),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('N')).with_len(2))
+    );
+}
+
+#[cfg(feature = "unicode-names")]
+#[test]
+fn test_named_unicode() {
+    for (string, unescaped) in [
+        (r"\N{LATIN SMALL LETTER A}", "a"),
+        (r"\N{GREEK SMALL LETTER ALPHA}\t\x41", "\u{3B1}\tA"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(NamedUnicodeHandler(DefaultHandler)).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+
+    for (string, len) in [
+        (r"ab\N{NOT A REAL NAME}", 19),
+        (r"ab\N{LATIN SMALL LETTER A", 23),
+        (r"ab\NA", 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(NamedUnicodeHandler(DefaultHandler)),
+            Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownName).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[cfg(not(feature = "unicode-names"))]
+#[test]
+fn test_named_unicode_no_names() {
+    assert_eq!(
+        r"ab\N{LATIN SMALL LETTER A}".to_unescaped_with(NamedUnicodeHandler(DefaultHandler)),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('N')).with_len(2))
+    );
+}
+
+#[test]
+fn test_ruby() {
+    for (escaped, unescaped) in [
+        (r#"\a\b\e\f\n\r\s\t\v\\\'\""#, "\x07\x08\x1B\x0C\n\r \t\x0B\\'\""),
+        (r"\101\0\777\1018", "A\0\u{FF}A8"),
+        (r"\x4\x41\x411", "\x04AA1"),
+        ("é\\u{61 62}\\u{ 1F600\t21 }", "\u{E9}ab\u{1F600}!"),
+        (r"\cG\q\#{x}", "\x07q#{x}"),
+        ("a\\\nb\\\r\nc", "abc"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(RubyHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\x", EscapeErrorKind::Truncated, 2),
+        (r"ab\xg", EscapeErrorKind::InvalidHexDigit, 2),
+        (r"ab\u41", EscapeErrorKind::Truncated, 4),
+        (r"ab\u{}", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"ab\u{ }", EscapeErrorKind::InvalidHexDigit, 5),
+        (r"ab\u{61 zz}", EscapeErrorKind::InvalidHexDigit, 9),
+        (r"ab\u{61 D800}", EscapeErrorKind::LoneSurrogate(0xD800), 11),
+        (r"ab\u{61", EscapeErrorKind::Truncated, 5),
+        (r"ab\c", EscapeErrorKind::Truncated, 2),
+        (r"ab\c1", EscapeErrorKind::UnknownEscape('1'), 3),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(RubyHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_php() {
+    for (escaped, unescaped) in [
+        (r#"\n\r\t\v\e\f\\\$\""#, "\n\r\t\x0B\x1B\x0C\\$\""),
+        (r"$x \$x {\$x}", "$x $x {$x}"),
+        (r"\101\0\400\1018\xA\x41\x414", "A\0\0A8\nAA4"),
+        (r"\xC3\xA9\303\251\u{E9}\u{1F600}", "\u{E9}\u{E9}\u{E9}\u{1F600}"),
+        (r"\q\'\x\xg\u\u1F600\U0001F600", r"\q\'\x\xg\u\u1F600\U0001F600"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(PhpHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+    // Unknown escapes are kept exactly, so the string is still borrowed
+    assert!(matches!(r"C:\Windows\q".to_unescaped_with(PhpHandler), Ok(Cow::Borrowed(_))));
+
+    for (string, kind, len) in [
+        (r"ab\u{}", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"ab\u{zz}", EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\u{110000}", EscapeErrorKind::InvalidCodepoint(0x0011_0000), 10),
+        (r"ab\u{D800}", EscapeErrorKind::LoneSurrogate(0xD800), 8),
+        (r"ab\u{41", EscapeErrorKind::Truncated, 5),
+        (r"ab\xC3", EscapeErrorKind::OutOfRange(0xC3), 4),
+        (r"ab\xC3\x41", EscapeErrorKind::OutOfRange(0xC3), 8),
+        (r"ab\xFF", EscapeErrorKind::OutOfRange(0xFF), 4),
+        (r"ab\", EscapeErrorKind::Truncated, 1),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(PhpHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_postgres_e_string() {
+    for (escaped, unescaped) in [
+        (r"\b\f\n\r\t\\\'\q\x", "\x08\x0C\n\r\t\\'qx"),
+        (r"\1\12\101\1011\501\xA\x41\x414\xg", "\x01\nAA1A\nAA4xg"),
+        (r"\xC3\xA9\303\251\xc3\251\xE2\x82\xAC\xF0\x9F\x98\x80", "ééé€😀"),
+        (r"é😀\U0001F600", "é😀😀"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(PostgresEStringHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\0", EscapeErrorKind::OutOfRange(0), 2),
+        (r"ab\x00", EscapeErrorKind::OutOfRange(0), 4),
+        (r"ab\u0000", EscapeErrorKind::OutOfRange(0), 6),
+        (r"ab\xC3", EscapeErrorKind::OutOfRange(0xC3), 4),
+        (r"ab\xC3x", EscapeErrorKind::OutOfRange(0xC3), 4),
+        (r"ab\xC3\n", EscapeErrorKind::OutOfRange(0xC3), 4),
+        (r"ab\xC3\x41", EscapeErrorKind::OutOfRange(0xC3), 8),
+        (r"ab\xE2\x82", EscapeErrorKind::OutOfRange(0xE2), 8),
+        (r"ab\x80", EscapeErrorKind::OutOfRange(0x80), 4),
+        (r"ab\uD83D", EscapeErrorKind::LoneSurrogate(0xD83D), 6),
+        (r"ab\U00110000", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(PostgresEStringHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+
+    let source = r"E'a\nb''c'";
+    let (literal, rest) = PostgresEStringHandler::unescape_literal(&source[2 ..]).expect("this is a valid literal");
+    assert_eq!(literal, "a\nb'c");
+    assert_eq!(rest, "");
+
+    assert!(matches!(PostgresEStringHandler::unescape_literal("plain', 1"), Ok((Cow::Borrowed("plain"), ", 1"))));
+    assert_eq!(PostgresEStringHandler::unescape_literal(r"''\''''"), Ok((Cow::Owned("'''".into()), "")));
+    assert_eq!(
+        PostgresEStringHandler::unescape_literal(r"a''b\x00'"),
+        Err(InvalidEscape::with_kind(4, EscapeErrorKind::OutOfRange(0)).with_len(4))
+    );
+    assert_eq!(
+        PostgresEStringHandler::unescape_literal("a''b"),
+        Err(InvalidEscape::with_kind(4, EscapeErrorKind::Unterminated))
+    );
+}
+
+#[test]
+fn test_sql_ansi() {
+    let literal = "'it''s'";
+    assert_eq!(
+        literal[1 .. literal.len() - 1].to_unescaped_with_escape_char('\'', SqlAnsiHandler).expect("doubled quotes should be valid"),
+        Cow::<'static, str>::Owned(String::from("it's")),
+        "doubled quotes gave incorrect result"
+    );
+    assert_eq!(
+        r"C:\temp\n".to_unescaped_with_escape_char('\'', SqlAnsiHandler),
+        Ok(Cow::Borrowed(r"C:\temp\n")),
+        "backslashes aren't escapes in SQL"
+    );
+    assert_eq!("''''''".to_unescaped_with_escape_char('\'', SqlAnsiHandler).as_deref(), Ok("'''"));
+
+    for (string, kind, len) in [
+        ("ab'", EscapeErrorKind::Truncated, 1),
+        ("ab'c", EscapeErrorKind::UnknownEscape('c'), 2),
+        ("ab' '", EscapeErrorKind::UnknownEscape(' '), 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with_escape_char('\'', SqlAnsiHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_csv() {
+    assert_eq!(
+        r#"she said ""hi"""#.to_unescaped_with_escape_char('"', CsvHandler).as_deref(),
+        Ok(r#"she said "hi""#)
+    );
+    assert_eq!(
+        r#"a"b"#.to_unescaped_with_escape_char('"', CsvHandler),
+        Err(InvalidEscape::with_kind(1, EscapeErrorKind::UnknownEscape('b')).with_len(2))
+    );
+
+    for (line, field, rest) in [
+        (r#""she said ""hi""""#, r#"she said "hi""#, ""),
+        (r#""plain",next"#, "plain", ",next"),
+        (r#""""""#, "\"", ""),
+        (r#""""#, "", ""),
+        (r#""a,b"",""c",d"#, r#"a,b","c"#, ",d"),
+        (r#""no \n escapes""#, r"no \n escapes", ""),
+    ].iter() {
+        let (unescaped, remainder) = CsvHandler::unescape_field(&line[1 ..])
+            .unwrap_or_else(|err| panic!("{:?} failed with {}", line, err));
+        assert_eq!((&*unescaped, remainder), (*field, *rest), "{:?} gave incorrect result", line);
+    }
+    assert!(matches!(CsvHandler::unescape_field(r#"plain",next"#), Ok((Cow::Borrowed("plain"), ",next"))));
+    assert_eq!(
+        CsvHandler::unescape_field(r#"never ""closed"#),
+        Err(InvalidEscape::with_kind(14, EscapeErrorKind::Unterminated))
+    );
+}
+
+#[test]
+fn test_toml() {
+    assert_eq!(
+        r#"\b\t\n\f\r\"\\é\U0001F600"#.to_unescaped_with(TomlHandler::new()).expect("TOML escapes should be valid"),
+        Cow::<'static, str>::Owned(String::from("\x08\t\n\x0C\r\"\\\u{E9}\u{1F600}")),
+        "TOML escapes gave incorrect result"
+    );
+    for (string, unescaped) in [
+        ("a\\\n  \n\tb", "ab"),
+        ("a\\ \t\r\n  b", "ab"),
+        ("a\\\r\n", "a"),
+        // Only the whitespace right after the backslash is trimmed
+        ("a\\\nb \n c", "ab \n c"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(TomlHandler::new().multiline(true)).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+
+    for (string, multiline, kind, len) in [
+        (r"ab\a", false, EscapeErrorKind::UnknownEscape('a'), 2),
+        (r"ab\v", false, EscapeErrorKind::UnknownEscape('v'), 2),
+        (r"ab\'", false, EscapeErrorKind::UnknownEscape('\''), 2),
+        (r"ab\0", false, EscapeErrorKind::UnknownEscape('0'), 2),
+        (r"ab\x41", false, EscapeErrorKind::UnknownEscape('x'), 2),
+        (r"ab\u{41}", false, EscapeErrorKind::InvalidHexDigit, 6),
+        (r"ab\uD800", false, EscapeErrorKind::LoneSurrogate(0xD800), 6),
+        (r"ab\U0000004", false, EscapeErrorKind::Truncated, 9),
+        ("ab\\\nc", false, EscapeErrorKind::UnknownEscape('\n'), 2),
+        ("ab\\ c\n", true, EscapeErrorKind::UnknownEscape(' '), 2),
+        ("ab\\\rc", true, EscapeErrorKind::UnknownEscape('\r'), 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(TomlHandler::new().multiline(*multiline)),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_yaml() {
+    assert_eq!(
+        "\\0\\a\\b\\t\\\t\\n\\v\\f\\r\\e\\ \\\"\\/\\\\".to_unescaped_with(YamlHandler).expect("YAML escapes should be valid"),
+        Cow::<'static, str>::Owned(String::from("\0\x07\x08\t\t\n\x0B\x0C\r\x1B \"/\\")),
+        "YAML escapes gave incorrect result"
+    );
+    for (string, unescaped) in [
+        (r"\N\_\L\P", "\u{85}\u{A0}\u{2028}\u{2029}"),
+        (r"a\ \ b", "a  b"),
+        (r"\x41\xe9☺\U0001F600", "A\u{E9}\u{263A}\u{1F600}"),
+        ("a\\\n   b", "ab"),
+        ("a\\\r\n\tb", "ab"),
+        // Only the indentation of the next line is removed
+        ("a\\\n\nb", "a\nb"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(YamlHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+
+    for (string, kind, len) in [
+        (r"ab\q", EscapeErrorKind::UnknownEscape('q'), 2),
+        (r"ab\'", EscapeErrorKind::UnknownEscape('\''), 2),
+        (r"ab\101", EscapeErrorKind::UnknownEscape('1'), 2),
+        (r"ab\x4", EscapeErrorKind::Truncated, 3),
+        (r"ab\uDC00", EscapeErrorKind::LoneSurrogate(0xDC00), 6),
+        ("ab\\\rc", EscapeErrorKind::UnknownEscape('\r'), 2),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with(YamlHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[test]
+fn test_xml() {
+    for (string, unescaped) in [
+        ("&amp;&lt;&gt;&quot;&apos;", "&<>\"'"),
+        ("&#65;&#x41;&#X41;&#0065;&#x1F600;", "AAAA\u{1F600}"),
+        ("no references", "no references"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with_escape_char('&', XmlHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+
+    for (string, kind, len) in [
+        ("ab&", EscapeErrorKind::Truncated, 1),
+        ("ab&amp", EscapeErrorKind::Truncated, 4),
+        ("ab&#65", EscapeErrorKind::Truncated, 4),
+        ("ab&amp b;", EscapeErrorKind::UnknownName, 4),
+        ("ab&nope;", EscapeErrorKind::UnknownName, 6),
+        ("ab&Amp;", EscapeErrorKind::UnknownName, 5),
+        ("ab& ;", EscapeErrorKind::UnknownEscape(' '), 2),
+        ("ab&#;", EscapeErrorKind::InvalidDecimalDigit, 2),
+        ("ab&#6A;", EscapeErrorKind::InvalidDecimalDigit, 3),
+        ("ab&#x;", EscapeErrorKind::InvalidHexDigit, 3),
+        ("ab&#x4G;", EscapeErrorKind::InvalidHexDigit, 4),
+        ("ab&#xD800;", EscapeErrorKind::LoneSurrogate(0xD800), 8),
+        ("ab&#1114112;", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+        ("ab&#99999999999;", EscapeErrorKind::InvalidCodepoint(u32::MAX), 14),
+        ("ab&#x10000000000;", EscapeErrorKind::InvalidCodepoint(u32::MAX), 15),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with_escape_char('&', XmlHandler),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+}
+
+#[cfg(feature = "html-entities")]
+#[test]
+fn test_html_entities() {
+    for (string, unescaped) in [
+        ("&eacute;&Eacute;&AMP;&nbsp;", "\u{E9}\u{C9}&\u{A0}"),
+        ("&NotEqualTilde;&CounterClockwiseContourIntegral;", "\u{2242}\u{338}\u{2233}"),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped_with_escape_char('&', XmlHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", string
+        );
+    }
+    assert_eq!(
+        "ab&eacut;".to_unescaped_with_escape_char('&', XmlHandler),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownName).with_len(7))
+    );
+}
+
+#[test]
+fn test_or_else() {
+    // Consumes as much as it can before giving up
+    let greedy = |_: usize, chr: char, iter: &mut CharIndices<'_>| {
+        if chr != 'w' {
+            return Err(());
+        }
+        let rest = iter.as_str();
+        while iter.next().is_some() {}
+        if rest == "ow" { Ok(Some('!')) } else { Err(()) }
+    };
+    assert_eq!(
+        r"\wow".to_unescaped_with(greedy.or_else(DefaultHandler)).as_deref(),
+        Ok("!")
+    );
+    // The fallback sees the sequence as if the first handler was never called
+    assert_eq!(
+        r"\w\x41".to_unescaped_with(greedy.or_else(|_: usize, chr: char, _: &mut CharIndices<'_>| Ok(Some(chr.to_ascii_uppercase())))).as_deref(),
+        Ok("WX41")
+    );
+    // Only the fallback's error is reported
+    assert_eq!(
+        r"ab\wonder".to_unescaped_with(greedy.or_else(JsonHandler)),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('w')).with_len(2))
+    );
+    assert_eq!(
+        r"ab\u12".to_unescaped_with(greedy.or_else(JsonHandler).or_else(DefaultHandler)),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::Truncated).with_len(4))
+    );
+}
+
+#[test]
+fn test_map() {
+    static TABLE: MapHandler<'static> = MapHandler::new(&[('l', '<'), ('g', '>'), ('☺', '☹'), ('l', '!')]);
+
+    assert_eq!(r"\l\g\☺\l".to_unescaped_with(TABLE).as_deref(), Ok("<>☹<"));
+    assert_eq!(
+        r"ab\n".to_unescaped_with(TABLE),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('n')).with_len(2))
+    );
+    assert_eq!(r"\l\n".to_unescaped_with(TABLE.or_else(DefaultHandler)).as_deref(), Ok("<\n"));
+
+    let empty = MapHandler::new(&[]);
+    assert!(empty.map().is_empty());
+    r"\l".to_unescaped_with(empty).expect_err("nothing is in the table");
+}
+
+#[test]
+fn test_identity_drop() {
+    assert_eq!(r"\H\i \n\\ \❤".to_unescaped_with(IdentityHandler).as_deref(), Ok("Hi n\\ ❤"));
+    assert_eq!(r"\H\i \n\\ \❤".to_unescaped_with(DropHandler).as_deref(), Ok("  "));
+    assert_eq!(r"ab".to_unescaped_with(DropHandler), Ok(Cow::Borrowed("ab")));
+    assert_eq!(
+        r"ab\".to_unescaped_with(IdentityHandler),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::Truncated).with_len(1))
+    );
+}
+
+#[test]
+fn test_map_or_default() {
+    // Consumes a character before deciding whether this is a sequence it knows
+    let brackets = |_: usize, chr: char, iter: &mut CharIndices<'_>| match (chr, iter.next()) {
+        ('l', Some((_, 't'))) => Ok(Some('<')),
+        ('g', Some((_, 't'))) => Ok(Some('>')),
+        _ => Err(())
+    };
+    assert_eq!(r"\lt\tag\gt\x41".to_unescaped_with(MapOrDefault(brackets)).as_deref(), Ok("<\tag>A"));
+    assert_eq!(
+        r"ab\q".to_unescaped_with(MapOrDefault(brackets)),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('q')).with_len(2))
+    );
+}
+
+#[test]
+fn test_box_handler() {
+    enum Format { Json, Default }
+
+    fn handler(format: &Format) -> BoxHandler<'static> {
+        match format {
+            Format::Json => Box::new(JsonHandler),
+            Format::Default => Box::new(DefaultHandler),
+        }
+    }
+
+    assert_eq!(r"\/é".to_unescaped_with(handler(&Format::Json)).as_deref(), Ok("/\u{E9}"));
+    assert_eq!(r"\x41\u{E9}".to_unescaped_with(handler(&Format::Default)).as_deref(), Ok("A\u{E9}"));
+    // The detailed error makes it through the box
+    assert_eq!(
+        r"ab\x41".to_unescaped_with(handler(&Format::Json)),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('x')).with_len(2))
+    );
+
+    // Boxed handlers can borrow too
+    let mut count = 0;
+    let counting: BoxHandler<'_> = Box::new(|_: usize, chr: char, _: &mut CharIndices<'_>| {
+        count += 1;
+        Ok(Some(chr))
+    });
+    assert_eq!(r"\a\b".to_unescaped_with(counting).as_deref(), Ok("ab"));
+    assert_eq!(count, 2);
+}
+
+#[test]
+fn test_by_ref() {
+    struct Counter(usize);
+
+    impl EscapeHandler for Counter {
+        fn escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, ()> {
+            self.escape_detailed(idx, chr, iter).map_err(|_| ())
+        }
+
+        fn escape_detailed(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, EscapeErrorKind> {
+            self.0 += 1;
+            DefaultHandler.escape_detailed(idx, chr, iter)
+        }
+    }
+
+    let mut counter = Counter(0);
+    for string in [r"\n\t", r"\x41", "none"].iter() {
+        string.to_unescaped_with(counter.by_ref()).expect("escapes should be valid");
+    }
+    assert_eq!(counter.0, 3);
+    // The detailed error makes it through the reference
+    assert_eq!(
+        r"ab\q".to_unescaped_with(counter.by_ref()),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('q')).with_len(2))
+    );
+    assert_eq!(counter.0, 4);
+
+    // Closures can be borrowed without it
+    let mut count = 0;
+    let mut closure = |_: usize, chr: char, _: &mut CharIndices<'_>| {
+        count += 1;
+        Ok(Some(chr))
+    };
+    r"\a\b".to_unescaped_with(&mut closure).expect("escapes should be valid");
+    r"\c".to_unescaped_with(&mut closure).expect("escapes should be valid");
+    r"\d".to_unescaped_with(closure.by_ref()).expect("escapes should be valid");
+    assert_eq!(count, 4);
+}
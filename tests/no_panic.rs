@@ -0,0 +1,122 @@
+//! Throws pseudo-random, mostly malformed input at every entry point and handler,
+//! checking that nothing panics and that errors always point inside of the input.
+//!
+//! This is a cheap stand-in for fuzzing, so it uses a fixed seed to stay reproducible.
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use std::borrow::Cow;
+use common::Rng;
+use descape::{
+    UnescapeExt, UnescapeBytesExt, EscapeExt, InvalidEscape, Unescaper, OctalMode, UnknownEscapePolicy,
+    DefaultHandler, ContinuationHandler, ResilientHandler, NamedUnicodeHandler, JsonHandler, JavaHandler, KotlinHandler, JsHandler,
+    RustHandler, CHandler, CSharpHandler, GoHandler, LuaHandler, HaskellHandler, OCamlHandler, ElixirHandler, ShellHandler, PowerShellHandler, PythonStrHandler, PythonBytesHandler, RubyHandler, PhpHandler, GraphQLHandler, GraphQLBlockHandler, SqlAnsiHandler, PostgresEStringHandler,
+    CsvHandler, TomlHandler, YamlHandler, XmlHandler, PercentHandler
+};
+
+/// Pieces that are likely to end up somewhere interesting when put together.
+const PIECES: &[&str] = &[
+    "\\", "\\", "\\", "u", "U", "x", "N", "c", "{", "}", " ", "0", "1", "7", "8", "9", "a", "F", "g", "D83D", "DE00", "D800",
+    "10FFFF", "110000", "FFFFFFFFF", "\n", "\r", "\"", "'", "`", "%", "&", ";", "#", "amp", "_", "$",
+    "\u{E9}", "\u{1F600}", "\u{2028}", "\u{7F}", "\0",
+];
+
+fn cases() -> impl Iterator<Item = String> {
+    let mut rng = Rng(0x5EED_DE5C_A9E0_0001);
+    (0 .. 20_000).map(move |_| {
+        let len = rng.below(12);
+        (0 .. len).map(|_| PIECES[rng.below(PIECES.len())]).collect()
+    })
+}
+
+/// Checks that an error is inside of the input, starting and ending on character boundaries.
+fn check(name: &str, source: &str, res: Result<String, InvalidEscape>) {
+    if let Err(err) = res {
+        assert!(err.end() <= source.len(), "{} gave an error past the end of {:?}: {:?}", name, source, err);
+        assert!(
+            source.is_char_boundary(err.index) && source.is_char_boundary(err.end()),
+            "{} gave an error splitting a character in {:?}: {:?}", name, source, err
+        );
+    }
+}
+
+type Run = fn(&str) -> Result<String, InvalidEscape>;
+
+#[test]
+fn test_no_panic() {
+    let runs: &[(&str, Run)] = &[
+        ("default", |s| s.to_unescaped().map(Cow::into_owned)),
+        ("unescaper", |s| {
+            let unescaper = Unescaper::new()
+                .octal_mode(OctalMode::Strict).braced_x(true).control_escapes(true).multi_codepoint(true)
+                .unknown_escape(UnknownEscapePolicy::KeepBackslash);
+            unescaper.unescape(s).map(Cow::into_owned)
+        }),
+        ("decimal", |s| Unescaper::new().decimal(true).strict_x_ascii(true).unescape(s).map(Cow::into_owned)),
+        ("continuation", |s| s.to_unescaped_with(ContinuationHandler(DefaultHandler)).map(Cow::into_owned)),
+        ("resilient", |s| s.to_unescaped_with(ResilientHandler(JsonHandler)).map(Cow::into_owned)),
+        ("named", |s| s.to_unescaped_with(NamedUnicodeHandler(DefaultHandler)).map(Cow::into_owned)),
+        ("json", |s| s.to_unescaped_with(JsonHandler).map(Cow::into_owned)),
+        ("java", |s| s.to_unescaped_with(JavaHandler::new().dollar(true)).map(Cow::into_owned)),
+        ("kotlin", |s| s.to_unescaped_with(KotlinHandler).map(Cow::into_owned)),
+        ("js", |s| s.to_unescaped_with(JsHandler::new()).map(Cow::into_owned)),
+        ("js strict", |s| s.to_unescaped_with(JsHandler::new().strict(true)).map(Cow::into_owned)),
+        ("rust", |s| s.to_unescaped_with(RustHandler).map(Cow::into_owned)),
+        ("c", |s| s.to_unescaped_with(CHandler).map(Cow::into_owned)),
+        ("c#", |s| s.to_unescaped_with(CSharpHandler).map(Cow::into_owned)),
+        ("go", |s| s.to_unescaped_with(GoHandler).map(Cow::into_owned)),
+        ("lua", |s| s.to_unescaped_with(LuaHandler).map(Cow::into_owned)),
+        ("haskell", |s| s.to_unescaped_with(HaskellHandler).map(Cow::into_owned)),
+        ("ocaml", |s| s.to_unescaped_with(OCamlHandler).map(Cow::into_owned)),
+        ("elixir", |s| s.to_unescaped_with(ElixirHandler).map(Cow::into_owned)),
+        ("shell", |s| s.to_unescaped_with(ShellHandler).map(Cow::into_owned)),
+        ("powershell", |s| s.to_unescaped_with_escape_char('`', PowerShellHandler).map(Cow::into_owned)),
+        ("python", |s| s.to_unescaped_with(PythonStrHandler).map(Cow::into_owned)),
+        ("ruby", |s| s.to_unescaped_with(RubyHandler).map(Cow::into_owned)),
+        ("php", |s| s.to_unescaped_with(PhpHandler).map(Cow::into_owned)),
+        ("graphql", |s| s.to_unescaped_with(GraphQLHandler).map(Cow::into_owned)),
+        ("graphql block", |s| s.to_unescaped_with(GraphQLBlockHandler).map(Cow::into_owned)),
+        ("sql", |s| s.to_unescaped_with_escape_char('\'', SqlAnsiHandler).map(Cow::into_owned)),
+        ("csv", |s| CsvHandler::unescape_field(s).map(|(field, _)| field.into_owned())),
+        ("postgres", |s| s.to_unescaped_with(PostgresEStringHandler).map(Cow::into_owned)),
+        ("postgres literal", |s| PostgresEStringHandler::unescape_literal(s).map(|(literal, _)| literal.into_owned())),
+        ("toml", |s| s.to_unescaped_with(TomlHandler::new().multiline(true)).map(Cow::into_owned)),
+        ("yaml", |s| s.to_unescaped_with(YamlHandler).map(Cow::into_owned)),
+        ("xml", |s| s.to_unescaped_with_escape_char('&', XmlHandler).map(Cow::into_owned)),
+        ("chars", |s| s.unescape_chars().collect()),
+        ("spans", |s| s.unescape_spans().map(|res| res.map(|(_, chr)| chr)).collect()),
+        ("validate", |s| s.validate_escapes().map(|()| String::new())),
+        ("until", |s| s.unescape_until('"', DefaultHandler).map(|(contents, _)| contents.into_owned())),
+        ("quoted", |s| descape::escaped_string(s, '"', DefaultHandler).map(|(contents, _)| contents.into_owned())),
+        ("into", |s| {
+            let mut out = String::new();
+            s.unescape_into(&mut out).map(|()| out)
+        }),
+    ];
+
+    for source in cases() {
+        for (name, run) in runs.iter() {
+            check(name, &source, run(&source));
+        }
+
+        // These can't fail, so they only need to not panic
+        let _ = source.to_unescaped_lossy();
+        let escaped = source.to_escaped();
+        assert_eq!(escaped.to_unescaped().as_deref(), Ok(&*source), "{:?} did not round trip", source);
+        source.to_escaped_default();
+        source.to_json_escaped();
+        source.to_shell_escaped();
+
+        let bytes = source.as_bytes();
+        for res in [
+            bytes.to_unescaped_bytes().map(|_| ()),
+            bytes.to_unescaped_bytes_with(PythonBytesHandler).map(|_| ()),
+            bytes.to_unescaped_bytes_with_escape_byte(b'%', PercentHandler).map(|_| ()),
+        ].iter() {
+            if let Err(err) = res {
+                assert!(err.end() <= bytes.len(), "{:?} gave an error past the end: {:?}", source, err);
+            }
+        }
+    }
+}
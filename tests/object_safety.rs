@@ -0,0 +1,54 @@
+//! These only need to compile: if any of the handler traits stop being object-safe,
+//! this file stops building, instead of breaking everyone using them through `dyn`.
+#![cfg(feature = "alloc")]
+use std::str::CharIndices;
+use descape::{
+    UnescapeExt, EscapeHandler, MultiEscapeHandler, EscapeWriter, ByteEscapeHandler, Escaper,
+    DefaultHandler, DefaultEscaper, JsonHandler, PythonStrHandler
+};
+
+#[allow(dead_code)]
+fn assert_object_safe(
+    _: &dyn EscapeHandler,
+    _: &dyn MultiEscapeHandler,
+    _: &dyn EscapeWriter,
+    _: &dyn ByteEscapeHandler,
+    _: &dyn Escaper,
+) {}
+
+#[test]
+fn test_dyn_escape_handler() {
+    let mut handlers: Vec<Box<dyn EscapeHandler>> = vec![
+        Box::new(DefaultHandler),
+        Box::new(JsonHandler),
+        Box::new(|_: usize, chr: char, _: &mut CharIndices<'_>| Ok(Some(chr))),
+    ];
+    let unescaped: Vec<_> = handlers.iter_mut()
+        .map(|handler| r"\n".to_unescaped_with(handler.by_ref()).map(|cow| cow.into_owned()))
+        .collect();
+    assert_eq!(unescaped, [Ok("\n".into()), Ok("\n".into()), Ok("n".into())]);
+
+    let mut handler = DefaultHandler;
+    let handler: &mut dyn EscapeHandler = &mut handler;
+    assert_eq!(r"\x41".to_unescaped_with_dyn(handler).as_deref(), Ok("A"));
+    assert_eq!(String::from(r"\q").to_unescaped_with_dyn(handler).map_err(|err| err.index), Err(0));
+    assert_eq!(r"\x41".to_unescaped_with(handler).as_deref(), Ok("A"));
+}
+
+#[test]
+fn test_dyn_other_handlers() {
+    let mut multi: Box<dyn MultiEscapeHandler> = Box::new(PythonStrHandler);
+    let mut iter = "n".char_indices();
+    iter.next();
+    assert!(multi.escape_multi(0, 'n', &mut iter).is_ok());
+
+    let mut writer: Box<dyn EscapeWriter> = Box::new(DefaultHandler);
+    let mut out = String::new();
+    writer.write_escape(0, 't', &mut "".char_indices(), &mut out).expect("\\t is valid");
+    assert_eq!(out, "\t");
+
+    let mut escaper: Box<dyn Escaper> = Box::new(DefaultEscaper);
+    let mut out = String::new();
+    assert!(escaper.escape_char('\n', &mut out));
+    assert_eq!(out, "\\n");
+}
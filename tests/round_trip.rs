@@ -0,0 +1,36 @@
+//! Checks that escaping and unescaping again always gives back the original string,
+//! for every escaper with a matching handler.
+#![cfg(feature = "alloc")]
+
+mod common;
+
+use common::Rng;
+use descape::{UnescapeExt, EscapeExt, JsonEscaper, RustHandler, JsonHandler};
+
+#[test]
+fn test_round_trip() {
+    let mut rng = Rng(0x0DD5_EED5_0FCA_FE00);
+    for _ in 0 .. 20_000 {
+        let raw = rng.string(16);
+
+        let escaped = raw.to_escaped();
+        assert_eq!(escaped.to_unescaped().as_deref(), Ok(&*raw), "{:?} did not round trip through {:?}", raw, escaped);
+        // Every way of unescaping should agree
+        assert_eq!(escaped.to_unescaped_exact().as_deref(), Ok(&*raw), "{:?} did not round trip exactly", raw);
+        assert_eq!(escaped.unescape_chars().collect::<Result<String, _>>().as_deref(), Ok(&*raw), "{:?} did not round trip as chars", raw);
+
+        let escaped = raw.to_escaped_default();
+        assert_eq!(
+            escaped.to_unescaped_with(RustHandler).as_deref(), Ok(&*raw),
+            "{:?} did not round trip through {:?}", raw, escaped
+        );
+
+        for escaper in [JsonEscaper::new(), JsonEscaper::new().ascii_only(true)].iter() {
+            let escaped = raw.to_escaped_with(*escaper);
+            assert_eq!(
+                escaped.to_unescaped_with(JsonHandler).as_deref(), Ok(&*raw),
+                "{:?} did not round trip through {:?}", raw, escaped
+            );
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use descape::{scan, EscapeErrorKind};
+
+#[test]
+fn test_hex() {
+    for (string, res, len) in [
+        ("41", Ok('A'), 2),
+        ("ff!", Ok('\u{FF}'), 2),
+        ("", Err(EscapeErrorKind::Truncated), 0),
+        ("4", Err(EscapeErrorKind::Truncated), 1),
+        ("4G", Err(EscapeErrorKind::InvalidHexDigit), 2),
+        ("4é", Err(EscapeErrorKind::InvalidHexDigit), 3),
+    ].iter() {
+        assert_eq!(scan::hex(string), (*res, *len), "{:?} gave the wrong result", string);
+    }
+}
+
+#[test]
+fn test_unicode() {
+    for (string, res, len) in [
+        ("{41}", Ok('A'), 4),
+        ("{41", Err(EscapeErrorKind::Truncated), 3),
+        ("0041", Ok('A'), 4),
+        ("004", Err(EscapeErrorKind::Truncated), 3),
+        ("12é3", Err(EscapeErrorKind::InvalidHexDigit), 5),
+        (r"D83D\uDE00", Ok('\u{1F600}'), 10),
+        (r"D83DA", Err(EscapeErrorKind::LoneSurrogate(0xD83D)), 4),
+    ].iter() {
+        assert_eq!(scan::unicode(string), (*res, *len), "{:?} gave the wrong result", string);
+    }
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_unicode_multi() {
+    for (string, res, out, len) in [
+        ("{61 62 63}", Ok(()), "abc", 10),
+        ("{ 61\t1F600 }!", Ok(()), "a\u{1F600}", 12),
+        ("{41}", Ok(()), "A", 4),
+        ("0041", Ok(()), "A", 4),
+        ("{}", Err(EscapeErrorKind::InvalidHexDigit), "", 2),
+        ("{  }", Err(EscapeErrorKind::InvalidHexDigit), "", 4),
+        ("{61 6G}", Err(EscapeErrorKind::InvalidHexDigit), "a", 7),
+        ("{61 DE00}", Err(EscapeErrorKind::LoneSurrogate(0xDE00)), "a", 9),
+        ("{61 62", Err(EscapeErrorKind::Truncated), "", 6),
+    ].iter() {
+        let mut buf = String::new();
+        assert_eq!(scan::unicode_multi(string, &mut buf), (*res, *len), "{:?} gave the wrong result", string);
+        assert_eq!(buf, *out, "{:?} pushed the wrong characters", string);
+    }
+}
+
+#[test]
+fn test_octal() {
+    for (first, rest, res, len) in [
+        ('0', "", Ok(0), 0),
+        ('1', "2", Ok(0o12), 1),
+        ('1', "234", Ok(0o123), 2),
+        ('7', "77", Ok(0o777), 2),
+        ('3', "8", Ok(0o3), 0),
+        ('9', "", Err(EscapeErrorKind::UnknownEscape('9')), 0),
+    ].iter() {
+        assert_eq!(scan::octal(*first, rest), (*res, *len), "{:?} {:?} gave the wrong result", first, rest);
+    }
+}
+
+#[test]
+fn test_decimal() {
+    for (first, rest, res, len) in [
+        ('0', "", Ok(0), 0),
+        ('6', "5", Ok(65), 1),
+        ('0', "659", Ok(65), 2),
+        ('9', "99", Ok(999), 2),
+        ('1', "a", Ok(1), 0),
+        ('a', "", Err(EscapeErrorKind::UnknownEscape('a')), 0),
+    ].iter() {
+        assert_eq!(scan::decimal(*first, rest), (*res, *len), "{:?} {:?} gave the wrong result", first, rest);
+    }
+}
+
+#[test]
+fn test_consume() {
+    // The iterator is advanced past the sequence even on failure
+    let mut iter = "4Gabc".char_indices();
+    assert_eq!(scan::consume(&mut iter, scan::hex), Err(EscapeErrorKind::InvalidHexDigit));
+    assert_eq!(iter.as_str(), "abc");
+
+    let mut iter = "1234".char_indices();
+    assert_eq!(scan::consume(&mut iter, |rest| scan::octal('0', rest)), Ok(0o12));
+    assert_eq!(iter.next(), Some((2, '3')));
+}
@@ -1,6 +1,9 @@
+#![cfg(feature = "alloc")]
+
 use std::borrow::Cow;
+use std::convert::TryFrom;
 use std::str::CharIndices;
-use descape::UnescapeExt;
+use descape::{UnescapeExt, EscapeHandler, MultiEscapeHandler, Replacement, DefaultHandler, EscapeErrorKind, InvalidEscape, UnescapeFmtError, Unescaper, Escapes, UnknownEscapePolicy, TrailingPolicy, OctalMode, HexMode, EscapeWriter, unescape_control, ESCAPE_TABLE, Unescaped, UnescapedString};
 
 macro_rules! ensure_err {
     ($($name: ident),+) => {$(
@@ -50,6 +53,15 @@ fn test_escapes() {
         EMPTY_HEX,
         NON_UNICODE
     );
+
+    // The error should point at the trailing backslash in the source, not the output
+    for (string, index) in [("ab\\", 2), ("\\n\\", 2), ("\\u{1F600}\\", 9), ("❤️\\t\\", 8)].iter() {
+        assert_eq!(
+            string.to_unescaped().map_err(|err| err.index),
+            Err(*index),
+            "trailing backslash in {:?} reported at the wrong index", string
+        );
+    }
 }
 
 fn custom_esc(_: usize, chr: char, iter: &mut CharIndices<'_>) -> Result<Option<char>, ()> {
@@ -83,3 +95,1276 @@ fn test_customs() {
 }
 
 
+
+struct Expand;
+
+impl MultiEscapeHandler for Expand {
+    fn escape_multi<'source>(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'source>) -> Result<Option<Replacement<'source>>, EscapeErrorKind> {
+        match chr {
+            't' => Ok(Some("    ".into())),
+            // Borrows the rest of the word from the source
+            'w' => {
+                let rest = iter.as_str();
+                let end = rest.find(' ').unwrap_or(rest.len());
+                for _ in rest[..end].chars() { iter.next(); }
+                Ok(Some(rest[..end].into()))
+            },
+            'N' => Ok(Some(String::from("e\u{301}").into())),
+            _ => DefaultHandler.escape_detailed(idx, chr, iter).map(|chr| chr.map(Replacement::Char))
+        }
+    }
+}
+
+#[test]
+fn test_multi() {
+    assert_eq!(
+        r"\tcaf\N\n".to_unescaped_with(Expand).expect("multi escape should succeed"),
+        Cow::<'static, str>::Owned(String::from("    cafe\u{301}\n")),
+        "multi escape gave incorrect result"
+    );
+    assert_eq!(
+        r"\wword \t".to_unescaped_with(Expand).expect("multi escape should succeed"),
+        Cow::<'static, str>::Owned(String::from("word     ")),
+        "multi escape gave incorrect result"
+    );
+    r"\q".to_unescaped_with(Expand)
+        .expect_err(r"multi escape should fail for \q");
+}
+
+#[test]
+fn test_long_unicode() {
+    assert_eq!(
+        r"\U0001F600 \U00000041\U0010FFFF0".to_unescaped().expect(r"\U escapes should be valid"),
+        Cow::<'static, str>::Owned(String::from("\u{1F600} A\u{10FFFF}0")),
+        r"\U escapes gave incorrect result"
+    );
+    assert_eq!(
+        r"abc \U0001F60".to_unescaped().map_err(|err| err.index),
+        Err(4),
+        r"truncated \U escape reported at the wrong index"
+    );
+}
+
+#[test]
+fn test_surrogate_pairs() {
+    assert_eq!(
+        r"\uD83D\uDE00 \U0001F600\uDBFF\uDFFF".to_unescaped().expect("surrogate pairs should be valid"),
+        Cow::<'static, str>::Owned(String::from("\u{1F600} \u{1F600}\u{10FFFF}")),
+        "surrogate pairs gave incorrect result"
+    );
+    for (string, index, kind) in [
+        // High surrogates need to be followed by a low surrogate
+        (r"ab\uD83D", 2, EscapeErrorKind::LoneSurrogate(0xD83D)),
+        (r"ab\uD83Dx", 2, EscapeErrorKind::LoneSurrogate(0xD83D)),
+        (r"ab\uD83DA", 2, EscapeErrorKind::LoneSurrogate(0xD83D)),
+        (r"ab\uD83D\uD83D", 2, EscapeErrorKind::LoneSurrogate(0xD83D)),
+        (r"ab\uD83D\u{DE00}", 2, EscapeErrorKind::LoneSurrogate(0xD83D)),
+        // Low surrogates can't stand on their own
+        (r"ab\uDE00", 2, EscapeErrorKind::LoneSurrogate(0xDE00)),
+        (r"ab\u0041\uDE00", 8, EscapeErrorKind::LoneSurrogate(0xDE00)),
+        // Braced escapes are codepoints, not UTF-16
+        (r"ab\u{D83D}\uDE00", 2, EscapeErrorKind::LoneSurrogate(0xD83D)),
+    ].iter() {
+        let err = string.to_unescaped().unwrap_err();
+        assert_eq!((err.index, err.kind), (*index, *kind), "{:?} gave the wrong error", string);
+    }
+}
+
+#[test]
+fn test_error_kinds() {
+    for (string, kind, len) in [
+        (r"\q", EscapeErrorKind::UnknownEscape('q'), 2),
+        (r"\❤️", EscapeErrorKind::UnknownEscape('❤'), 4),
+        (r"\", EscapeErrorKind::Truncated, 1),
+        (r"\x", EscapeErrorKind::Truncated, 2),
+        (r"\xA", EscapeErrorKind::Truncated, 3),
+        (r"\u", EscapeErrorKind::Truncated, 2),
+        (r"\u12", EscapeErrorKind::Truncated, 4),
+        (r"\u{41", EscapeErrorKind::Truncated, 5),
+        (r"\xGG", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"\u{}", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"\u{+41}", EscapeErrorKind::InvalidHexDigit, 7),
+        (r"\u12é3", EscapeErrorKind::InvalidHexDigit, 7),
+        (r"\u12é3456", EscapeErrorKind::InvalidHexDigit, 7),
+        (r"\u123é", EscapeErrorKind::InvalidHexDigit, 7),
+        (r"\u1G", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"\u{110000}", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+        (r"\u{FFFFFFFFFFFF}", EscapeErrorKind::InvalidCodepoint(u32::MAX), 16),
+        (r"\u{D800}", EscapeErrorKind::LoneSurrogate(0xD800), 8),
+        (r"\uDFFF", EscapeErrorKind::LoneSurrogate(0xDFFF), 6),
+        (r"\U", EscapeErrorKind::Truncated, 2),
+        (r"\U0001F60", EscapeErrorKind::Truncated, 9),
+        (r"\U0001F60Z", EscapeErrorKind::InvalidHexDigit, 10),
+        (r"\U00110000", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+        (r"\U0000D800", EscapeErrorKind::LoneSurrogate(0xD800), 10),
+    ].iter() {
+        assert_eq!(
+            string.to_unescaped(),
+            Err(InvalidEscape::with_kind(0, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+
+    // The span should cover the whole sequence, and nothing after it
+    let source = r"Uh oh! \u{ZZZ} \n";
+    let err = source.to_unescaped().unwrap_err();
+    assert_eq!(&source[err.index .. err.end()], r"\u{ZZZ}");
+
+    assert_eq!(
+        r"\T".to_unescaped_with(custom_esc).unwrap_err().kind,
+        EscapeErrorKind::Rejected,
+        "custom escape handler errors should be reported as rejected"
+    );
+    assert_eq!(
+        r"ab\q".to_unescaped().unwrap_err().to_string(),
+        "invalid escape sequence `\\q` at index 2: unknown escape character 'q'"
+    );
+}
+
+#[test]
+fn test_unescaper() {
+    let default = Unescaper::new();
+    assert_eq!(
+        default.unescape(r"\x41\101\xFF"),
+        r"\x41\101\xFF".to_unescaped(),
+        "default unescaper should match DefaultHandler"
+    );
+
+    let no_octal = Unescaper::new().allow_octal(false);
+    assert_eq!(no_octal.unescape(r"\x41\t").expect("non-octal escapes are still valid"), "A\t");
+    assert_eq!(
+        no_octal.unescape(r"ab\101").unwrap_err(),
+        InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('1')).with_len(2)
+    );
+
+    let strict = Unescaper::new().strict_x_ascii(true);
+    assert_eq!(strict.unescape(r"\x7F\377").expect("ASCII hex escapes are still valid"), "\x7F\u{FF}");
+    assert_eq!(
+        strict.unescape(r"ab\x80").unwrap_err(),
+        InvalidEscape::with_kind(2, EscapeErrorKind::OutOfRange(0x80)).with_len(4)
+    );
+
+    // Unescapers are handlers too
+    assert_eq!(
+        r"\101".to_unescaped_with(Unescaper::new()).expect("octal is enabled by default"),
+        "A"
+    );
+}
+
+#[test]
+fn test_decimal() {
+    let decimal = Unescaper::new().decimal(true);
+    assert_eq!(decimal.unescape(r"\065\066\67\9\0").as_deref(), Ok("ABC\t\0"));
+    assert_eq!(decimal.unescape(r"\0650").as_deref(), Ok("A0"));
+    assert_eq!(decimal.unescape(r"\999").as_deref(), Ok("\u{3E7}"));
+    assert_eq!(decimal.octal_mode(OctalMode::Wrap).unescape(r"\256\999").as_deref(), Ok("\0\u{E7}"));
+    assert_eq!(
+        decimal.octal_mode(OctalMode::Strict).unescape(r"ab\256"),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::OutOfRange(256)).with_len(4))
+    );
+    assert_eq!(decimal.octal_mode(OctalMode::Strict).unescape(r"\255").as_deref(), Ok("\u{FF}"));
+    // Turning off octal escapes turns these off too
+    assert!(decimal.allow_octal(false).unescape(r"\65").is_err());
+    assert!(decimal.decimal(false).unescape(r"\9").is_err());
+}
+
+#[test]
+fn test_strict_nul() {
+    let strict = Unescaper::new().strict_nul(true);
+    assert_eq!(strict.unescape(r"\0").as_deref(), Ok("\0"));
+    assert_eq!(strict.unescape(r"\08\0a\7").as_deref(), Ok("\08\0a\x07"));
+    assert_eq!(strict.unescape(r"ab\01"), Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('0')).with_len(2)));
+    // Legacy behaviour, where \01 is an octal escape
+    assert_eq!(Unescaper::new().unescape(r"\0\01\08").as_deref(), Ok("\0\x01\08"));
+    // With decimal escapes, \08 is a longer escape too
+    assert!(strict.decimal(true).unescape(r"\08").is_err());
+    assert_eq!(strict.decimal(true).unescape(r"\0a").as_deref(), Ok("\0a"));
+}
+
+#[test]
+fn test_multi_codepoint() {
+    let multi = Unescaper::new().multi_codepoint(true);
+    assert_eq!(multi.unescape(r"\u{61 62}\u{ 63 }d\u{1F600}").as_deref(), Ok("abcd\u{1F600}"));
+    for (string, kind, len) in [
+        (r"ab\u{ }", EscapeErrorKind::InvalidHexDigit, 5),
+        (r"ab\u{61 XY}", EscapeErrorKind::InvalidHexDigit, 9),
+        (r"ab\u{D83D DE00}", EscapeErrorKind::LoneSurrogate(0xD83D), 13),
+    ].iter() {
+        assert_eq!(
+            multi.unescape(string),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+    // Off by default, and turned off with the rest of the braced escapes
+    assert!(Unescaper::new().unescape(r"\u{61 62}").is_err());
+    assert!(multi.escapes(Escapes::ALL - Escapes::UNICODE_BRACED).unescape(r"\u{61}").is_err());
+}
+
+#[test]
+fn test_max_output_len() {
+    let limited = Unescaper::new().multi_codepoint(true).max_output_len(6);
+    assert_eq!(limited.unescape(r"abc\x41\t"), Ok(Cow::Owned(String::from("abcA\t"))));
+    assert_eq!(limited.unescape(r"abcdef\t"), Err(InvalidEscape::with_kind(6, EscapeErrorKind::OutputTooLong).with_len(2)));
+    assert_eq!(limited.unescape(r"a\u{1F600 1F600}"), Err(InvalidEscape::with_kind(1, EscapeErrorKind::OutputTooLong).with_len(15)));
+    assert_eq!(limited.unescape(r"\u{1F600}ab"), Ok(Cow::Owned(String::from("\u{1F600}ab"))));
+    // Text is checked too, pointing at the first character that doesn't fit
+    let too_long = |index, len| InvalidEscape::with_kind(index, EscapeErrorKind::OutputTooLong).with_len(len);
+    assert_eq!(limited.unescape("no escapes here"), Err(too_long(6, 1)));
+    assert_eq!(limited.unescape(r"\tabcdefg"), Err(too_long(7, 1)));
+    assert_eq!(limited.unescape(r"\tabcd😀"), Err(too_long(6, 4)));
+    assert_eq!(limited.unescape(r"abcdefg\t"), Err(too_long(6, 1)));
+    assert_eq!(limited.unescape(r"\tabcde"), Ok(Cow::Owned(String::from("\tabcde"))));
+    assert_eq!(limited.unescape("abcdef"), Ok(Cow::Borrowed("abcdef")));
+    for string in ["no escapes here", r"\tabcdefg", r"\tabcd😀", r"\tabcde"].iter() {
+        assert_eq!(string.to_unescaped_exact_with(limited), limited.unescape(string), "{:?} gave incorrect result", string);
+        assert_eq!(string.validate_escapes_with(limited), limited.unescape(string).map(|_| ()), "{:?} gave incorrect result", string);
+    }
+    assert_eq!(
+        r#"\tabc"de"#.unescape_until('"', limited).map(|(contents, _)| contents),
+        Ok(Cow::Owned(String::from("\tabc")))
+    );
+    assert_eq!(r#"abcdefg"\t"#.unescape_until('"', limited), Err(too_long(6, 1)));
+
+    // Only what's written by this call counts
+    let mut out = String::from("12345");
+    assert_eq!(r"\t\t".unescape_into_with(&mut out, limited), Ok(()));
+    assert_eq!(out, "12345\t\t");
+    assert_eq!(r"\tabcdefg".unescape_into_with(&mut out, limited), Err(too_long(7, 1)));
+    assert_eq!(out, "12345\t\t\t");
+
+    assert!(Unescaper::new().unescape(&r"\u{1F600}".repeat(100)).is_ok());
+
+    // Passes that don't build the output still count everything before each escape
+    let err = InvalidEscape::with_kind(10, EscapeErrorKind::OutputTooLong).with_len(2);
+    assert_eq!(r"abcd\x41\t\n".to_unescaped_with(limited), Err(err));
+    assert_eq!(r"abcd\x41\t\n".to_unescaped_exact_with(limited), Err(err));
+    assert_eq!(r"abcd\x41\t\n".validate_escapes_with(limited), Err(err));
+    assert_eq!(r"abcd\x41\t".to_unescaped_exact_with(limited), Ok(Cow::Owned(String::from("abcdA\t"))));
+    // Sequences kept as written count towards the limit before the output is built, too
+    let keep = Unescaper::new().unknown_escape(UnknownEscapePolicy::KeepBackslash).max_output_len(4);
+    assert_eq!(r"\q\q\n".to_unescaped_with(keep), Err(InvalidEscape::with_kind(4, EscapeErrorKind::OutputTooLong).with_len(2)));
+    assert_eq!(r"\q\q".to_unescaped_with(keep), Ok(Cow::Borrowed(r"\q\q")));
+}
+
+#[test]
+fn test_normalize_newlines() {
+    let unescaper = Unescaper::new().normalize_newlines(true);
+    for (escaped, unescaped) in [
+        (r"a\r\nb", "a\nb"),
+        ("a\r\nb", "a\nb"),
+        ("a\\r\nb", "a\nb"),
+        ("a\r\\nb", "a\nb"),
+        (r"a\r\r\n\rb\n\r", "a\r\n\rb\n\r"),
+        ("\r\n\r\n", "\n\n"),
+    ].iter() {
+        assert_eq!(unescaper.unescape(escaped).as_deref(), Ok(*unescaped), "{:?} gave incorrect result", escaped);
+    }
+    assert!(matches!(unescaper.unescape("no\nline\rendings"), Ok(Cow::Borrowed(_))));
+    assert_eq!(Unescaper::new().unescape(r"a\r\nb").as_deref(), Ok("a\r\nb"));
+    // Only `Unescaper::unescape` normalizes
+    assert_eq!("a\r\nb".to_unescaped_with(unescaper).as_deref(), Ok("a\r\nb"));
+    // ...and only after the length has been checked
+    let limited = unescaper.max_output_len(3);
+    assert_eq!(limited.unescape(r"a\nb").as_deref(), Ok("a\nb"));
+    assert_eq!(limited.unescape(r"a\r\nb"), Err(InvalidEscape::with_kind(5, EscapeErrorKind::OutputTooLong).with_len(1)));
+}
+
+#[test]
+fn test_escapes_set() {
+    let no_octal = DefaultHandler::builder().escapes(Escapes::ALL - Escapes::OCTAL);
+    assert_eq!(no_octal.unescape(r"\11"), Err(InvalidEscape::with_kind(0, EscapeErrorKind::UnknownEscape('1')).with_len(2)));
+    assert_eq!(no_octal.unescape(r"\t\x09\u0009"), Ok("\t\t\t".into()));
+    assert_eq!(no_octal, Unescaper::new().allow_octal(false));
+    assert_eq!(no_octal.allow_octal(true), Unescaper::new());
+
+    for (set, enabled, disabled) in [
+        (Escapes::ALL - Escapes::BACKTICK, r"\'", r"\`"),
+        (Escapes::ALL - Escapes::UNICODE_BRACED, r"\u0041", r"\u{41}"),
+        (Escapes::ALL - Escapes::UNICODE_FIXED, r"\u{41}", r"\u0041"),
+        (Escapes::ALL - Escapes::HEX, r"\U00000041", r"\x41"),
+        (Escapes::NEWLINE | Escapes::BACKSLASH, r"\\\n", r"\t"),
+        (Escapes::NONE, "no escapes", r"\\"),
+    ].iter() {
+        let unescaper = Unescaper::new().escapes(*set);
+        assert!(unescaper.unescape(enabled).is_ok(), "{:?} should be enabled", enabled);
+        assert!(unescaper.unescape(disabled).is_err(), "{:?} should be disabled", disabled);
+    }
+
+    // Disabled escapes are unknown, so the policy for those still applies
+    let keep = Unescaper::new().escapes(Escapes::ALL - Escapes::ESCAPE).unknown_escape(UnknownEscapePolicy::KeepBackslash);
+    assert_eq!(keep.unescape(r"\e\n").as_deref(), Ok("\\e\n"));
+    // Doubled custom escape characters count as backslashes
+    let caret = Unescaper::new().escape_char('^').escapes(Escapes::ALL - Escapes::BACKSLASH);
+    assert!(caret.unescape("^^").is_err());
+    assert!(Escapes::ALL.contains(Escapes::OCTAL | Escapes::HEX));
+    assert!(!(Escapes::ALL - Escapes::HEX).contains(Escapes::OCTAL | Escapes::HEX));
+}
+
+#[test]
+fn test_from_allowed() {
+    assert_eq!("abtnvfre'\"`\\".parse(), Ok(Escapes::ALL - Escapes::HEX - Escapes::OCTAL - Escapes::UNICODE_FIXED - Escapes::UNICODE_BRACED - Escapes::UNICODE_LONG));
+    assert_eq!("".parse(), Ok(Escapes::NONE));
+    assert_eq!("nn".parse(), Ok(Escapes::NEWLINE));
+    for chr in ['x', 'u', 'U', '0', '7', 'q', 'é'].iter() {
+        assert_eq!(format!("n{}", chr).parse::<Escapes>(), Err(EscapeErrorKind::UnknownEscape(*chr)), "{:?} should be rejected", chr);
+    }
+
+    let unescaper = DefaultHandler::from_allowed(r#"ntr\""#).expect("these are all single-character escapes");
+    assert_eq!(unescaper.unescape(r#"\n\t\r\\\""#).as_deref(), Ok("\n\t\r\\\""));
+    for string in [r"\a", r"\'", r"\x41", r"\101"].iter() {
+        assert!(unescaper.unescape(string).is_err(), "{:?} should be rejected", string);
+    }
+    let with_hex = unescaper.escapes("n".parse::<Escapes>().unwrap() | Escapes::HEX);
+    assert_eq!(with_hex.unescape(r"\x41\n").as_deref(), Ok("A\n"));
+    assert_eq!(DefaultHandler::from_allowed("n?"), Err(EscapeErrorKind::UnknownEscape('?')));
+}
+
+#[test]
+fn test_escape_char() {
+    assert_eq!(
+        r"50%% off%n\n".to_unescaped_with_escape_char('%', Unescaper::new().escape_char('%')).expect("custom escape character should be valid"),
+        Cow::<'static, str>::Owned(String::from("50% off\n\\n")),
+        "custom escape character gave incorrect result"
+    );
+    assert_eq!(
+        "a€u{41}€€".to_unescaped_with_escape_char('€', Unescaper::new().escape_char('€')).expect("multibyte escape character should be valid"),
+        Cow::<'static, str>::Owned(String::from("aA€")),
+        "multibyte escape character gave incorrect result"
+    );
+    assert_eq!(
+        r"no escapes \n".to_unescaped_with_escape_char('^', DefaultHandler).expect("backslashes aren't escapes here"),
+        Cow::Borrowed(r"no escapes \n")
+    );
+
+    // A trailing escape character should be truncated, spanning the whole character
+    for (string, escape, index, len) in [("ab%", '%', 2, 1), ("ab€", '€', 2, 3), ("❤️€t€", '€', 10, 3)].iter() {
+        assert_eq!(
+            string.to_unescaped_with_escape_char(*escape, DefaultHandler),
+            Err(InvalidEscape::with_kind(*index, EscapeErrorKind::Truncated).with_len(*len)),
+            "trailing escape character in {:?} gave the wrong error", string
+        );
+    }
+
+    let unescaper = Unescaper::new().escape_char('^');
+    assert_eq!(unescaper.unescape(r"^^\^x41").expect("unescaper should use its escape character"), r"^\A");
+    assert_eq!(
+        unescaper.unescape(r"ab^q").unwrap_err(),
+        InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('q')).with_len(2)
+    );
+    // Only the escape character escapes itself
+    assert_eq!(
+        unescaper.unescape(r"ab^\"),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('\\')).with_len(2))
+    );
+    assert_eq!(
+        unescaper.unknown_escape(UnknownEscapePolicy::KeepBackslash).unescape(r"^\^^").as_deref(),
+        Ok(r"^\^")
+    );
+    assert_eq!(unescaper.escapes(Escapes::ALL - Escapes::BACKSLASH).unescape(r"^\").unwrap_err().kind, EscapeErrorKind::UnknownEscape('\\'));
+
+    // Unknown escapes are kept with the character that was searched for, not the configured one
+    let keep = unescaper.unknown_escape(UnknownEscapePolicy::KeepBackslash);
+    assert_eq!(r"\d^d".to_unescaped_with(keep).as_deref(), Ok(r"\d^d"));
+    let keep = Unescaper::new().unknown_escape(UnknownEscapePolicy::KeepBackslash);
+    assert_eq!("%d\\d".to_unescaped_with_escape_char('%', keep).as_deref(), Ok(r"%d\d"));
+    assert_eq!("%d".to_unescaped_with_escape_char('%', descape::ResilientHandler(keep)).as_deref(), Ok("%d"));
+}
+
+#[test]
+fn test_lossy() {
+    assert_eq!(
+        r"\q\n\u{D800}\xGG \u{41".to_unescaped_lossy(),
+        Cow::<'static, str>::Owned(String::from("\u{FFFD}\n\u{FFFD}\u{FFFD} \u{FFFD}")),
+        "lossy unescaping gave incorrect result"
+    );
+    assert_eq!(r"trailing\".to_unescaped_lossy(), "trailing\u{FFFD}");
+    assert_eq!(
+        "No escapes here!".to_unescaped_lossy(),
+        Cow::Borrowed("No escapes here!"),
+        "lossy unescaping allocated without escapes"
+    );
+
+    assert_eq!(r"a\qb\tc".to_unescaped_lossy_with(Some('?'), DefaultHandler), "a?b\tc");
+    // Keeping the sequence keeps everything the handler consumed
+    assert_eq!(
+        r"\q\n\u{D800}\xGG \".to_unescaped_lossy_with(None, DefaultHandler),
+        Cow::<'static, str>::Owned(String::from("\\q\n\\u{D800}\\xGG \\")),
+        "lossy unescaping didn't keep invalid sequences"
+    );
+    assert_eq!(r"Spam E\Tags \Tx".to_unescaped_lossy_with(None, custom_esc), r"Spam Eggs \Tx");
+}
+
+#[test]
+fn test_validate() {
+    for string in [r"No escapes here!", r"\a\b\t\n\u{1F600}😀\x7E\101", ""].iter() {
+        assert_eq!(string.validate_escapes(), Ok(()), "{:?} should be valid", string);
+    }
+    // Validation should fail in exactly the same way unescaping does
+    for string in [r"\Z", r"\", r"ab\u{D800}", r"❤️\xGG", r"\uD83D", r"\n\U0001F60"].iter() {
+        assert_eq!(
+            string.validate_escapes(),
+            string.to_unescaped().map(|_| ()),
+            "{:?} gave a different error when validating", string
+        );
+    }
+    assert_eq!(r"E\Tags".validate_escapes_with(custom_esc), Ok(()));
+    assert_eq!(
+        r"E\Tx".validate_escapes_with(custom_esc),
+        Err(InvalidEscape::with_kind(1, EscapeErrorKind::Rejected).with_len(3))
+    );
+
+    // Handlers that only implement `EscapeWriter` validate the same way they unescape
+    let keep = Unescaper::new().unknown_escape(UnknownEscapePolicy::KeepBackslash);
+    let literal = Unescaper::new().trailing_backslash(TrailingPolicy::Literal);
+    for &(string, handler) in [(r"\q\n", keep), (r"\q\xGG", keep), (r"ab\", literal), (r"ab\", keep), (r"\q", literal)].iter() {
+        assert_eq!(
+            string.validate_escapes_with(handler),
+            string.to_unescaped_with(handler).map(|_| ()),
+            "{:?} gave a different error when validating", string
+        );
+    }
+    assert_eq!(r"ab\".validate_escapes_with(literal), Ok(()));
+}
+
+#[test]
+fn test_unescape_chars() {
+    static ESCAPED: &str = r"\a \t❤️\u{1F600}\x41\101😀 \\";
+    assert_eq!(
+        ESCAPED.unescape_chars().collect::<Result<String, _>>(),
+        ESCAPED.to_unescaped().map(Cow::into_owned),
+        "streaming unescaping gave a different result"
+    );
+
+    // The error should be the same as unescaping, and the iterator should stop after it
+    let mut chars = r"ab\u{D800}cd".unescape_chars();
+    assert_eq!(chars.next(), Some(Ok('a')));
+    assert_eq!(chars.next(), Some(Ok('b')));
+    assert_eq!(chars.next(), Some(Err(InvalidEscape::with_kind(2, EscapeErrorKind::LoneSurrogate(0xD800)).with_len(8))));
+    assert_eq!(chars.next(), None);
+    assert_eq!(chars.as_str(), "");
+
+    let mut chars = r"x\".unescape_chars();
+    assert_eq!(chars.next(), Some(Ok('x')));
+    assert_eq!(chars.next(), Some(Err(InvalidEscape::with_kind(1, EscapeErrorKind::Truncated).with_len(1))));
+    assert_eq!(chars.next(), None);
+
+    // Removed escapes are skipped
+    let no_z = |_: usize, chr: char, _: &mut CharIndices<'_>| if chr == 'z' { Ok(None) } else { Err(()) };
+    assert_eq!(r"\za\zb\z".unescape_chars_with(no_z).collect::<Result<String, _>>(), Ok(String::from("ab")));
+    let mut chars = r"E\Tags".unescape_chars_with(custom_esc);
+    assert_eq!(chars.nth(1), Some(Ok('g')));
+    assert_eq!(chars.as_str(), "gs");
+}
+
+#[test]
+fn test_unescape_spans() {
+    static ESCAPED: &str = r"a\t❤\u{1F600}\x41 \\";
+    let spans: Vec<_> = ESCAPED.unescape_spans().collect::<Result<_, _>>().unwrap();
+    assert_eq!(spans, [
+        (0..1, 'a'),
+        (1..3, '\t'),
+        (3..6, '❤'),
+        (6..15, '\u{1F600}'),
+        (15..19, 'A'),
+        (19..20, ' '),
+        (20..22, '\\'),
+    ]);
+    for (range, chr) in &spans {
+        assert_eq!(ESCAPED[range.clone()].to_unescaped().as_deref(), Ok(&*chr.to_string()), "{:?} has the wrong span", chr);
+    }
+
+    let mut spans = r"ab\u{D800}cd".unescape_spans();
+    assert_eq!(spans.nth(1), Some(Ok((1..2, 'b'))));
+    assert_eq!(spans.next(), Some(Err(InvalidEscape::with_kind(2, EscapeErrorKind::LoneSurrogate(0xD800)).with_len(8))));
+    assert_eq!(spans.next(), None);
+}
+
+struct Entities;
+
+impl EscapeWriter for Entities {
+    fn write_escape(&mut self, idx: usize, chr: char, iter: &mut CharIndices<'_>, out: &mut String) -> Result<(), EscapeErrorKind> {
+        match chr {
+            '&' => {
+                // Writes a partial entity before failing, which should be discarded
+                out.push('&');
+                let (_, name) = iter.next().ok_or(EscapeErrorKind::Truncated)?;
+                out.push_str(match name {
+                    'l' => "lt;",
+                    'g' => "gt;",
+                    _ => return Err(EscapeErrorKind::UnknownEscape(name))
+                });
+                Ok(())
+            },
+            _ => DefaultHandler.write_escape(idx, chr, iter, out)
+        }
+    }
+}
+
+#[test]
+fn test_writer() {
+    assert_eq!(
+        r"\&lb\&g \n".to_unescaped_with(Entities).expect("escape writer should succeed"),
+        Cow::<'static, str>::Owned(String::from("&lt;b&gt; \n")),
+        "escape writer gave incorrect result"
+    );
+    assert_eq!(
+        r"ab\&x".to_unescaped_with(Entities),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('x')).with_len(3))
+    );
+    assert_eq!(
+        r"\&lb\&x\&".to_unescaped_lossy_with(Some('?'), Entities),
+        "&lt;b??",
+        "escape writer output wasn't discarded on failure"
+    );
+    assert_eq!(r"\&x \q".to_unescaped_lossy_with(None, Entities), r"\&x \q");
+}
+
+#[test]
+fn test_escape_table() {
+    let singles: Vec<_> = ESCAPE_TABLE.iter()
+        .enumerate()
+        .filter_map(|(byte, chr)| chr.map(|chr| (char::from(byte as u8), chr)))
+        .collect();
+    assert_eq!(singles.len(), 12);
+    for (escape, unescaped) in singles {
+        assert_eq!(
+            format!("\\{}", escape).to_unescaped().as_deref(),
+            Ok(&*unescaped.to_string()),
+            "{:?} doesn't match DefaultHandler", escape
+        );
+    }
+    assert_eq!(ESCAPE_TABLE[usize::from(b'e')], Some('\x1B'));
+    assert_eq!(ESCAPE_TABLE[usize::from(b'x')], None);
+}
+
+#[test]
+fn test_control() {
+    for (string, control) in [
+        ("A", Some('\x01')),
+        ("a", Some('\x01')),
+        ("Z", Some('\x1A')),
+        ("z", Some('\x1A')),
+        ("@", Some('\0')),
+        ("[", Some('\x1B')),
+        ("_", Some('\x1F')),
+        ("?", Some('\x7F')),
+        ("1", None),
+        ("é", None),
+        ("", None),
+    ].iter() {
+        assert_eq!(unescape_control(&mut string.char_indices()), *control, "{:?} gave the wrong control character", string);
+    }
+    // Only one character is consumed
+    let mut iter = "ABC".char_indices();
+    unescape_control(&mut iter);
+    assert_eq!(iter.as_str(), "BC");
+
+    let unescaper = Unescaper::new().control_escapes(true);
+    assert_eq!(unescaper.unescape(r"\cG\cz\c[\n").expect("control escapes should be valid"), "\x07\x1A\x1B\n");
+    for (string, kind, len) in [
+        (r"ab\c", EscapeErrorKind::Truncated, 2),
+        (r"ab\c1", EscapeErrorKind::UnknownEscape('1'), 3),
+        (r"ab\cé", EscapeErrorKind::UnknownEscape('é'), 4),
+    ].iter() {
+        assert_eq!(
+            unescaper.unescape(string),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+    r"\cA".to_unescaped().expect_err("control escapes should be opt-in");
+}
+
+#[test]
+fn test_unknown_policy() {
+    static SOURCE: &str = r"\d+\s*\n\❤️\";
+    let keep = Unescaper::new().unknown_escape(UnknownEscapePolicy::KeepBackslash);
+    assert_eq!(
+        keep.unescape(&SOURCE[.. SOURCE.len() - 1]).expect("unknown escapes should be kept"),
+        Cow::<'static, str>::Owned(String::from("\\d+\\s*\n\\❤️")),
+        "unknown escapes weren't kept exactly"
+    );
+    let drop = Unescaper::new().unknown_escape(UnknownEscapePolicy::DropBackslash);
+    assert_eq!(
+        drop.unescape(&SOURCE[.. SOURCE.len() - 1]).expect("unknown escapes should be dropped"),
+        Cow::<'static, str>::Owned(String::from("d+s*\n❤️")),
+        "unknown escapes weren't dropped correctly"
+    );
+    assert_eq!(
+        Unescaper::new().unknown_escape(UnknownEscapePolicy::Error).unescape(r"ab\d"),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('d')).with_len(2))
+    );
+
+    // Only unrecognized escapes are affected, not invalid ones
+    for unescaper in [keep, drop].iter() {
+        assert_eq!(
+            unescaper.unescape(r"ab\xGG").unwrap_err().kind,
+            EscapeErrorKind::InvalidHexDigit
+        );
+        assert_eq!(
+            unescaper.unescape(SOURCE).unwrap_err().kind,
+            EscapeErrorKind::Truncated
+        );
+    }
+
+    // Escapes that are turned off count as unrecognized
+    assert_eq!(keep.allow_octal(false).unescape(r"\101\t").as_deref(), Ok("\\101\t"));
+    // The kept escape character is the configured one
+    assert_eq!(keep.escape_char('%').unescape(r"%d%%\d").as_deref(), Ok(r"%d%\d"));
+}
+
+#[test]
+fn test_trailing_policy() {
+    let literal = Unescaper::new().trailing_backslash(TrailingPolicy::Literal);
+    for (escaped, unescaped) in [
+        ("abc\\", "abc\\"),
+        ("abc\\\\", "abc\\"),
+        ("abc\\\\\\", "abc\\\\"),
+        ("a\\tb\\", "a\tb\\"),
+        ("\\", "\\"),
+    ].iter() {
+        assert_eq!(literal.unescape(escaped).as_deref(), Ok(*unescaped), "{:?} gave incorrect result", escaped);
+        assert_eq!(escaped.to_unescaped_exact_with(literal).as_deref(), Ok(*unescaped), "{:?} gave incorrect result", escaped);
+        let mut out = String::new();
+        escaped.unescape_into_with(&mut out, literal).expect("trailing escapes should be kept");
+        assert_eq!(out, *unescaped, "{:?} gave incorrect result", escaped);
+    }
+    assert!(matches!(literal.unescape("abc\\"), Ok(Cow::Borrowed("abc\\"))));
+    assert_eq!(literal.escape_char('%').unescape("50%%%").as_deref(), Ok("50%%"));
+
+    let error = Unescaper::new().trailing_backslash(TrailingPolicy::Error);
+    assert_eq!(error, Unescaper::new());
+    assert_eq!(error.unescape("abc\\"), Err(InvalidEscape::with_kind(3, EscapeErrorKind::Truncated).with_len(1)));
+    assert_eq!(error.unescape("abc\\\\").as_deref(), Ok("abc\\"));
+    // The handler is still called for everything else
+    assert_eq!(literal.unescape("\\q\\"), Err(InvalidEscape::with_kind(0, EscapeErrorKind::UnknownEscape('q')).with_len(2)));
+}
+
+#[test]
+fn test_hex_digits() {
+    let exact = Unescaper::new().hex_digits(HexMode::Exact(2));
+    let up_to = Unescaper::new().hex_digits(HexMode::UpTo(2));
+    let greedy = Unescaper::new().hex_digits(HexMode::Greedy);
+    assert_eq!(exact, Unescaper::new());
+
+    for (unescaper, escaped, unescaped) in [
+        (exact, r"\x41g", "Ag"),
+        (up_to, r"\x41g", "Ag"),
+        (greedy, r"\x41g", "Ag"),
+        (exact, r"\x414", "A4"),
+        (up_to, r"\x414", "A4"),
+        (greedy, r"\x414", "\u{414}"),
+        (up_to, r"\x4", "\x04"),
+        (greedy, r"\x4", "\x04"),
+        (up_to, r"\x4g", "\x04g"),
+        (greedy, r"\x00000000041", "A"),
+        (Unescaper::new().hex_digits(HexMode::Exact(4)), r"\x20AC1", "\u{20AC}1"),
+        (Unescaper::new().hex_digits(HexMode::UpTo(4)), r"\x20AC1\x7", "\u{20AC}1\x07"),
+    ].iter() {
+        assert_eq!(unescaper.unescape(escaped).as_deref(), Ok(*unescaped), "{:?} gave incorrect result with {:?}", escaped, unescaper);
+    }
+
+    for (unescaper, string, kind, len) in [
+        (exact, r"ab\x4", EscapeErrorKind::Truncated, 3),
+        (exact, r"ab\x4g", EscapeErrorKind::InvalidHexDigit, 4),
+        (up_to, r"ab\x", EscapeErrorKind::Truncated, 2),
+        (greedy, r"ab\x", EscapeErrorKind::Truncated, 2),
+        (up_to, r"ab\xg", EscapeErrorKind::InvalidHexDigit, 2),
+        (greedy, r"ab\xg", EscapeErrorKind::InvalidHexDigit, 2),
+        (greedy, r"ab\xD800", EscapeErrorKind::LoneSurrogate(0xD800), 6),
+        (greedy, r"ab\x110000", EscapeErrorKind::InvalidCodepoint(0x0011_0000), 8),
+        (greedy, r"ab\xFFFFFFFFFF", EscapeErrorKind::InvalidCodepoint(u32::MAX), 12),
+        (greedy.strict_x_ascii(true), r"ab\x80", EscapeErrorKind::OutOfRange(0x80), 4),
+    ].iter() {
+        assert_eq!(
+            unescaper.unescape(string),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error with {:?}", string, unescaper
+        );
+    }
+}
+
+#[test]
+fn test_owned_strings() {
+    fn borrowed(string: &String) -> Cow<'_, str> {
+        string.to_unescaped().expect("should not reject legal escaped string")
+    }
+
+    let owned = String::from(r"a\tb");
+    assert_eq!(owned.to_unescaped().as_deref(), Ok("a\tb"));
+    assert_eq!(borrowed(&owned), "a\tb");
+    let plain = String::from("No escapes here!");
+    assert!(matches!(borrowed(&plain), Cow::Borrowed("No escapes here!")), "String allocated without escapes");
+
+    let cow: Cow<'_, str> = Cow::Owned(String::from(r"\x41\q"));
+    assert_eq!(cow.to_unescaped_lossy(), "A\u{FFFD}");
+    assert_eq!(cow.validate_escapes().unwrap_err().index, 4);
+    let cow: Cow<'_, str> = Cow::Borrowed(r"\u{1F600}");
+    assert_eq!(cow.unescape_chars().collect::<Result<String, _>>().as_deref(), Ok("\u{1F600}"));
+    assert_eq!(cow.to_unescaped_with(Unescaper::new()).as_deref(), Ok("\u{1F600}"));
+
+    // Generic code can take any of them
+    fn unescape_all<T: UnescapeExt + ?Sized>(strings: &[&T]) -> Vec<String> {
+        strings.iter().map(|string| string.to_unescaped().unwrap().into_owned()).collect()
+    }
+    assert_eq!(unescape_all::<String>(&[&owned, &plain]), ["a\tb", "No escapes here!"]);
+}
+
+#[test]
+fn test_free_functions() {
+    assert_eq!(descape::unescape(r"\x41\n"), r"\x41\n".to_unescaped());
+    assert_eq!(descape::unescape(r"ab\q"), r"ab\q".to_unescaped());
+    assert!(matches!(descape::unescape("plain"), Ok(Cow::Borrowed("plain"))));
+    assert_eq!(descape::unescape_with(r"E\Tags", custom_esc).as_deref(), Ok("Eggs"));
+    assert_eq!(
+        [r"\tx", r"\q"].iter().copied().map(descape::unescape).map(|res| res.map_err(|err| err.kind)).collect::<Vec<_>>(),
+        [Ok(Cow::Owned(String::from("\tx"))), Err(EscapeErrorKind::UnknownEscape('q'))]
+    );
+}
+
+#[test]
+fn test_escaped_string() {
+    for &(input, contents, rest) in [
+        (r#""""#, "", ""),
+        (r#""a\tb" rest"#, "a\tb", " rest"),
+        (r#""plain", 1"#, "plain", ", 1"),
+        (r#""\"quoted\"""#, "\"quoted\"", ""),
+    ].iter() {
+        assert_eq!(
+            descape::escaped_string(input, '"', DefaultHandler),
+            Ok((Cow::Borrowed(contents), rest)),
+            "{:?} gave incorrect result", input
+        );
+    }
+    assert_eq!(
+        descape::escaped_string(r"»a\u{E9}»b", '»', DefaultHandler),
+        Ok((Cow::Owned(String::from("a\u{E9}")), "b"))
+    );
+
+    // Errors point into the whole input, counting the opening quote
+    for &(input, err) in [
+        ("", InvalidEscape::with_kind(0, EscapeErrorKind::MissingQuote)),
+        ("x\"\"", InvalidEscape::with_kind(0, EscapeErrorKind::MissingQuote)),
+        (r#""ab"#, InvalidEscape::with_kind(3, EscapeErrorKind::Unterminated)),
+        (r#""ab\q""#, InvalidEscape::with_kind(3, EscapeErrorKind::UnknownEscape('q')).with_len(2)),
+        (r"»\q»", InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('q')).with_len(2)),
+    ].iter() {
+        let quote = input.chars().next().filter(|&chr| chr == '»').unwrap_or('"');
+        assert_eq!(descape::escaped_string(input, quote, DefaultHandler), Err(err), "{:?} gave the wrong error", input);
+    }
+}
+
+#[test]
+fn test_exact() {
+    for string in [
+        r"\a \t❤️\u{1F600}\x41\101😀 \\",
+        r"Shrinks a lot: \x41\x41\x41\x41\x41\x41\x41\x41",
+        r"\u{1F600}",
+        r"trailing text after \n escapes",
+    ].iter() {
+        let exact = string.to_unescaped_exact().expect("should not reject legal escaped string");
+        assert_eq!(exact, string.to_unescaped().unwrap(), "exact unescaping gave a different result for {:?}", string);
+        let exact = exact.into_owned();
+        assert_eq!(exact.capacity(), exact.len(), "{:?} wasn't allocated exactly", string);
+    }
+    assert!(matches!("plain".to_unescaped_exact(), Ok(Cow::Borrowed("plain"))));
+    assert_eq!(r"ab\q".to_unescaped_exact(), r"ab\q".to_unescaped());
+    assert_eq!(r"ab\".to_unescaped_exact(), r"ab\".to_unescaped());
+
+    // Expansions from custom handlers are accounted for too
+    let expanded = r"\tcaf\N\n\wword".to_unescaped_exact_with(Expand).unwrap().into_owned();
+    assert_eq!(expanded, "    cafe\u{301}\nword");
+    assert_eq!(expanded.capacity(), expanded.len());
+}
+
+#[test]
+fn test_octal_modes() {
+    static SOURCE: &str = r"\0\101\377\400\777\7777";
+    for (mode, unescaped) in [
+        (OctalMode::Codepoint, "\0A\u{FF}\u{100}\u{1FF}\u{1FF}7"),
+        (OctalMode::Wrap, "\0A\u{FF}\0\u{FF}\u{FF}7"),
+    ].iter() {
+        assert_eq!(
+            Unescaper::new().octal_mode(*mode).unescape(SOURCE).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result", mode
+        );
+    }
+    assert_eq!(SOURCE.to_unescaped(), Unescaper::new().octal_mode(OctalMode::default()).unescape(SOURCE));
+
+    let strict = Unescaper::new().octal_mode(OctalMode::Strict);
+    assert_eq!(strict.unescape(r"\0\101\377").as_deref(), Ok("\0A\u{FF}"));
+    assert_eq!(
+        strict.unescape(r"ab\400"),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::OutOfRange(0o400)).with_len(4))
+    );
+    assert_eq!(
+        strict.unescape(r"\n\7777"),
+        Err(InvalidEscape::with_kind(2, EscapeErrorKind::OutOfRange(0o777)).with_len(4))
+    );
+}
+
+#[test]
+fn test_braced_hex() {
+    let braced = Unescaper::new().braced_x(true);
+    assert_eq!(
+        braced.unescape(r"\x{1F600}\x{41}\x{0}\x41\xFF").as_deref(),
+        Ok("\u{1F600}A\0A\u{FF}")
+    );
+    assert_eq!(braced.strict_x_ascii(true).unescape(r"\x{FF}").as_deref(), Ok("\u{FF}"));
+    for (string, kind, len) in [
+        (r"ab\x{}", EscapeErrorKind::InvalidHexDigit, 4),
+        (r"ab\x{41", EscapeErrorKind::Truncated, 5),
+        (r"ab\x{", EscapeErrorKind::Truncated, 3),
+        (r"ab\x{G}", EscapeErrorKind::InvalidHexDigit, 5),
+        (r"ab\x{110000}", EscapeErrorKind::InvalidCodepoint(0x11_0000), 10),
+        (r"ab\x{D800}", EscapeErrorKind::LoneSurrogate(0xD800), 8),
+    ].iter() {
+        assert_eq!(
+            braced.unescape(string),
+            Err(InvalidEscape::with_kind(2, *kind).with_len(*len)),
+            "{:?} gave the wrong error", string
+        );
+    }
+    r"\x{41}".to_unescaped().expect_err(r"\x{...} should be opt-in");
+}
+
+#[test]
+fn test_error_chr() {
+    let err = r"ab\q".to_unescaped().unwrap_err();
+    assert_eq!(err.chr, Some('q'));
+
+    let err = r"ab\".to_unescaped().unwrap_err();
+    assert_eq!(err.chr, None);
+    assert_eq!(err.to_string(), "invalid escape sequence at index 2: string ended in the middle of the escape");
+
+    let err = r"\u{D800}".to_unescaped().unwrap_err();
+    assert_eq!(err.chr, None);
+    assert_eq!(InvalidEscape::new(0).with_chr(Some('x')).chr, Some('x'));
+}
+
+#[test]
+fn test_line_col() {
+    for (source, line_col) in [
+        (r"\q", (1, 1)),
+        (r"abc\q", (1, 4)),
+        ("a\nb\n\\q", (3, 1)),
+        ("\u{1F600}\u{E9}\n\u{E9}\u{1F600}x\\q", (2, 4)),
+        ("a\r\nb\\q", (2, 2)),
+    ].iter() {
+        let err = source.to_unescaped().unwrap_err();
+        assert_eq!(err.line_col(source), *line_col, "{:?} gave the wrong position", source);
+    }
+    // Indices past the end are clamped
+    assert_eq!(InvalidEscape::new(100).line_col("ab\ncd"), (2, 3));
+}
+
+#[test]
+fn test_handler_positions() {
+    // Records where each sequence starts, and where the iterator says the character after it is
+    fn record(seen: &mut Vec<(usize, Option<usize>)>) -> impl FnMut(usize, char, &mut CharIndices) -> Result<Option<char>, ()> + '_ {
+        move |idx, chr, iter| {
+            seen.push((idx, iter.clone().next().map(|(next, _)| next)));
+            Ok(Some(chr))
+        }
+    }
+    let source = r"é\xab\u{1F600}\é|";
+    let expected = [(2, Some(4)), (6, Some(8)), (15, Some(18))];
+
+    let mut seen = Vec::new();
+    source.to_unescaped_with(record(&mut seen)).unwrap();
+    assert_eq!(seen, expected);
+
+    let mut seen = Vec::new();
+    source.unescape_chars_with(record(&mut seen)).for_each(drop);
+    assert_eq!(seen, expected);
+
+    let mut seen = Vec::new();
+    source.unescape_until('|', record(&mut seen)).unwrap();
+    assert_eq!(seen, expected);
+}
+
+#[test]
+fn test_to_unescaped_string() {
+    assert_eq!(r"a\tb".to_unescaped_string(), Ok(String::from("a\tb")));
+    assert_eq!("plain".to_unescaped_string(), Ok(String::from("plain")));
+    assert_eq!(Cow::Borrowed(r"\x41").to_unescaped_string(), Ok(String::from("A")));
+    assert_eq!(r"\q".to_unescaped_string(), Err(InvalidEscape::with_kind(0, EscapeErrorKind::UnknownEscape('q')).with_len(2)));
+}
+
+#[test]
+fn test_to_unescaped_range() {
+    static SOURCE: &str = "caf\u{E9} \\x41\\t \\q";
+    for (range, unescaped) in [
+        (0..5, "caf\u{E9}"),
+        (6..10, "A"),
+        (6..12, "A\t"),
+        (0..0, ""),
+        (14..14, ""),
+    ].iter() {
+        assert_eq!(SOURCE.to_unescaped_range(range.clone()).as_deref(), Ok(*unescaped), "{:?} gave incorrect result", range);
+    }
+    assert!(matches!(SOURCE.to_unescaped_range(0..5), Ok(Cow::Borrowed("caf\u{E9}"))));
+
+    for (range, err) in [
+        (6..15, InvalidEscape::with_kind(13, EscapeErrorKind::UnknownEscape('q')).with_len(2)),
+        (6..9, InvalidEscape::with_kind(6, EscapeErrorKind::Truncated).with_len(3)),
+        (11..14, InvalidEscape::with_kind(13, EscapeErrorKind::Truncated).with_len(1)),
+        (4..6, InvalidEscape::with_kind(4, EscapeErrorKind::InvalidRange)),
+        (0..4, InvalidEscape::with_kind(4, EscapeErrorKind::InvalidRange)),
+        (6..16, InvalidEscape::with_kind(16, EscapeErrorKind::InvalidRange)),
+        (20..30, InvalidEscape::with_kind(20, EscapeErrorKind::InvalidRange)),
+        (std::ops::Range { start: 10, end: 6 }, InvalidEscape::with_kind(6, EscapeErrorKind::InvalidRange)),
+    ].iter() {
+        assert_eq!(SOURCE.to_unescaped_range(range.clone()), Err(*err), "{:?} gave the wrong error", range);
+    }
+}
+
+#[test]
+fn test_to_unescaped_n() {
+    for (escaped, times, unescaped) in [
+        (r"\\\\n", 0, r"\\\\n"),
+        (r"\\\\n", 1, r"\\n"),
+        (r"\\\\n", 2, r"\n"),
+        (r"\\\\n", 3, "\n"),
+        (r"\\\\n", 10, "\n"),
+        (r"\x5Cx41", 2, "A"),
+    ].iter() {
+        assert_eq!(
+            escaped.to_unescaped_n(*times, DefaultHandler).as_deref(),
+            Ok(*unescaped),
+            "{:?} gave incorrect result {} times", escaped, times
+        );
+    }
+
+    assert!(matches!("plain".to_unescaped_n(3, DefaultHandler), Ok(Cow::Borrowed("plain"))));
+    assert!(matches!(r"\d".to_unescaped_n(3, Unescaper::new().unknown_escape(UnknownEscapePolicy::KeepBackslash)), Ok(Cow::Borrowed(r"\d"))));
+    // The index is into what the failing pass was given
+    assert_eq!(
+        r"abc\\\\x\\4".to_unescaped_n(3, DefaultHandler),
+        Err(InvalidEscape::with_kind(3, EscapeErrorKind::InvalidHexDigit).with_len(3))
+    );
+    assert_eq!(
+        String::from(r"\q").to_unescaped_n(1, DefaultHandler),
+        Err(InvalidEscape::with_kind(0, EscapeErrorKind::UnknownEscape('q')).with_len(2))
+    );
+}
+
+#[test]
+fn test_unescape_wrapped() -> Result<(), InvalidEscape> {
+    let unescaped = r"a\tb".unescape_wrapped()?;
+    assert_eq!(unescaped, "a\tb");
+    assert_eq!("a\tb", unescaped);
+    assert_eq!(unescaped, String::from("a\tb"));
+    assert_eq!(*unescaped, *"a\tb");
+    assert_eq!(format!("[{}]", unescaped), "[a\tb]");
+    assert!(!unescaped.is_borrowed());
+    assert_eq!(unescaped.into_owned_string(), "a\tb");
+
+    let borrowed = "plain".unescape_wrapped()?;
+    assert!(borrowed.is_borrowed());
+    assert_eq!(borrowed.into_inner(), Cow::Borrowed("plain"));
+
+    assert_eq!(String::from(r"\u{1F600}").unescape_wrapped()?.chars().count(), 1);
+    assert_eq!(r"\q".unescape_wrapped(), Err(InvalidEscape::with_kind(0, EscapeErrorKind::UnknownEscape('q')).with_len(2)));
+    Ok(())
+}
+
+#[test]
+fn test_try_from_wrappers() -> Result<(), InvalidEscape> {
+    let unescaped = Unescaped::try_from(r"a\tb")?;
+    assert_eq!(unescaped, "a\tb");
+    assert!(!unescaped.is_borrowed());
+    assert!(Unescaped::try_from("plain")?.is_borrowed());
+
+    let owned = UnescapedString::try_from(r"a\tb")?;
+    assert_eq!(owned, "a\tb");
+    assert_eq!("a\tb", owned);
+    assert_eq!(owned, String::from("a\tb"));
+    assert_eq!(format!("[{}]", owned), "[a\tb]");
+    assert_eq!(owned.len(), 3);
+    assert_eq!(UnescapedString::from(unescaped), owned);
+    assert_eq!(String::from(owned), "a\tb");
+
+    let all: Vec<UnescapedString> = [r"\x41", "b", r"\u{1F600}"].iter().copied().map(UnescapedString::try_from).collect::<Result<_, _>>()?;
+    assert_eq!(all, ["A", "b", "\u{1F600}"]);
+
+    let err = InvalidEscape::with_kind(0, EscapeErrorKind::UnknownEscape('q')).with_len(2);
+    assert_eq!(Unescaped::try_from(r"\q"), Err(err));
+    assert_eq!(UnescapedString::try_from(r"\q"), Err(err));
+    assert_eq!([r"ok", r"\q"].iter().copied().map(UnescapedString::try_from).collect::<Result<Vec<_>, _>>(), Err(err));
+    Ok(())
+}
+
+#[test]
+fn test_unescape_until() {
+    for (source, delim, contents, rest) in [
+        (r#"abc" rest"#, '"', "abc", " rest"),
+        (r#"a\"b\\" rest"#, '"', "a\"b\\", " rest"),
+        (r#""""#, '"', "", "\""),
+        (r"it\'s' + 'more'", '\'', "it's", " + 'more'"),
+        (r"é\u{1F600}☺☺", '☺', "é\u{1F600}", "☺"),
+        (r"no escapes|", '|', "no escapes", ""),
+        (r"a\b", '\\', "a", "b"),
+    ].iter() {
+        let (unescaped, remainder) = source.unescape_until(*delim, DefaultHandler)
+            .unwrap_or_else(|err| panic!("{:?} failed with {}", source, err));
+        assert_eq!((&*unescaped, remainder), (*contents, *rest), "{:?} gave incorrect result", source);
+        // Only allocate if there were escapes before the delimiter
+        assert_eq!(matches!(unescaped, Cow::Borrowed(_)), source.starts_with(contents));
+    }
+
+    for (source, err) in [
+        (r"abc", InvalidEscape::with_kind(3, EscapeErrorKind::Unterminated)),
+        (r#"abc\""#, InvalidEscape::with_kind(5, EscapeErrorKind::Unterminated)),
+        (r"abc\", InvalidEscape::with_kind(3, EscapeErrorKind::Truncated).with_len(1)),
+        (r#"ab\q""#, InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('q')).with_len(2)),
+        // Handlers don't know about the delimiter, so they can read past it
+        (r#"ab\u{41" rest"#, InvalidEscape::with_kind(2, EscapeErrorKind::Truncated).with_len(11)),
+    ].iter() {
+        assert_eq!(source.unescape_until('"', DefaultHandler), Err(*err), "{:?} gave the wrong error", source);
+    }
+}
+
+#[test]
+fn test_unescape_until_consumed() {
+    for (source, delim, contents, consumed) in [
+        (r#"abc" rest"#, '"', "abc", 4),
+        (r#"é\u{E9}☺" rest"#, '"', "éé☺", 12),
+        (r"\x41\101é\n😀😀", '😀', "AA\u{E9}\n", 16),
+        (r"\\\''", '\'', "\\'", 5),
+    ].iter() {
+        let (unescaped, len) = source.unescape_until_consumed(*delim, DefaultHandler)
+            .unwrap_or_else(|err| panic!("{:?} failed with {}", source, err));
+        assert_eq!((&*unescaped, len), (*contents, *consumed), "{:?} gave incorrect result", source);
+        // This should always agree with the remainder
+        let (_, rest) = source.unescape_until(*delim, DefaultHandler).expect("this already succeeded");
+        assert_eq!(&source[len ..], rest);
+    }
+    assert_eq!(
+        "é\\u{E9}".unescape_until_consumed('"', DefaultHandler),
+        Err(InvalidEscape::with_kind(8, EscapeErrorKind::Unterminated))
+    );
+}
+
+#[test]
+fn test_borrow_unchanged() {
+    let unescaper = Unescaper::new().unknown_escape(UnknownEscapePolicy::KeepBackslash);
+    for escaped in [r"\d", r"a\d+\w*", r"\.\{\}"].iter() {
+        assert!(
+            matches!(escaped.to_unescaped_with(unescaper), Ok(Cow::Borrowed(s)) if s == *escaped),
+            "{:?} should have been borrowed", escaped
+        );
+    }
+    for escaped in [r"\q\n", r"\n\q", r"\d\d\tb"].iter() {
+        assert_eq!(
+            escaped.to_unescaped_with(unescaper),
+            Ok(Cow::<'static, str>::Owned(escaped.replace(r"\n", "\n").replace(r"\t", "\t"))),
+            "{:?} gave incorrect result", escaped
+        );
+    }
+    assert!(matches!(r"\q \xJJ".to_unescaped_lossy_with(None, DefaultHandler), Cow::Borrowed(r"\q \xJJ")));
+
+    // Everything kept as written before the first change still ends up in the output
+    assert_eq!(r"a\d b\w\n c".to_unescaped_with(unescaper), Ok(Cow::<'static, str>::Owned(String::from("a\\d b\\w\n c"))));
+    assert_eq!(r"\q \xJJ\n".to_unescaped_lossy_with(None, DefaultHandler), "\\q \\xJJ\n");
+    assert_eq!(r"\q\q\".to_unescaped_lossy_with(Some('?'), DefaultHandler), "???");
+}
+
+#[test]
+fn test_unescape_into() {
+    let mut out = String::from("kept: ");
+    r"\x41\u{1F600}".unescape_into(&mut out).expect("this is valid");
+    assert_eq!(out, "kept: A\u{1F600}");
+
+    // The buffer is reused without reallocating
+    out.clear();
+    out.reserve(64);
+    let capacity = out.capacity();
+    for (source, unescaped) in [(r"a\tb", "a\tb"), ("no escapes", "no escapes"), (r"\\\101", "\\A")].iter() {
+        out.clear();
+        source.unescape_into(&mut out).unwrap_or_else(|err| panic!("{:?} failed with {}", source, err));
+        assert_eq!(out, *unescaped);
+    }
+    assert_eq!(out.capacity(), capacity);
+
+    out.clear();
+    assert_eq!(
+        r"ab\n\u{D800}cd".unescape_into(&mut out),
+        Err(InvalidEscape::with_kind(4, EscapeErrorKind::LoneSurrogate(0xD800)).with_len(8))
+    );
+    assert_eq!(out, "ab\n");
+
+    out.clear();
+    String::from(r"\qux").unescape_into_with(&mut out, Unescaper::new().unknown_escape(UnknownEscapePolicy::DropBackslash))
+        .expect("unknown escapes are dropped");
+    assert_eq!(out, "qux");
+}
+
+#[test]
+fn test_unescape_to_fmt() {
+    use std::fmt::{self, Write};
+
+    /// Holds at most `N` bytes, like a fixed-size buffer on an embedded target.
+    struct Fixed<const N: usize> {
+        buf: [u8; N],
+        len: usize,
+    }
+
+    impl<const N: usize> Write for Fixed<N> {
+        fn write_str(&mut self, string: &str) -> fmt::Result {
+            let end = self.len + string.len();
+            self.buf.get_mut(self.len .. end).ok_or(fmt::Error)?.copy_from_slice(string.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    impl<const N: usize> Fixed<N> {
+        fn as_str(&self) -> &str {
+            std::str::from_utf8(&self.buf[.. self.len]).expect("only strings are written")
+        }
+    }
+
+    let mut out = Fixed { buf: [0; 16], len: 0 };
+    r"\x41\n\u{1F600}".unescape_to_fmt(&mut out, DefaultHandler).expect("this fits");
+    assert_eq!(out.as_str(), "A\n\u{1F600}");
+
+    let mut out = Fixed { buf: [0; 4], len: 0 };
+    assert_eq!(
+        r"too \x6Cong".unescape_to_fmt(&mut out, DefaultHandler),
+        Err(UnescapeFmtError::Write(fmt::Error))
+    );
+
+    let mut out = String::new();
+    assert_eq!(
+        r"ab\q".unescape_to_fmt(&mut out, DefaultHandler),
+        Err(UnescapeFmtError::Invalid(InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('q')).with_len(2)))
+    );
+    assert_eq!(out, "ab");
+
+    // Removed sequences write nothing
+    let mut out = String::new();
+    String::from(r"a\zb").unescape_to_fmt(&mut out, |_: usize, _: char, _: &mut CharIndices| Ok(None))
+        .expect("every escape is removed");
+    assert_eq!(out, "ab");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_io_error() {
+    fn unescape_io(string: &str) -> std::io::Result<Cow<'_, str>> {
+        Ok(string.to_unescaped()?)
+    }
+
+    assert_eq!(unescape_io(r"\x41").expect("this is valid"), "A");
+    let err = unescape_io(r"ab\q").expect_err("this is invalid");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    assert_eq!(err.to_string(), "invalid escape sequence `\\q` at index 2: unknown escape character 'q'");
+    let inner = err.into_inner().expect("the original error should be kept");
+    assert_eq!(
+        inner.downcast_ref::<InvalidEscape>(),
+        Some(&InvalidEscape::with_kind(2, EscapeErrorKind::UnknownEscape('q')).with_len(2))
+    );
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_unescape_reader() {
+    use std::io::Read;
+
+    /// Reads at most `first` bytes the first time, and at most `rest` bytes after that.
+    /// Both must be above zero, since reading nothing means the reader is done.
+    struct Chunked<'a> {
+        data: &'a [u8],
+        first: Option<usize>,
+        rest: usize,
+    }
+
+    impl Read for Chunked<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            let len = self.first.take().unwrap_or(self.rest).min(buf.len()).min(self.data.len());
+            buf[.. len].copy_from_slice(&self.data[.. len]);
+            self.data = &self.data[len ..];
+            Ok(len)
+        }
+    }
+
+    fn unescape_chunked(data: &str, first: usize, rest: usize) -> std::io::Result<String> {
+        let mut out = Vec::new();
+        let reader = Chunked { data: data.as_bytes(), first: Some(first), rest };
+        descape::unescape_reader(reader, &mut out, DefaultHandler)?;
+        Ok(String::from_utf8(out).expect("the output should be valid UTF-8"))
+    }
+
+    let cases = [
+        r"\u{1F600}",
+        r"smile: \u{1F600}!",
+        r"padding to get past the lookahead \u{000000000001F600} and more",
+        r"\101\x42C é😀 \\",
+        "no escapes at all",
+    ];
+    for case in cases.iter() {
+        let expected = case.to_unescaped().expect("the test cases are valid");
+        for split in 1 ..= case.len() {
+            assert_eq!(unescape_chunked(case, split, case.len()).unwrap(), expected, "{:?} split at {}", case, split);
+            assert_eq!(unescape_chunked(case, split, 1).unwrap(), expected, "{:?} split at {} then in bytes", case, split);
+        }
+    }
+
+    let source = r"a long enough string to need a few reads, \q";
+    let err = unescape_chunked(source, 7, 5).expect_err("\\q is invalid");
+    let inner = err.into_inner().expect("the original error should be kept");
+    assert_eq!(
+        inner.downcast_ref::<InvalidEscape>(),
+        Some(&InvalidEscape::with_kind(source.len() - 2, EscapeErrorKind::UnknownEscape('q')).with_len(2))
+    );
+
+    let mut out = Vec::new();
+    descape::unescape_reader(&b"ok \xFF"[..], &mut out, DefaultHandler).expect_err("this isn't UTF-8");
+
+    // The index given to the handler lines up with its iterator's, however the stream is split up
+    let source = r"a long enough string to need a few reads, \ab then \cd";
+    for &(first, rest) in [(7, 5), (50, 1), (source.len(), source.len())].iter() {
+        let mut mismatched = Vec::new();
+        let handler = |idx: usize, chr: char, iter: &mut CharIndices| -> Result<Option<char>, ()> {
+            if iter.next().map(|(next, _)| next) != Some(idx + 2) {
+                mismatched.push(idx);
+            }
+            Ok(Some(chr))
+        };
+        let mut out = Vec::new();
+        let reader = Chunked { data: source.as_bytes(), first: Some(first), rest };
+        descape::unescape_reader(reader, &mut out, handler).expect("every escape is allowed");
+        assert_eq!(out, b"a long enough string to need a few reads, a then c");
+        assert!(mismatched.is_empty(), "read as {} then {} had mismatched indices at {:?}", first, rest, mismatched);
+    }
+
+    // A trailing backslash is only known to be trailing once the reader runs out
+    let literal = Unescaper::new().trailing_backslash(TrailingPolicy::Literal);
+    for &(first, rest) in [(1, 1), (3, 2), (4, 4)].iter() {
+        let mut out = Vec::new();
+        let reader = Chunked { data: br"a\tb\", first: Some(first), rest };
+        descape::unescape_reader(reader, &mut out, literal).expect("the backslash should be kept");
+        assert_eq!(out, b"a\tb\\", "read as {} then {} gave incorrect result", first, rest);
+    }
+    let err = unescape_chunked(r"a\tb\", 2, 2).expect_err("the default handler rejects a trailing backslash");
+    assert_eq!(
+        err.into_inner().expect("the original error should be kept").downcast_ref::<InvalidEscape>(),
+        Some(&InvalidEscape::with_kind(4, EscapeErrorKind::Truncated).with_len(1))
+    );
+
+    // The limit on the output counts everything written so far, across every read
+    let limited = Unescaper::new().max_output_len(6);
+    for &(first, rest) in [(1, 1), (3, 2), (9, 9)].iter() {
+        let mut out = Vec::new();
+        let reader = Chunked { data: br"\tabcdefg", first: Some(first), rest };
+        let err = descape::unescape_reader(reader, &mut out, limited).expect_err("this is too long");
+        assert_eq!(
+            err.into_inner().expect("the original error should be kept").downcast_ref::<InvalidEscape>(),
+            Some(&InvalidEscape::with_kind(7, EscapeErrorKind::OutputTooLong).with_len(1)),
+            "read as {} then {} gave the wrong error", first, rest
+        );
+    }
+}